@@ -0,0 +1,160 @@
+use std::{collections::HashMap, fmt::Display, fs, io::ErrorKind};
+
+use aoc2024::solution::Part;
+
+use crate::toml;
+
+pub const ANSWERS_FILE: &str = "./answers.toml";
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Ok,
+    Wrong(String),
+    Unknown,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Ok => write!(f, "OK"),
+            Verdict::Wrong(expected) => write!(f, "WRONG, expected {}", expected),
+            Verdict::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+/// Recorded answers for the real puzzle inputs, keyed by day and part.
+///
+/// The file is a small TOML subset: one `[day_XX]` table per day holding `part1` and `part2`,
+/// each either an integer or a quoted string.
+#[derive(Debug, Default)]
+pub struct Answers {
+    known: HashMap<(u8, Part), String>,
+}
+
+impl Answers {
+    /// Loads the answers file, treating a missing file as "nothing recorded yet".
+    pub fn load(path: &str) -> Result<Answers, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => Answers::parse(&content).map_err(|err| format!("{}: {}", path, err)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Answers::default()),
+            Err(err) => Err(format!("{}: {}", path, err)),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Answers, String> {
+        let mut known = HashMap::new();
+        for table in toml::parse(content)? {
+            let day = table
+                .name
+                .strip_prefix("day_")
+                .and_then(|num| num.parse::<u8>().ok())
+                .ok_or(format!("line {}: expected a [day_XX] table, got [{}]", table.line, table.name))?;
+            for (key, value) in &table.entries {
+                let part = match key.as_str() {
+                    "part1" => Part::One,
+                    "part2" => Part::Two,
+                    other => return Err(format!("[{}]: expected part1 or part2, got {}", table.name, other)),
+                };
+                known.insert((day, part), value.as_text());
+            }
+        }
+        Ok(Answers { known })
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.known.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn check(&self, day: u8, part: Part, answer: &str) -> Verdict {
+        match self.get(day, part) {
+            Some(expected) if expected == answer => Verdict::Ok,
+            Some(expected) => Verdict::Wrong(String::from(expected)),
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+/// Adds `answer` to the `[day_XX]` table of the answers file `content`, creating the table
+/// before the ones of later days if there is none yet.
+pub fn with_answer(content: &str, day: u8, part: Part, answer: &str) -> String {
+    let header = format!("[day_{:02}]", day);
+    let value = if answer.parse::<i64>().is_ok_and(|number| number.to_string() == answer) {
+        String::from(answer)
+    } else {
+        format!("\"{}\"", answer)
+    };
+    let entry = format!("part{} = {}", part, value);
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let table_day = |line: &String| line.trim().strip_prefix("[day_")?.strip_suffix(']')?.parse::<u8>().ok();
+    if let Some(start) = lines.iter().position(|line| line.trim() == header) {
+        let end = lines[start + 1..]
+            .iter()
+            .position(|line| line.trim_start().starts_with('['))
+            .map_or(lines.len(), |offset| start + 1 + offset);
+        let last_entry = (start + 1..end).rev().find(|&index| !lines[index].trim().is_empty()).unwrap_or(start);
+        lines.insert(last_entry + 1, entry);
+    } else if let Some(next) = lines.iter().position(|line| table_day(line).is_some_and(|other| other > day)) {
+        lines.splice(next..next, [header, entry, String::new()]);
+    } else {
+        if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.extend([header, entry]);
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Adds an accepted answer to the answers file at `path`.
+pub fn record(path: &str, day: u8, part: Part, answer: &str) -> Result<(), String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(format!("{}: {}", path, err)),
+    };
+    fs::write(path, with_answer(&content, day, part, answer)).map_err(|err| format!("{}: {}", path, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = Answers::parse("# recorded\n[day_07]\npart1 = 3749 # example\n\n[day_17]\npart1 = \"4,6,3\"\n").unwrap();
+        assert_eq!(answers.check(7, Part::One, "3749"), Verdict::Ok);
+        assert_eq!(answers.check(7, Part::One, "3750"), Verdict::Wrong(String::from("3749")));
+        assert_eq!(answers.check(7, Part::Two, "3749"), Verdict::Unknown);
+        assert_eq!(answers.get(17, Part::One), Some("4,6,3"));
+    }
+
+    #[test]
+    fn test_parse_answers_errors() {
+        assert!(Answers::parse("part1 = 1").is_err());
+        assert!(Answers::parse("[day_x]").is_err());
+        assert!(Answers::parse("[day_01]\npart3 = 1").is_err());
+        assert!(Answers::parse("[day_01]\npart1 = abc").is_err());
+        assert!(Answers::parse("[day_01]\npart1").is_err());
+        assert!(Answers::parse("[day_01]\n[day_02]\npart1 = 1\n[day_x]").is_err());
+    }
+
+    #[test]
+    fn test_with_answer() {
+        let content = "# recorded\n[day_01]\npart1 = 11\n\n[day_17]\npart1 = \"4,6,3\"\n";
+        assert_eq!(with_answer(content, 1, Part::Two, "31"), "# recorded\n[day_01]\npart1 = 11\npart2 = 31\n\n[day_17]\npart1 = \"4,6,3\"\n");
+        assert_eq!(
+            with_answer(content, 5, Part::One, "143"),
+            "# recorded\n[day_01]\npart1 = 11\n\n[day_05]\npart1 = 143\n\n[day_17]\npart1 = \"4,6,3\"\n"
+        );
+        assert_eq!(with_answer(content, 18, Part::Two, "6,1"), format!("{}\n[day_18]\npart2 = \"6,1\"\n", content));
+        assert_eq!(with_answer("", 3, Part::One, "007"), "[day_03]\npart1 = \"007\"\n");
+        let answers = Answers::parse(&with_answer(content, 1, Part::Two, "31")).unwrap();
+        assert_eq!(answers.check(1, Part::Two, "31"), Verdict::Ok);
+    }
+
+    #[test]
+    fn test_recorded_answers_file_parses() {
+        let answers = Answers::load(ANSWERS_FILE).unwrap();
+        assert!(answers.get(1, Part::One).is_some());
+    }
+}
@@ -0,0 +1,275 @@
+use std::{
+    fmt::Write,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use aoc2024::{params::Params, parse::{ParseError, Source}, solution::{Entry, Part}};
+
+use crate::memory::{self, Usage};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Summarizes the samples; for an even count the upper of the two middle samples is the median.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Stats {
+        assert!(!samples.is_empty(), "Expecting at least one sample");
+        samples.sort();
+        Stats {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DayBench {
+    pub day: u8,
+    pub iterations: usize,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+    pub memory: Option<MemoryProfile>,
+}
+
+/// What parsing and each part allocate, from one more run after the timed ones so the counting
+/// doesn't slow those down.
+#[derive(Debug)]
+pub struct MemoryProfile {
+    pub parse: Usage,
+    pub part1: Usage,
+    pub part2: Usage,
+}
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = black_box(f());
+    (result, start.elapsed())
+}
+
+pub fn bench_entry(
+    entry: &Entry,
+    input_file: &str,
+    params: &Params,
+    iterations: usize,
+    profile_mem: bool,
+) -> Result<DayBench, ParseError> {
+    let mut parse = Vec::with_capacity(iterations);
+    let mut part1 = Vec::with_capacity(iterations);
+    let mut part2 = Vec::with_capacity(iterations);
+
+    let source = Source::read(input_file)?;
+    for _ in 0..iterations {
+        let (input, elapsed) = time(|| entry.solution.parse(&source, params));
+        let input = input?;
+        parse.push(elapsed);
+        part1.push(time(|| entry.solve(input.as_ref(), Part::One)).1);
+        part2.push(time(|| entry.solve(input.as_ref(), Part::Two)).1);
+    }
+
+    let memory = if profile_mem { profile_memory(entry, &source, params)? } else { None };
+
+    Ok(DayBench {
+        day: entry.day,
+        iterations,
+        parse: Stats::from_samples(parse),
+        part1: Stats::from_samples(part1),
+        part2: Stats::from_samples(part2),
+        memory,
+    })
+}
+
+#[cfg(feature = "profile-mem")]
+fn profile_memory(entry: &Entry, source: &Source, params: &Params) -> Result<Option<MemoryProfile>, ParseError> {
+    let (input, parse) = memory::measure(|| entry.solution.parse(source, params));
+    let input = input?;
+    // only the solving counts, not formatting the answer afterwards
+    let measure_part = |part| {
+        let mut usage = Usage::default();
+        entry.solution.solve_within(input.as_ref(), part, &mut |solve| usage = memory::measure(solve).1);
+        usage
+    };
+    Ok(Some(MemoryProfile { parse, part1: measure_part(Part::One), part2: measure_part(Part::Two) }))
+}
+
+/// Nothing counts allocations without the `profile-mem` feature, and `--profile-mem` is refused then.
+#[cfg(not(feature = "profile-mem"))]
+fn profile_memory(_entry: &Entry, _source: &Source, _params: &Params) -> Result<Option<MemoryProfile>, ParseError> {
+    Ok(None)
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.1}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.3}s", nanos as f64 / 1e9)
+    }
+}
+
+fn format_stats(stats: &Stats) -> String {
+    format!(
+        "{} / {} / {}",
+        format_duration(stats.min),
+        format_duration(stats.median),
+        format_duration(stats.max)
+    )
+}
+
+fn format_usage(usage: &Usage) -> String {
+    format!(
+        "{} / {} / {}",
+        memory::format_bytes(usage.peak),
+        memory::format_bytes(usage.allocated),
+        usage.allocations
+    )
+}
+
+/// A table of the timings, with the memory use of each phase in more columns if it was profiled.
+pub fn to_markdown(results: &[DayBench]) -> String {
+    let profiled = results.iter().any(|result| result.memory.is_some());
+    let mut out = String::from("| Day | Runs | Parse (min / median / max) | Part 1 (min / median / max) | Part 2 (min / median / max) |");
+    if profiled {
+        out.push_str(" Parse memory (peak / allocated / count) | Part 1 memory | Part 2 memory |");
+    }
+    out.push_str(if profiled { "\n|----:|-----:|---|---|---|---|---|---|\n" } else { "\n|----:|-----:|---|---|---|\n" });
+    for result in results {
+        write!(
+            out,
+            "| {:02} | {} | {} | {} | {} |",
+            result.day,
+            result.iterations,
+            format_stats(&result.parse),
+            format_stats(&result.part1),
+            format_stats(&result.part2)
+        )
+        .unwrap();
+        match &result.memory {
+            Some(memory) => write!(
+                out,
+                " {} | {} | {} |",
+                format_usage(&memory.parse),
+                format_usage(&memory.part1),
+                format_usage(&memory.part2)
+            )
+            .unwrap(),
+            None if profiled => out.push_str(" - | - | - |"),
+            None => {}
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn stats_json(stats: &Stats) -> String {
+    format!(
+        "{{\"min_ns\": {}, \"median_ns\": {}, \"max_ns\": {}}}",
+        stats.min.as_nanos(),
+        stats.median.as_nanos(),
+        stats.max.as_nanos()
+    )
+}
+
+fn usage_json(usage: &Usage) -> String {
+    format!(
+        "{{\"peak_bytes\": {}, \"allocated_bytes\": {}, \"allocations\": {}}}",
+        usage.peak, usage.allocated, usage.allocations
+    )
+}
+
+pub fn to_json(results: &[DayBench]) -> String {
+    let days: Vec<String> = results
+        .iter()
+        .map(|result| {
+            let memory = result.memory.as_ref().map_or(String::new(), |memory| format!(
+                ", \"memory\": {{\"parse\": {}, \"part1\": {}, \"part2\": {}}}",
+                usage_json(&memory.parse),
+                usage_json(&memory.part1),
+                usage_json(&memory.part2)
+            ));
+            format!(
+                "  {{\"day\": {}, \"iterations\": {}, \"parse\": {}, \"part1\": {}, \"part2\": {}{}}}",
+                result.day,
+                result.iterations,
+                stats_json(&result.parse),
+                stats_json(&result.part1),
+                stats_json(&result.part2),
+                memory
+            )
+        })
+        .collect();
+    format!("[\n{}\n]\n", days.join(",\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc2024::solution;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&ms| Duration::from_millis(ms)).collect()
+    }
+
+    #[test]
+    fn test_stats_from_samples() {
+        let stats = Stats::from_samples(millis(&[5, 1, 3]));
+        assert_eq!(stats, Stats {
+            min: Duration::from_millis(1),
+            median: Duration::from_millis(3),
+            max: Duration::from_millis(5)
+        });
+        assert_eq!(Stats::from_samples(millis(&[4, 2, 1, 3])).median, Duration::from_millis(3));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(999)), "999ns");
+        assert_eq!(format_duration(Duration::from_nanos(12_340)), "12.3µs");
+        assert_eq!(format_duration(Duration::from_micros(1_500)), "1.50ms");
+        assert_eq!(format_duration(Duration::from_millis(2_000)), "2.000s");
+    }
+
+    #[test]
+    fn test_bench_entry_reports() {
+        let entry = solution::find(1).unwrap();
+        let result = bench_entry(entry, &entry.example_file(), &entry.solution.params(), 3, false).unwrap();
+        assert_eq!(result.iterations, 3);
+        assert!(result.parse.min <= result.parse.median && result.parse.median <= result.parse.max);
+
+        let markdown = to_markdown(&[result]);
+        assert_eq!(markdown.lines().count(), 3);
+        assert!(markdown.lines().nth(2).unwrap().starts_with("| 01 | 3 |"));
+
+        let json = to_json(&[bench_entry(entry, &entry.example_file(), &entry.solution.params(), 1, false).unwrap()]);
+        assert!(json.starts_with("[\n  {\"day\": 1, \"iterations\": 1, \"parse\": {\"min_ns\": "));
+        assert!(!json.contains("memory"));
+    }
+
+    #[cfg(feature = "profile-mem")]
+    #[test]
+    fn test_bench_entry_profiles_memory() {
+        let entry = solution::find(1).unwrap();
+        let result = bench_entry(entry, &entry.example_file(), &entry.solution.params(), 1, true).unwrap();
+        let memory = result.memory.as_ref().unwrap();
+        // the parsed input is still held, so parsing peaks at no less than two lists of six numbers
+        assert!(memory.parse.allocations >= 2 && memory.parse.peak >= 2 * 6 * 8);
+        assert!(memory.part1.allocated >= memory.part1.peak);
+
+        let markdown = to_markdown(&[result]);
+        assert!(markdown.lines().next().unwrap().ends_with("| Parse memory (peak / allocated / count) | Part 1 memory | Part 2 memory |"));
+        assert_eq!(markdown.lines().nth(2).unwrap().split(" | ").count(), 8);
+
+        let json = to_json(&[bench_entry(entry, &entry.example_file(), &entry.solution.params(), 1, true).unwrap()]);
+        assert!(json.contains(", \"memory\": {\"parse\": {\"peak_bytes\": "));
+    }
+}
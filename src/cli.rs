@@ -0,0 +1,946 @@
+use std::{fmt::Display, time::Duration};
+
+use aoc2024::{log::{Filter, Level}, parse::STDIN, solution::Part, visualize::Settings};
+
+pub const USAGE: &str = "\
+Usage: aoc2024 <command> [options]
+
+Commands:
+  run       Solve one or all days
+  bench     Time parsing and both parts of one or all days
+  examples  Check the example inputs listed in ./input_test/examples.toml
+  new       Create the files for a new day and register its solution
+  gen       Write a random puzzle input for a day
+  fuzz      Feed mangled inputs to one or all days, solving the ones that parse, and report every panic
+  watch     Solve a day and its examples again whenever its input, examples or answers change
+  repl      Parse a day's input once, then solve, visualize and query it interactively
+  fetch     Download the puzzle input of a day
+  submit    Send an answer to the puzzle server, unless it is known to be wrong
+  serve     Run a local stand-in for the puzzle server with the inputs and answers in this repo
+
+Options for all commands:
+  -v, --verbose     Log what the solutions are doing, repeat (-vv) for even more detail
+  --log <filter>    Log levels overall and per day, e.g. info,day_05=trace
+                    (levels: error, warn, info, debug, trace)
+  --log-file <path> Write the log to <path> instead of stderr
+
+Options for run and bench:
+  --day <N>         Day to solve
+  --all             Solve every registered day
+  --input <path>    Read the puzzle input from <path>, or from stdin if <path> is -
+  --example         Read the puzzle input from ./input_test/day_XX.txt
+  --param <name=N>  Override a puzzle parameter, e.g. --param width=11 (requires --day)
+
+Options for run:
+  --part <1|2>      Only solve the given part
+  --jobs <N>        Solve the days on <N> threads and print a summary table of answers and timings
+  --visualize       Animate the day in the terminal before solving it (requires --day)
+  --fps <N>         Frames per second of the animation, 0 for no delay (default 20)
+  --viewport <WxH>  Largest part of the map shown at once (default 100x50)
+  --export <path>   Save the visualization as a .gif animation, or one frame of it as .png or .ppm (requires --day)
+  --frame <N>       Frame to save as .png or .ppm, counted from 1 (default the last)
+  --scale <N>       Pixels per map cell in exported images (default 4)
+
+Options for examples:
+  --day <N>         Only check the examples of day <N>
+
+Options for new:
+  --day <N>         Day to create, from 1 to 25
+
+Options for bench:
+  --iterations <N>  Number of timed runs per day (default 10)
+  --output <path>   Write a summary to <path>, as JSON if it ends in .json, else Markdown
+  --profile-mem     Also report the peak heap use, bytes allocated and allocation count of
+                    parsing and each part, from one more run after the timed ones
+                    (requires a build with --features profile-mem)
+
+Options for gen:
+  --day <N>         Day to generate an input for
+  --size <N>        How big the input is, e.g. lines or tiles per side of a map, depending on the day (default 100)
+  --seed <N>        Seed of the random numbers, the same seed gives the same input (default 0)
+  --param <name=N>  Override a puzzle parameter the input has to fit, e.g. --param width=11
+  --output <path>   Write the input to <path> instead of stdout
+
+Options for fuzz:
+  --day <N>         Day to fuzz
+  --all             Fuzz every registered day
+  --cases <N>       Number of inputs per day (default 10000)
+  --seed <N>        Seed of the random numbers (default 0)
+  --output <dir>    Save the inputs that crashed to <dir> (default ./fuzz)
+
+Options for watch:
+  --day <N>         Day to watch
+  --input <path>    Watch and solve <path> instead of ./input/day_XX.txt
+  --example         Watch and solve ./input_test/day_XX.txt
+  --param <name=N>  Override a puzzle parameter, e.g. --param width=11
+  --interval <ms>   How often to look for changes (default 500)
+
+Options for repl:
+  --day <N>         Day to explore
+  --input <path>    Explore <path> instead of ./input/day_XX.txt
+  --example         Explore ./input_test/day_XX.txt
+  --param <name=N>  Start with a puzzle parameter overridden, e.g. --param width=11
+  --fps <N>         Frames per second of `show` (default 20)
+  --viewport <WxH>  Largest part of the map `show` draws at once (default 100x50)
+
+Options for fetch and submit:
+  --day <N>         Day of the puzzle
+  --base-url <url>  Puzzle server to talk to (default $AOC_BASE_URL, or https://adventofcode.com)
+  --session <token> Session cookie of the logged in user (default $AOC_SESSION)
+
+Options for fetch:
+  --output <path>   Where to save the input (default ./input/day_XX.txt)
+  --force           Download the input again even if it was saved before
+
+Options for submit:
+  --part <1|2>      Part the answer is for
+  --answer <value>  Answer to send (default: solve the part with ./input/day_XX.txt)
+
+Options for serve:
+  --port <N>        Port to listen on (default 8024)
+  --session <token> Session token the server accepts (default mock)
+  --cooldown <s>    Seconds to wait after a wrong answer (default 60)";
+
+#[derive(Debug, PartialEq)]
+pub struct Invocation {
+    pub command: Command,
+    pub logging: Logging,
+}
+
+/// What to log and where to, from the options every command accepts.
+#[derive(Debug, PartialEq)]
+pub struct Logging {
+    pub filter: Filter,
+    pub file: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(RunArgs),
+    Bench(BenchArgs),
+    Examples(Option<u8>),
+    New(u8),
+    Gen(GenArgs),
+    Fuzz(FuzzArgs),
+    Watch(WatchArgs),
+    Repl(ReplArgs),
+    Fetch(FetchArgs),
+    Submit(SubmitArgs),
+    Serve(ServeArgs),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DaySelection {
+    Single(u8),
+    All,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InputSelection {
+    Real,
+    Example,
+    File(String),
+}
+
+/// Puzzle parameters given with `--param`, as name and value.
+pub type ParamOverrides = Vec<(String, String)>;
+
+#[derive(Debug, PartialEq)]
+pub struct RunArgs {
+    pub days: DaySelection,
+    pub part: Option<Part>,
+    pub input: InputSelection,
+    pub params: ParamOverrides,
+    /// Animation settings, if the day should be visualized.
+    pub visualize: Option<Settings>,
+    pub export: Option<Export>,
+    /// Number of threads to solve the days on, if the results should be summarized in a table.
+    pub jobs: Option<usize>,
+}
+
+/// Where and how to save the frames of a visualization.
+#[derive(Debug, PartialEq)]
+pub struct Export {
+    pub path: String,
+    pub frame: Option<usize>,
+    pub scale: usize,
+    pub fps: u32,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchArgs {
+    pub days: DaySelection,
+    pub input: InputSelection,
+    pub params: ParamOverrides,
+    pub iterations: usize,
+    pub output: Option<String>,
+    /// Whether to count what parsing and each part allocate.
+    pub profile_mem: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct GenArgs {
+    pub day: u8,
+    pub size: usize,
+    pub seed: u64,
+    pub params: ParamOverrides,
+    pub output: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FuzzArgs {
+    pub days: DaySelection,
+    pub cases: usize,
+    pub seed: u64,
+    pub output: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WatchArgs {
+    pub day: u8,
+    pub input: InputSelection,
+    pub params: ParamOverrides,
+    pub interval: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ReplArgs {
+    pub day: u8,
+    pub input: InputSelection,
+    pub params: ParamOverrides,
+    /// How `show` animates the day.
+    pub settings: Settings,
+}
+
+/// Which puzzle server to talk to and as whom; unset values come from the environment.
+#[derive(Debug, Default, PartialEq)]
+pub struct ServerArgs {
+    pub base_url: Option<String>,
+    pub session: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FetchArgs {
+    pub day: u8,
+    pub server: ServerArgs,
+    pub output: Option<String>,
+    pub force: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SubmitArgs {
+    pub day: u8,
+    pub part: Part,
+    /// The answer to send, if it shouldn't be solved for.
+    pub answer: Option<String>,
+    pub server: ServerArgs,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ServeArgs {
+    pub port: u16,
+    pub session: String,
+    pub cooldown: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CliError(String);
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+fn error<T>(message: impl Into<String>) -> Result<T, CliError> {
+    Err(CliError(message.into()))
+}
+
+/// Walks the raw arguments of a subcommand, handing out flags and their values.
+struct Args<I: Iterator<Item = String>> {
+    inner: I,
+}
+
+impl<I: Iterator<Item = String>> Args<I> {
+    fn next_flag(&mut self) -> Option<String> {
+        self.inner.next()
+    }
+
+    fn value(&mut self, flag: &str) -> Result<String, CliError> {
+        match self.inner.next() {
+            Some(value) => Ok(value),
+            None => error(format!("Missing value for {}", flag)),
+        }
+    }
+
+    fn parsed<T: std::str::FromStr>(&mut self, flag: &str) -> Result<T, CliError> {
+        let value = self.value(flag)?;
+        value
+            .parse()
+            .or_else(|_| error(format!("Invalid value for {}: {}", flag, value)))
+    }
+}
+
+/// Collects the flags shared by all subcommands that pick days and their input.
+#[derive(Default)]
+struct SelectionFlags {
+    day: Option<u8>,
+    all: bool,
+    input: Option<String>,
+    example: bool,
+    params: ParamOverrides,
+}
+
+impl SelectionFlags {
+    /// Consumes `flag` if it is a selection flag, returning whether it was one.
+    fn accept<I: Iterator<Item = String>>(&mut self, flag: &str, args: &mut Args<I>) -> Result<bool, CliError> {
+        match flag {
+            "--day" => self.day = Some(args.parsed("--day")?),
+            "--all" => self.all = true,
+            "--input" => self.input = Some(args.value("--input")?),
+            "--example" => self.example = true,
+            "--param" => self.params.push(parse_param(&args.value("--param")?)?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn finish(self) -> Result<(DaySelection, InputSelection, ParamOverrides), CliError> {
+        let days = match (self.day, self.all) {
+            (Some(_), true) => return error("--day and --all can't be combined"),
+            (Some(day), false) => DaySelection::Single(day),
+            (None, true) => DaySelection::All,
+            (None, false) => return error("Either --day or --all is required"),
+        };
+        let input = match (self.input, self.example) {
+            (Some(_), true) => return error("--input and --example can't be combined"),
+            (Some(_), false) if days == DaySelection::All => return error("--input requires --day"),
+            (Some(path), false) => InputSelection::File(path),
+            (None, true) => InputSelection::Example,
+            (None, false) => InputSelection::Real,
+        };
+        if !self.params.is_empty() && days == DaySelection::All {
+            return error("--param requires --day");
+        }
+        Ok((days, input, self.params))
+    }
+}
+
+/// Collects the logging flags, which all commands accept anywhere among their options.
+#[derive(Default)]
+struct LoggingFlags {
+    verbosity: usize,
+    filter: Option<String>,
+    file: Option<String>,
+}
+
+impl LoggingFlags {
+    /// Consumes `flag` if it is a logging flag, returning whether it was one.
+    fn accept<I: Iterator<Item = String>>(&mut self, flag: &str, args: &mut Args<I>) -> Result<bool, CliError> {
+        match flag {
+            "--verbose" => self.verbosity += 1,
+            "-v" | "-vv" | "-vvv" => self.verbosity += flag.len() - 1,
+            "--log" => self.filter = Some(args.value("--log")?),
+            "--log-file" => self.file = Some(args.value("--log-file")?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn finish(self) -> Result<Logging, CliError> {
+        let default = match self.verbosity {
+            0 => Level::Warn,
+            1 => Level::Debug,
+            _ => Level::Trace,
+        };
+        let filter = match self.filter {
+            Some(spec) => Filter::parse(&spec, default).or_else(|err| error(format!("Invalid value for --log: {}", err)))?,
+            None => Filter::new(default),
+        };
+        Ok(Logging { filter, file: self.file })
+    }
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Invocation, CliError> {
+    let mut logging = LoggingFlags::default();
+    let mut rest = vec![];
+    let mut args = Args { inner: args.into_iter() };
+    while let Some(arg) = args.next_flag() {
+        if !logging.accept(&arg, &mut args)? {
+            rest.push(arg);
+        }
+    }
+    let command = parse_command(rest)?;
+    Ok(Invocation { command, logging: logging.finish()? })
+}
+
+fn parse_command(args: Vec<String>) -> Result<Command, CliError> {
+    let mut args = args.into_iter();
+    match args.next().as_deref() {
+        Some("run") => parse_run(Args { inner: args }).map(Command::Run),
+        Some("bench") => parse_bench(Args { inner: args }).map(Command::Bench),
+        Some("examples") => parse_examples(Args { inner: args }).map(Command::Examples),
+        Some("new") => parse_new(Args { inner: args }).map(Command::New),
+        Some("gen") => parse_gen(Args { inner: args }).map(Command::Gen),
+        Some("fuzz") => parse_fuzz(Args { inner: args }).map(Command::Fuzz),
+        Some("watch") => parse_watch(Args { inner: args }).map(Command::Watch),
+        Some("repl") => parse_repl(Args { inner: args }).map(Command::Repl),
+        Some("fetch") => parse_fetch(Args { inner: args }).map(Command::Fetch),
+        Some("submit") => parse_submit(Args { inner: args }).map(Command::Submit),
+        Some("serve") => parse_serve(Args { inner: args }).map(Command::Serve),
+        Some(other) => error(format!("Unknown command: {}", other)),
+        None => error("Missing command"),
+    }
+}
+
+fn parse_run(mut args: Args<impl Iterator<Item = String>>) -> Result<RunArgs, CliError> {
+    let mut selection = SelectionFlags::default();
+    let mut part = None;
+    let mut visualize = false;
+    let mut export = None;
+    let mut frame = None;
+    let mut scale = 4;
+    let mut jobs = None;
+    // NO_COLOR (https://no-color.org) turns off the escape codes, animation included
+    let mut settings = Settings {
+        color: std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()),
+        ..Settings::default()
+    };
+
+    while let Some(flag) = args.next_flag() {
+        if selection.accept(&flag, &mut args)? {
+            continue;
+        }
+        match flag.as_str() {
+            "--part" => part = Some(parse_part(&args.value("--part")?)?),
+            "--visualize" => visualize = true,
+            "--fps" => settings.fps = args.parsed("--fps")?,
+            "--viewport" => (settings.width, settings.height) = parse_viewport(&args.value("--viewport")?)?,
+            "--export" => export = Some(args.value("--export")?),
+            "--frame" => frame = Some(args.parsed("--frame")?),
+            "--scale" => scale = args.parsed("--scale")?,
+            "--jobs" => jobs = Some(args.parsed("--jobs")?),
+            other => return error(format!("Unknown option for run: {}", other)),
+        }
+    }
+
+    let (days, input, params) = selection.finish()?;
+    if visualize && days == DaySelection::All {
+        return error("--visualize requires --day");
+    }
+    if export.is_some() && days == DaySelection::All {
+        return error("--export requires --day");
+    }
+    if frame == Some(0) || scale == 0 {
+        return error("--frame and --scale must be at least 1");
+    }
+    if jobs == Some(0) {
+        return error("--jobs must be at least 1");
+    }
+    if jobs.is_some() && (visualize || export.is_some()) {
+        return error("--jobs can't be combined with --visualize or --export");
+    }
+    let export = export.map(|path| Export { path, frame, scale, fps: settings.fps });
+    Ok(RunArgs { days, part, input, params, visualize: visualize.then_some(settings), export, jobs })
+}
+
+fn parse_bench(mut args: Args<impl Iterator<Item = String>>) -> Result<BenchArgs, CliError> {
+    let mut selection = SelectionFlags::default();
+    let mut iterations = 10;
+    let mut output = None;
+    let mut profile_mem = false;
+
+    while let Some(flag) = args.next_flag() {
+        if selection.accept(&flag, &mut args)? {
+            continue;
+        }
+        match flag.as_str() {
+            "--iterations" => iterations = args.parsed("--iterations")?,
+            "--output" => output = Some(args.value("--output")?),
+            "--profile-mem" if !cfg!(feature = "profile-mem") => {
+                return error("--profile-mem requires a build with the profile-mem feature, like cargo run --release --features profile-mem -- bench");
+            }
+            "--profile-mem" => profile_mem = true,
+            other => return error(format!("Unknown option for bench: {}", other)),
+        }
+    }
+    if iterations == 0 {
+        return error("--iterations must be at least 1");
+    }
+
+    let (days, input, params) = selection.finish()?;
+    Ok(BenchArgs { days, input, params, iterations, output, profile_mem })
+}
+
+fn parse_examples(mut args: Args<impl Iterator<Item = String>>) -> Result<Option<u8>, CliError> {
+    let mut day = None;
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--day" => day = Some(args.parsed("--day")?),
+            other => return error(format!("Unknown option for examples: {}", other)),
+        }
+    }
+    Ok(day)
+}
+
+fn parse_new(mut args: Args<impl Iterator<Item = String>>) -> Result<u8, CliError> {
+    let mut day = None;
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--day" => day = Some(args.parsed("--day")?),
+            other => return error(format!("Unknown option for new: {}", other)),
+        }
+    }
+    match day {
+        Some(day @ 1..=25) => Ok(day),
+        Some(day) => error(format!("--day must be between 1 and 25, got {}", day)),
+        None => error("--day is required"),
+    }
+}
+
+fn parse_gen(mut args: Args<impl Iterator<Item = String>>) -> Result<GenArgs, CliError> {
+    let mut day = None;
+    let mut size = 100;
+    let mut seed = 0;
+    let mut params = vec![];
+    let mut output = None;
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--day" => day = Some(args.parsed("--day")?),
+            "--size" => size = args.parsed("--size")?,
+            "--seed" => seed = args.parsed("--seed")?,
+            "--param" => params.push(parse_param(&args.value("--param")?)?),
+            "--output" => output = Some(args.value("--output")?),
+            other => return error(format!("Unknown option for gen: {}", other)),
+        }
+    }
+    let Some(day) = day else {
+        return error("--day is required");
+    };
+    Ok(GenArgs { day, size, seed, params, output })
+}
+
+fn parse_fuzz(mut args: Args<impl Iterator<Item = String>>) -> Result<FuzzArgs, CliError> {
+    let mut day = None;
+    let mut all = false;
+    let mut cases = 10000;
+    let mut seed = 0;
+    let mut output = String::from("./fuzz");
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--day" => day = Some(args.parsed("--day")?),
+            "--all" => all = true,
+            "--cases" => cases = args.parsed("--cases")?,
+            "--seed" => seed = args.parsed("--seed")?,
+            "--output" => output = args.value("--output")?,
+            other => return error(format!("Unknown option for fuzz: {}", other)),
+        }
+    }
+    let days = match (day, all) {
+        (Some(_), true) => return error("--day and --all can't be combined"),
+        (Some(day), false) => DaySelection::Single(day),
+        (None, true) => DaySelection::All,
+        (None, false) => return error("Either --day or --all is required"),
+    };
+    Ok(FuzzArgs { days, cases, seed, output })
+}
+
+fn parse_watch(mut args: Args<impl Iterator<Item = String>>) -> Result<WatchArgs, CliError> {
+    let mut selection = SelectionFlags::default();
+    let mut interval = 500;
+    while let Some(flag) = args.next_flag() {
+        if selection.accept(&flag, &mut args)? {
+            continue;
+        }
+        match flag.as_str() {
+            "--interval" => interval = args.parsed("--interval")?,
+            other => return error(format!("Unknown option for watch: {}", other)),
+        }
+    }
+    if interval == 0 {
+        return error("--interval must be at least 1");
+    }
+    match selection.finish()? {
+        (_, InputSelection::File(path), _) if path == STDIN => error("watch can't read the input from stdin"),
+        (DaySelection::Single(day), input, params) => Ok(WatchArgs { day, input, params, interval: Duration::from_millis(interval) }),
+        (DaySelection::All, ..) => error("watch requires --day"),
+    }
+}
+
+fn parse_repl(mut args: Args<impl Iterator<Item = String>>) -> Result<ReplArgs, CliError> {
+    let mut selection = SelectionFlags::default();
+    let mut settings = Settings {
+        color: std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()),
+        ..Settings::default()
+    };
+    while let Some(flag) = args.next_flag() {
+        if selection.accept(&flag, &mut args)? {
+            continue;
+        }
+        match flag.as_str() {
+            "--fps" => settings.fps = args.parsed("--fps")?,
+            "--viewport" => (settings.width, settings.height) = parse_viewport(&args.value("--viewport")?)?,
+            other => return error(format!("Unknown option for repl: {}", other)),
+        }
+    }
+    match selection.finish()? {
+        // the commands come from stdin
+        (_, InputSelection::File(path), _) if path == STDIN => error("repl can't read the input from stdin"),
+        (DaySelection::Single(day), input, params) => Ok(ReplArgs { day, input, params, settings }),
+        (DaySelection::All, ..) => error("repl requires --day"),
+    }
+}
+
+impl ServerArgs {
+    /// Consumes `flag` if it picks the server or the session, returning whether it did.
+    fn accept<I: Iterator<Item = String>>(&mut self, flag: &str, args: &mut Args<I>) -> Result<bool, CliError> {
+        match flag {
+            "--base-url" => self.base_url = Some(args.value("--base-url")?),
+            "--session" => self.session = Some(args.value("--session")?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+fn parse_fetch(mut args: Args<impl Iterator<Item = String>>) -> Result<FetchArgs, CliError> {
+    let mut day = None;
+    let mut server = ServerArgs::default();
+    let mut output = None;
+    let mut force = false;
+    while let Some(flag) = args.next_flag() {
+        if server.accept(&flag, &mut args)? {
+            continue;
+        }
+        match flag.as_str() {
+            "--day" => day = Some(args.parsed("--day")?),
+            "--output" => output = Some(args.value("--output")?),
+            "--force" => force = true,
+            other => return error(format!("Unknown option for fetch: {}", other)),
+        }
+    }
+    let Some(day) = day else {
+        return error("--day is required");
+    };
+    Ok(FetchArgs { day, server, output, force })
+}
+
+fn parse_submit(mut args: Args<impl Iterator<Item = String>>) -> Result<SubmitArgs, CliError> {
+    let mut day = None;
+    let mut part = None;
+    let mut answer = None;
+    let mut server = ServerArgs::default();
+    while let Some(flag) = args.next_flag() {
+        if server.accept(&flag, &mut args)? {
+            continue;
+        }
+        match flag.as_str() {
+            "--day" => day = Some(args.parsed("--day")?),
+            "--part" => part = Some(parse_part(&args.value("--part")?)?),
+            "--answer" => answer = Some(args.value("--answer")?),
+            other => return error(format!("Unknown option for submit: {}", other)),
+        }
+    }
+    let (Some(day), Some(part)) = (day, part) else {
+        return error("--day and --part are required");
+    };
+    Ok(SubmitArgs { day, part, answer, server })
+}
+
+fn parse_serve(mut args: Args<impl Iterator<Item = String>>) -> Result<ServeArgs, CliError> {
+    let mut port = 8024;
+    let mut session = String::from("mock");
+    let mut cooldown = 60;
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--port" => port = args.parsed("--port")?,
+            "--session" => session = args.value("--session")?,
+            "--cooldown" => cooldown = args.parsed("--cooldown")?,
+            other => return error(format!("Unknown option for serve: {}", other)),
+        }
+    }
+    Ok(ServeArgs { port, session, cooldown: Duration::from_secs(cooldown) })
+}
+
+fn parse_param(value: &str) -> Result<(String, String), CliError> {
+    match value.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((String::from(name), String::from(value))),
+        _ => error(format!("Invalid value for --param, expected name=value: {}", value)),
+    }
+}
+
+fn parse_viewport(value: &str) -> Result<(usize, usize), CliError> {
+    let size = value
+        .split_once('x')
+        .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)));
+    match size {
+        Some((width, height)) if width > 0 && height > 0 => Ok((width, height)),
+        _ => error(format!("Invalid value for --viewport: {}", value)),
+    }
+}
+
+fn parse_part(value: &str) -> Result<Part, CliError> {
+    match value {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => error(format!("Invalid value for --part: {}", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, CliError> {
+        parse_args(args.split_whitespace().map(String::from)).map(|invocation| invocation.command)
+    }
+
+    fn logging(args: &str) -> Result<Logging, CliError> {
+        parse_args(args.split_whitespace().map(String::from)).map(|invocation| invocation.logging)
+    }
+
+    #[test]
+    fn test_parse_run() {
+        assert_eq!(
+            parse("run --day 7 --part 2 --input some/file.txt"),
+            Ok(Command::Run(RunArgs {
+                days: DaySelection::Single(7),
+                part: Some(Part::Two),
+                input: InputSelection::File(String::from("some/file.txt")),
+                params: vec![],
+                visualize: None,
+                export: None,
+                jobs: None
+            }))
+        );
+        assert_eq!(
+            parse("run --all --example"),
+            Ok(Command::Run(RunArgs {
+                days: DaySelection::All,
+                part: None,
+                input: InputSelection::Example,
+                params: vec![],
+                visualize: None,
+                export: None,
+                jobs: None
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_params() {
+        let Ok(Command::Run(args)) = parse("run --day 14 --example --param width=11 --param height=7") else {
+            panic!("expected a run command");
+        };
+        assert_eq!(args.params, vec![
+            (String::from("width"), String::from("11")),
+            (String::from("height"), String::from("7"))
+        ]);
+    }
+
+    #[test]
+    fn test_parse_visualize() {
+        let Ok(Command::Run(args)) = parse("run --day 6 --visualize --fps 0 --viewport 40x20") else {
+            panic!("expected a run command");
+        };
+        let settings = args.visualize.unwrap();
+        assert_eq!((settings.fps, settings.width, settings.height), (0, 40, 20));
+        assert!(parse("run --all --visualize").is_err());
+        assert!(parse("run --day 6 --visualize --viewport 40").is_err());
+        assert!(parse("run --day 6 --visualize --viewport 0x20").is_err());
+        assert!(parse("run --day 6 --fps fast").is_err());
+    }
+
+    #[test]
+    fn test_parse_export() {
+        let Ok(Command::Run(args)) = parse("run --day 14 --export robots.png --frame 7 --fps 5") else {
+            panic!("expected a run command");
+        };
+        assert_eq!(args.visualize, None);
+        assert_eq!(args.export, Some(Export { path: String::from("robots.png"), frame: Some(7), scale: 4, fps: 5 }));
+        assert!(parse("run --all --export robots.gif").is_err());
+        assert!(parse("run --day 14 --export robots.gif --scale 0").is_err());
+        assert!(parse("run --day 14 --export").is_err());
+    }
+
+    #[test]
+    fn test_parse_jobs() {
+        let Ok(Command::Run(args)) = parse("run --all --jobs 4") else {
+            panic!("expected a run command");
+        };
+        assert_eq!((args.days, args.jobs), (DaySelection::All, Some(4)));
+        assert!(parse("run --all --jobs 0").is_err());
+        assert!(parse("run --all --jobs many").is_err());
+        assert!(parse("run --day 6 --jobs 2 --visualize").is_err());
+        assert!(parse("run --day 6 --jobs 2 --export walk.gif").is_err());
+    }
+
+    #[test]
+    fn test_parse_logging() {
+        assert_eq!(logging("run --all"), Ok(Logging { filter: Filter::new(Level::Warn), file: None }));
+        assert_eq!(logging("-v run --all"), Ok(Logging { filter: Filter::new(Level::Debug), file: None }));
+        assert_eq!(logging("run --verbose --all -v").unwrap().filter, Filter::new(Level::Trace));
+        assert_eq!(
+            logging("examples --log info,day_05=trace --log-file run.log"),
+            Ok(Logging { filter: Filter::parse("info,day_05=trace", Level::Warn).unwrap(), file: Some(String::from("run.log")) })
+        );
+        assert_eq!(parse("run --day 5 -vv --part 1").unwrap(), parse("run --day 5 --part 1").unwrap());
+        assert!(parse("run --all --log day_05=loud").is_err());
+        assert!(parse("run --all --log-file").is_err());
+        assert!(parse("--verbose").is_err());
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        assert_eq!(
+            parse("bench --all --iterations 5 --output bench.json"),
+            Ok(Command::Bench(BenchArgs {
+                days: DaySelection::All,
+                input: InputSelection::Real,
+                params: vec![],
+                iterations: 5,
+                output: Some(String::from("bench.json")),
+                profile_mem: false
+            }))
+        );
+        match parse("bench --day 2 --profile-mem") {
+            Ok(Command::Bench(args)) => assert!(cfg!(feature = "profile-mem") && args.profile_mem),
+            other => assert!(!cfg!(feature = "profile-mem") && other.is_err()),
+        }
+        assert!(parse("bench --day 1 --iterations 0").is_err());
+        assert!(parse("bench --day 1 --part 1").is_err());
+    }
+
+    #[test]
+    fn test_parse_examples() {
+        assert_eq!(parse("examples"), Ok(Command::Examples(None)));
+        assert_eq!(parse("examples --day 3"), Ok(Command::Examples(Some(3))));
+        assert!(parse("examples --all").is_err());
+    }
+
+    #[test]
+    fn test_parse_new() {
+        assert_eq!(parse("new --day 19"), Ok(Command::New(19)));
+        assert!(parse("new").is_err());
+        assert!(parse("new --day 26").is_err());
+        assert!(parse("new --all").is_err());
+    }
+
+    #[test]
+    fn test_parse_gen() {
+        assert_eq!(
+            parse("gen --day 14 --size 500 --seed 7 --param width=11 --output robots.txt"),
+            Ok(Command::Gen(GenArgs {
+                day: 14,
+                size: 500,
+                seed: 7,
+                params: vec![(String::from("width"), String::from("11"))],
+                output: Some(String::from("robots.txt"))
+            }))
+        );
+        assert_eq!(
+            parse("gen --day 9"),
+            Ok(Command::Gen(GenArgs { day: 9, size: 100, seed: 0, params: vec![], output: None }))
+        );
+        assert!(parse("gen --size 10").is_err());
+        assert!(parse("gen --all").is_err());
+        assert!(parse("gen --day 9 --seed -1").is_err());
+    }
+
+    #[test]
+    fn test_parse_fuzz() {
+        assert_eq!(
+            parse("fuzz --all --cases 500 --seed 3"),
+            Ok(Command::Fuzz(FuzzArgs { days: DaySelection::All, cases: 500, seed: 3, output: String::from("./fuzz") }))
+        );
+        assert_eq!(
+            parse("fuzz --day 17 --output crashes"),
+            Ok(Command::Fuzz(FuzzArgs { days: DaySelection::Single(17), cases: 10000, seed: 0, output: String::from("crashes") }))
+        );
+        assert!(parse("fuzz").is_err());
+        assert!(parse("fuzz --day 1 --all").is_err());
+        assert!(parse("fuzz --day 1 --example").is_err());
+    }
+
+    #[test]
+    fn test_parse_watch() {
+        assert_eq!(
+            parse("watch --day 5 --example --interval 100"),
+            Ok(Command::Watch(WatchArgs {
+                day: 5,
+                input: InputSelection::Example,
+                params: vec![],
+                interval: Duration::from_millis(100)
+            }))
+        );
+        assert_eq!(
+            parse("watch --day 14 --param width=11"),
+            Ok(Command::Watch(WatchArgs {
+                day: 14,
+                input: InputSelection::Real,
+                params: vec![(String::from("width"), String::from("11"))],
+                interval: Duration::from_millis(500)
+            }))
+        );
+        assert!(parse("watch").is_err());
+        assert!(parse("watch --all").is_err());
+        assert!(parse("watch --day 5 --interval 0").is_err());
+        assert!(parse("watch --day 5 --input -").is_err());
+    }
+
+    #[test]
+    fn test_parse_repl() {
+        let Ok(Command::Repl(args)) = parse("repl --day 12 --example --param width=11 --fps 0 --viewport 40x20") else {
+            panic!("expected a repl command");
+        };
+        assert_eq!((args.day, &args.input), (12, &InputSelection::Example));
+        assert_eq!(args.params, vec![(String::from("width"), String::from("11"))]);
+        assert_eq!((args.settings.fps, args.settings.width, args.settings.height), (0, 40, 20));
+        assert!(parse("repl").is_err());
+        assert!(parse("repl --all").is_err());
+        assert!(parse("repl --day 12 --input -").is_err());
+        assert!(parse("repl --day 12 --part 1").is_err());
+    }
+
+    #[test]
+    fn test_parse_fetch_and_submit() {
+        assert_eq!(
+            parse("fetch --day 5 --base-url http://127.0.0.1:8024 --session abc --force"),
+            Ok(Command::Fetch(FetchArgs {
+                day: 5,
+                server: ServerArgs { base_url: Some(String::from("http://127.0.0.1:8024")), session: Some(String::from("abc")) },
+                output: None,
+                force: true
+            }))
+        );
+        assert_eq!(
+            parse("submit --day 5 --part 2 --answer 4130"),
+            Ok(Command::Submit(SubmitArgs { day: 5, part: Part::Two, answer: Some(String::from("4130")), server: ServerArgs::default() }))
+        );
+        assert_eq!(
+            parse("serve --port 9000 --cooldown 5"),
+            Ok(Command::Serve(ServeArgs { port: 9000, session: String::from("mock"), cooldown: Duration::from_secs(5) }))
+        );
+        assert!(parse("fetch").is_err());
+        assert!(parse("fetch --day 5 --example").is_err());
+        assert!(parse("submit --day 5").is_err());
+        assert!(parse("submit --day 5 --part 3").is_err());
+        assert!(parse("serve --port 70000").is_err());
+    }
+
+    #[test]
+    fn test_parse_run_errors() {
+        assert!(parse("").is_err());
+        assert!(parse("walk --day 1").is_err());
+        assert!(parse("run").is_err());
+        assert!(parse("run --day").is_err());
+        assert!(parse("run --day x").is_err());
+        assert!(parse("run --day 1 --all").is_err());
+        assert!(parse("run --day 1 --part 3").is_err());
+        assert!(parse("run --all --input file.txt").is_err());
+        assert!(parse("run --all --input -").is_err());
+        assert!(parse("run --all --param width=11").is_err());
+        assert!(parse("run --day 14 --param width").is_err());
+        assert!(parse("run --day 1 --input file.txt --example").is_err());
+    }
+}
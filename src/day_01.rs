@@ -1,4 +1,6 @@
-use std::{borrow::BorrowMut, collections::{btree_map::Values, HashMap}, fs, ptr::read};
+use std::collections::HashMap;
+
+use crate::{params::Params, parse::{ParseError, Source}, random::Rng, solution::Solution};
 
 
 trait NumCounter {
@@ -16,7 +18,8 @@ impl NumCounter for Vec<usize> {
     }
 }
 
-struct Lists {
+#[derive(Clone, Default)]
+pub struct Lists {
     left: Vec<usize>,
     right: Vec<usize>
 }
@@ -56,38 +59,62 @@ impl Lists {
     }
 }
 
-#[allow(dead_code)]
-pub fn day_01() {
-    let distance = calc_distances("./input/day_01.txt");
-    println!("distance is {}", distance);
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Lists;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(source: &Source, _params: &Params) -> Result<Lists, ParseError> {
+        read_to_lists(source)
+    }
+
+    fn part1(input: &Lists) -> usize {
+        calc_distances(input)
+    }
+
+    fn part2(input: &Lists) -> usize {
+        calc_similarity(input)
+    }
 
-    let similarity = calc_similarity("./input/day_01.txt");
-    println!("similarity is {}", similarity);
+    fn generate(rng: &mut Rng, size: usize, _params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(Some(generate_lists(rng, size)))
+    }
 }
 
-fn read_to_lists(path: &str) -> Lists {
+fn read_to_lists(source: &Source) -> Result<Lists, ParseError> {
     let mut result_list = Lists::new();
-    fs::read_to_string(path)
-        .unwrap()
-        .lines()
-        .map(|line: &str| line
-            .split_whitespace()
-            .map(|str| str.parse::<usize>().unwrap())
-        )
-        .fold(result_list.borrow_mut(), |init, iter| init.add(iter));
-    result_list
+    for line in source.text.lines() {
+        let mut tokens = line.split_whitespace();
+        let left = source.number(source.next(&mut tokens, line, "the left location id")?)?;
+        let right = source.number(source.next(&mut tokens, line, "the right location id")?)?;
+        result_list.add([left, right].into_iter());
+    }
+    Ok(result_list)
+}
+
+/// `size` pairs of five digit location IDs; half of the right list repeats IDs of the left one.
+fn generate_lists(rng: &mut Rng, size: usize) -> String {
+    let left: Vec<usize> = (0..size).map(|_| rng.range(10000..=99999)).collect();
+    left
+        .iter()
+        .map(|&id| {
+            let right = if rng.chance(1, 2) { *rng.choose(&left) } else { rng.range(10000..=99999) };
+            format!("{}   {}\n", id, right)
+        })
+        .collect()
 }
 
-fn calc_distances(path: &str) -> usize {
-    let mut result_list = read_to_lists(path);
+fn calc_distances(lists: &Lists) -> usize {
+    let mut result_list = lists.clone();
     result_list.sort();
 
     result_list.calc_pair_diff()
 }
 
-fn calc_similarity(path: &str) -> usize {
-    let result_list = read_to_lists(path);
-    result_list.calc_similarity()
+fn calc_similarity(lists: &Lists) -> usize {
+    lists.calc_similarity()
 }
 
 #[cfg(test)]
@@ -96,13 +123,13 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        let sum = calc_distances("./input_test/day_01.txt");
+        let sum = calc_distances(&Day01::load("./input_test/day_01.txt").unwrap());
         assert_eq!(sum, 11);
     }
 
     #[test]
     fn test_part_2() {
-        let similarity = calc_similarity("./input_test/day_01.txt");
+        let similarity = calc_similarity(&Day01::load("./input_test/day_01.txt").unwrap());
         assert_eq!(similarity, 31);
     }
 }
\ No newline at end of file
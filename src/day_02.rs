@@ -1,16 +1,30 @@
-use std::fs;
+use crate::{params::Params, parse::{ParseError, Source}, random::Rng, solution::Solution};
 
+pub struct Day02;
 
-#[allow(dead_code)]
-pub fn day_02() {
-    let safe_reports = count_safe_reports("./input/day_02.txt");
-    println!("safe reports: {}", safe_reports);
-    let safe_reports = count_safe_reports_dampened("./input/day_02.txt");
-    println!("safe reports dampened: {}", safe_reports);
-}
+impl Solution for Day02 {
+    type Input = Vec<Report>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(source: &Source, _params: &Params) -> Result<Vec<Report>, ParseError> {
+        read_report(source)
+    }
+
+    fn part1(input: &Vec<Report>) -> usize {
+        count_safe_reports(input)
+    }
+
+    fn part2(input: &Vec<Report>) -> usize {
+        count_safe_reports_dampened(input)
+    }
 
+    fn generate(rng: &mut Rng, size: usize, _params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(Some(generate_reports(rng, size)))
+    }
+}
 
-struct Report{
+pub struct Report {
     levels: Vec<usize>
 }
 
@@ -56,23 +70,46 @@ impl FromIterator<usize> for Report {
     }
 }
 
-fn read_report(path: &str) -> Vec<Report> {
-    fs::read_to_string(path)
-        .unwrap()
+fn read_report(source: &Source) -> Result<Vec<Report>, ParseError> {
+    source.text
         .lines()
-        .map(|line| {
-            let levels = line.split_whitespace().map(|val| val.parse().unwrap());
-            Report::from_iter(levels)
+        .map(|line| line
+            .split_whitespace()
+            .map(|val| source.number(val))
+            .collect::<Result<Vec<usize>, _>>()
+            .map(Report::from_iter)
+        )
+        .collect()
+}
+
+/// `size` reports of five to eight levels that steadily rise or fall, a third of them with one bad step.
+fn generate_reports(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let len = rng.range(5..=8);
+            let direction = if rng.chance(1, 2) { 1 } else { -1 };
+            let bad_step = rng.chance(1, 3).then(|| rng.below(len - 1));
+            let mut level = rng.signed(50..=99);
+            let mut levels = vec![level.to_string()];
+            for step in 0..len - 1 {
+                level += direction * if bad_step == Some(step) {
+                    *rng.choose(&[0, 4, 5, -1, -2])
+                } else {
+                    rng.signed(1..=3)
+                };
+                levels.push(level.to_string());
+            }
+            levels.join(" ") + "\n"
         })
         .collect()
 }
 
-fn count_safe_reports(path: &str) -> usize {
-    read_report(path).iter().filter(|report| report.is_safe()).count()
+fn count_safe_reports(reports: &[Report]) -> usize {
+    reports.iter().filter(|report| report.is_safe()).count()
 }
 
-fn count_safe_reports_dampened(path: &str) -> usize {
-    read_report(path).iter().filter(|report| report.is_safe_dampened()).count()
+fn count_safe_reports_dampened(reports: &[Report]) -> usize {
+    reports.iter().filter(|report| report.is_safe_dampened()).count()
 }
 
 
@@ -83,11 +120,18 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        assert_eq!(count_safe_reports("./input_test/day_02.txt"), 2);
+        assert_eq!(count_safe_reports(&Day02::load("./input_test/day_02.txt").unwrap()), 2);
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(count_safe_reports_dampened("./input_test/day_02.txt"), 4);
+        assert_eq!(count_safe_reports_dampened(&Day02::load("./input_test/day_02.txt").unwrap()), 4);
+    }
+
+    #[test]
+    fn test_dampener_edges() {
+        // only removing the first or the last level makes these safe
+        assert_eq!(Day02::solve("9 1 2 3\n1 2 3 9\n"), Ok((0, 2)));
+        assert_eq!(Day02::solve("1 1 1 1\n"), Ok((0, 0)));
     }
 }
\ No newline at end of file
@@ -1,16 +1,64 @@
-use std::fs;
 use regex::Regex;
 
-#[allow(dead_code)]
-pub fn day_03() {
-    let sum = sum_mul_commands("./input/day_03.txt");
-    println!("sum mul commands: {}", sum);
-    let do_mul_sum = sum_enabled_mul_commands("./input/day_03.txt");
-    println!("sum do mul commands: {}", do_mul_sum);
+use crate::{params::Params, parse::{ParseError, Source}, random::Rng, solution::Solution};
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = String;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(source: &Source, _params: &Params) -> Result<String, ParseError> {
+        Ok(source.text.clone())
+    }
+
+    fn part1(input: &String) -> usize {
+        sum_mul_commands(input)
+    }
+
+    fn part2(input: &String) -> usize {
+        sum_enabled_mul_commands(input)
+    }
+
+    fn generate(rng: &mut Rng, size: usize, _params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(Some(generate_memory(rng, size)))
+    }
 }
 
-fn sum_enabled_mul_commands(path: &str) -> usize {
-    let input = fs::read_to_string(path).unwrap();
+/// `size` instructions between bits of junk: mostly `mul`s, some `do()`s and `don't()`s, and some
+/// `mul`s that are just a little off and don't count.
+fn generate_memory(rng: &mut Rng, size: usize) -> String {
+    const JUNK: &[&str] = &[
+        "!", "@", "#", "$", "%", "^", "&", "*", "(", ")", "[", "]", "{", "}", "<", ">", ",", ";", ":", "'", "?", "~",
+        "+", "-", " ", "from()", "what()", "select()", "where()", "when()", "how()", "why()", "who()",
+    ];
+    let mut memory = String::new();
+    for index in 0..size {
+        for _ in 0..rng.below(6) {
+            let junk = rng.choose(JUNK);
+            memory.push_str(junk);
+        }
+        let (a, b) = (rng.range(1..=999), rng.range(1..=999));
+        let instruction = match rng.below(12) {
+            0 => String::from("do()"),
+            1 => String::from("don't()"),
+            2 => format!("mul[{},{}]", a, b),
+            3 => format!("mul({}, {})", a, b),
+            4 => format!("mul({},{}!", a, b),
+            5 => format!("mul({},{})", a + 1000, b),
+            _ => format!("mul({},{})", a, b),
+        };
+        memory.push_str(&instruction);
+        if index % 50 == 49 {
+            memory.push('\n');
+        }
+    }
+    memory.push('\n');
+    memory
+}
+
+fn sum_enabled_mul_commands(input: &str) -> usize {
     let dont_splits = input.split("don't()");
     let mut sum = 0;
     for (i, spl) in dont_splits.enumerate() {
@@ -21,15 +69,14 @@ fn sum_enabled_mul_commands(path: &str) -> usize {
         let str_iter = spl.split_once("do()");
         
         if let Some((_, do_str)) = str_iter {
-            sum += sum_mul_str(&do_str);
+            sum += sum_mul_str(do_str);
         }
     }
     sum
 }
 
-fn sum_mul_commands(path: &str) -> usize {
-    let input = fs::read_to_string(path).unwrap();
-    sum_mul_str(&input)
+fn sum_mul_commands(input: &str) -> usize {
+    sum_mul_str(input)
 }
 
 fn sum_mul_str(input: &str) -> usize {
@@ -54,11 +101,17 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        assert_eq!(sum_mul_commands("./input_test/day_03.txt"), 161);
+        assert_eq!(sum_mul_commands(&Day03::load("./input_test/day_03.txt").unwrap()), 161);
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(sum_enabled_mul_commands("./input_test/day_03_2.txt"), 48);
+        assert_eq!(sum_enabled_mul_commands(&Day03::load("./input_test/day_03_2.txt").unwrap()), 48);
+    }
+
+    #[test]
+    fn test_malformed_commands() {
+        assert_eq!(Day03::solve("mul(4*mul(6,9!?(12,34)mul(2,3)"), Ok((6, 6)));
+        assert_eq!(Day03::solve("don't()mul(2,3)do()mul(1,1)don't()"), Ok((7, 1)));
     }
 }
\ No newline at end of file
@@ -1,100 +1,84 @@
-use std::{collections::HashMap, fs};
-
-#[allow(dead_code)]
-pub fn day_04() {
-    let num_xmas = count_xmas("./input/day_04.txt");
-    println!("num xmases: {}", num_xmas);
-    let num_cross_mas = count_cross_mas("./input/day_04.txt");
-    println!("num crossmases: {}", num_cross_mas);
-}
+use crate::{grid::{Grid, Point, DIAGONALS, NEIGHBORS_8}, params::Params, parse::{ParseError, Source}, random::Rng, solution::Solution};
+
+pub struct Day04;
 
+impl Solution for Day04 {
+    type Input = Grid<char>;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-fn count_xmas(path: &str) -> usize {
-    let map = read_map(path);
+    fn parse(source: &Source, _params: &Params) -> Result<Grid<char>, ParseError> {
+        read_map(source)
+    }
+
+    fn part1(input: &Grid<char>) -> usize {
+        count_xmas(input)
+    }
+
+    fn part2(input: &Grid<char>) -> usize {
+        count_cross_mas(input)
+    }
+
+    fn generate(rng: &mut Rng, size: usize, _params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(Some(Grid::from_fn(size, size, |_| *rng.choose(&['X', 'M', 'A', 'S'])).to_string()))
+    }
+}
+
+fn count_xmas(map: &Grid<char>) -> usize {
     map
         .iter()
         .filter(|(_, char)| char == &&'X')
-        .map(|((x, y), _)| get_surrounding_matches(x, y, &map))
+        .map(|(point, _)| get_surrounding_matches(point, map))
         .sum()
 }
 
-fn count_cross_mas(path: &str) -> usize {
-    let map = read_map(path);
+fn count_cross_mas(map: &Grid<char>) -> usize {
     map
         .iter()
         .filter(|(_, char)| char == &&'A')
-        .filter(|((x, y), _)| has_diag_mas(x, y, &map))
+        .filter(|(point, _)| has_diag_mas(*point, map))
         .count()
 }
 
-fn has_diag_mas(x: &i32, y: &i32, map: &HashMap<(i32, i32), char>) -> bool {
-    let mut res = 0;
-    for k in -1..=1 {
-        for j in -1..=1 {
-            if k == 0 || j == 0 {
-                continue;
-            }
-            if matches_cross_mas_in_direction(x, y, &(k, j), map) {
-                res += 1;
-            }
-        }
-    }
-    res == 2
+fn has_diag_mas(point: Point, map: &Grid<char>) -> bool {
+    DIAGONALS
+        .into_iter()
+        .filter(|&dir| matches_cross_mas_in_direction(point, dir, map))
+        .count() == 2
 }
 
-fn matches_cross_mas_in_direction(x: &i32, y: &i32, dir: &(i32, i32), map: &HashMap<(i32, i32), char>) -> bool {
-    if let Some(opposite_char) = map.get(&(x - dir.0, y - dir.1)) {
-        if let Some(char_dir) = map.get(&(x + dir.0, y + dir.1)) {
-            return match (opposite_char, char_dir) {
-                ('M','S') => true,
-                _ => false
-            }
+fn matches_cross_mas_in_direction(point: Point, dir: Point, map: &Grid<char>) -> bool {
+    if let Some(opposite_char) = map.get(point - dir) {
+        if let Some(char_dir) = map.get(point + dir) {
+            return matches!((opposite_char, char_dir), ('M', 'S'))
         }
     }
     false
 }
 
-fn read_map(path: &str) -> HashMap<(i32, i32), char> {
-    fs::read_to_string(path)
-        .unwrap()
-        .lines()
-        .enumerate()
-        .flat_map(|(y , line)|
-            line
-                .chars()
-                .enumerate()
-                .map(move |(x, char)| ((x.try_into().unwrap(), y.try_into().unwrap()), char))
-        )
-        .collect()
+fn read_map(source: &Source) -> Result<Grid<char>, ParseError> {
+    Grid::parse(source, &source.text)
 }
 
-fn get_surrounding_matches(x: &i32, y: &i32, map: &HashMap<(i32, i32), char>) -> usize {
-    let mut res = 0;
-    for k in -1..=1 {
-        for j in -1..=1 {
-            if k == 0 && j == 0 {
-                continue;
-            }
-            if matches_mas_in_direction(x, y, &(k, j), 'M', map) {
-                res += 1;
-            }
-        }
-    }
-    res
+fn get_surrounding_matches(point: Point, map: &Grid<char>) -> usize {
+    NEIGHBORS_8
+        .into_iter()
+        .filter(|&dir| matches_mas_in_direction(point, dir, 'M', map))
+        .count()
 }
 
-fn matches_mas_in_direction(x: &i32, y: &i32, dir: &(i32, i32), needle: char, map: &HashMap<(i32, i32), char>) -> bool {
+fn matches_mas_in_direction(point: Point, dir: Point, needle: char, map: &Grid<char>) -> bool {
     let dir_multiplier = match needle {
         'M' => 1,
         'A' => 2,
         'S' => 3,
         _ => panic!("Didn't expect another char!")
     };
-    if let Some(char_at_pos) = map.get(&(x + dir.0 * dir_multiplier, y + dir.1 * dir_multiplier)) {
+    if let Some(char_at_pos) = map.get(point + dir * dir_multiplier) {
         if char_at_pos == &needle {
             return match needle {
-                'M' => matches_mas_in_direction(x, y, &dir, 'A', map),
-                'A' => matches_mas_in_direction(x, y, &dir, 'S', map),
+                'M' => matches_mas_in_direction(point, dir, 'A', map),
+                'A' => matches_mas_in_direction(point, dir, 'S', map),
                 'S' => {
                     true
                 },
@@ -102,7 +86,7 @@ fn matches_mas_in_direction(x: &i32, y: &i32, dir: &(i32, i32), needle: char, ma
             };
         }
     }
-    return false;
+    false
 }
 
 #[cfg(test)]
@@ -111,11 +95,11 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        assert_eq!(count_xmas("./input_test/day_04.txt"), 18);
+        assert_eq!(count_xmas(&Day04::load("./input_test/day_04.txt").unwrap()), 18);
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(count_cross_mas("./input_test/day_04.txt"), 9);
+        assert_eq!(count_cross_mas(&Day04::load("./input_test/day_04.txt").unwrap()), 9);
     }
 }
\ No newline at end of file
@@ -1,27 +1,55 @@
-use std::{cmp::Ordering, collections::{HashMap, HashSet}, fs, usize};
-#[allow(dead_code)]
-pub fn day_05() {
-    let res = calc_ordered_middle_sum("./input/day_05.txt");
-    println!("result: {}", res);
-    let res = calc_unordered_middle_sum("./input/day_05.txt");
-    println!("result2: {}", res);
+use std::{cmp::Ordering, collections::{HashMap, HashSet}};
+
+use crate::{debug, params::Params, parse::{ParseError, Source}, random::Rng, solution::Solution};
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = (Vec<Vec<usize>>, Sorter);
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(source: &Source, _params: &Params) -> Result<(Vec<Vec<usize>>, Sorter), ParseError> {
+        let (updates, lookup, reverse_lookup) = read_input(source)?;
+        Ok((updates, Sorter::new(lookup, reverse_lookup)))
+    }
+
+    fn part1((updates, sorter): &(Vec<Vec<usize>>, Sorter)) -> usize {
+        calc_ordered_middle_sum(updates, sorter)
+    }
+
+    fn part2((updates, sorter): &(Vec<Vec<usize>>, Sorter)) -> usize {
+        calc_unordered_middle_sum(updates, sorter)
+    }
+
+    fn generate(rng: &mut Rng, size: usize, _params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(Some(generate_manual(rng, size)))
+    }
 }
 
-fn read_input(path: &str) -> (String, HashMap<usize, HashSet<usize>>, HashMap<usize, HashSet<usize>>) {
-    let str = fs::read_to_string(path).unwrap();
-    let mut splits = str.split("\n\n");
-    let sorts = splits.next().unwrap();
-    let updates = splits.next().unwrap();
-    let mut lookup: HashMap<usize, HashSet<usize>> = HashMap::new();
-    let mut reverse_lookup: HashMap<usize, HashSet<usize>> = HashMap::new();
-    (lookup, reverse_lookup) = sorts
-        .split("\n")
+type Lookup = HashMap<usize, HashSet<usize>>;
+
+fn read_input(source: &Source) -> Result<(Vec<Vec<usize>>, Lookup, Lookup), ParseError> {
+    let (sorts, updates) = source.sections(&source.text)?;
+    let orders = sorts
+        .lines()
         .map(|sort| {
-            let mut order_iter = sort
-                .split("|")
-                .map(|spl| spl.parse::<usize>().unwrap());
-            (order_iter.next().unwrap(), order_iter.next().unwrap())
+            let (before, after) = source.split_once(sort, "|")?;
+            Ok((source.number(before)?, source.number(after)?))
         })
+        .collect::<Result<Vec<(usize, usize)>, ParseError>>()?;
+    let updates = updates
+        .lines()
+        .map(|update| update
+            .split(",")
+            .map(|page| source.number(page))
+            .collect()
+        )
+        .collect::<Result<_, ParseError>>()?;
+    let mut lookup: HashMap<usize, HashSet<usize>> = HashMap::new();
+    let mut reverse_lookup: HashMap<usize, HashSet<usize>> = HashMap::new();
+    (lookup, reverse_lookup) = orders
+        .into_iter()
         .fold((lookup, reverse_lookup), | (mut cur_lookup, mut cur_reverse_lookup), (before, after) | {
             let entry = cur_lookup
                 .entry(before)
@@ -33,10 +61,37 @@ fn read_input(path: &str) -> (String, HashMap<usize, HashSet<usize>>, HashMap<us
             reverse_entry.insert(before);
             (cur_lookup, cur_reverse_lookup)
         });
-    (String::from(updates), lookup, reverse_lookup)
+    Ok((updates, lookup, reverse_lookup))
+}
+
+/// Rules ordering every pair of 49 pages, like the real input, and `size` updates of five to 23
+/// of them, half of which are already in order.
+fn generate_manual(rng: &mut Rng, size: usize) -> String {
+    let mut pages: Vec<usize> = (10..=99).collect();
+    rng.shuffle(&mut pages);
+    pages.truncate(49);
+    let mut rules: Vec<String> = pages
+        .iter()
+        .enumerate()
+        .flat_map(|(index, before)| pages[index + 1..].iter().map(move |after| format!("{}|{}\n", before, after)))
+        .collect();
+    rng.shuffle(&mut rules);
+
+    let mut manual = rules.concat() + "\n";
+    for _ in 0..size {
+        let mut update = pages.clone();
+        rng.shuffle(&mut update);
+        update.truncate(2 * rng.range(2..=11) + 1);
+        if rng.chance(1, 2) {
+            update.sort_by_key(|page| pages.iter().position(|known| known == page));
+        }
+        let update: Vec<String> = update.iter().map(|page| page.to_string()).collect();
+        manual.push_str(&(update.join(",") + "\n"));
+    }
+    manual
 }
 
-struct Sorter {
+pub struct Sorter {
     lookup: HashMap<usize, HashSet<usize>>,
     reverse_lookup: HashMap<usize, HashSet<usize>>
 }
@@ -49,7 +104,7 @@ impl Sorter {
         }
     }
     pub fn all_sorted(&self, window: &[usize]) -> bool {
-        return self.sort(window[0], window[1]) == Ordering::Less;
+        self.sort(window[0], window[1]) == Ordering::Less
     }
     pub fn sort(&self, a: usize, b: usize) -> Ordering {
         let is_b_correctly_after_a = self.lookup
@@ -68,19 +123,13 @@ impl Sorter {
     }
 }
 
-fn calc_unordered_middle_sum(path: &str) -> usize {
-    let (updates, lookup, reverse_lookup) = read_input(path);
-    let sorter = Sorter::new(lookup, reverse_lookup);
+fn calc_unordered_middle_sum(updates: &[Vec<usize>], sorter: &Sorter) -> usize {
     updates
-        .split("\n")
-        .map(|update| update
-            .split(",")
-            .map(|page| page.parse::<usize>().unwrap())
-            .collect::<Vec<usize>>())
+        .iter()
         .filter(|update| {
             !update.windows(2).all(|window| sorter.all_sorted(window))
         })
-        .inspect(|update| println!("update is unordered: {:?}", update))
+        .inspect(|update| debug!("update is unordered: {:?}", update))
         .map(|update| {
             let mut cloned = update.clone();
             cloned.sort_by(|a, b| sorter.sort(*a, *b));
@@ -91,17 +140,11 @@ fn calc_unordered_middle_sum(path: &str) -> usize {
 
 }
 
-fn calc_ordered_middle_sum(path: &str) -> usize {
-    let (updates, lookup, reverse_lookup) = read_input(path);
-    let sorter = Sorter::new(lookup, reverse_lookup);
+fn calc_ordered_middle_sum(updates: &[Vec<usize>], sorter: &Sorter) -> usize {
     updates
-    .split("\n")
-    .map(|update| update
-        .split(",")
-        .map(|page| page.parse::<usize>().unwrap())
-        .collect::<Vec<usize>>())
+    .iter()
     .filter(|update| update.windows(2).all(|window| sorter.all_sorted(window)))
-    .inspect(|update| println!("update is ordered: {:?}", update))
+    .inspect(|update| debug!("update is ordered: {:?}", update))
     .map(|update| *update.get(update.len() / 2).unwrap())
     .sum()
 }
@@ -112,11 +155,13 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        assert_eq!(calc_ordered_middle_sum("./input_test/day_05.txt"), 143);
+        let (updates, sorter) = Day05::load("./input_test/day_05.txt").unwrap();
+        assert_eq!(calc_ordered_middle_sum(&updates, &sorter), 143);
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(calc_unordered_middle_sum("./input_test/day_05.txt"), 123);
+        let (updates, sorter) = Day05::load("./input_test/day_05.txt").unwrap();
+        assert_eq!(calc_unordered_middle_sum(&updates, &sorter), 123);
     }
 }
\ No newline at end of file
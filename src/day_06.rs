@@ -1,154 +1,178 @@
-use std::{collections::{HashMap, HashSet}, fs, ops::Add};
+use std::collections::HashSet;
 
-// Main function
-pub fn day_06() {
-    let input_file = "./input/day_06.txt";
+use crate::{grid::{Direction, Grid, Point}, params::Params, parse::{ParseError, Source}, random::Rng, solution::Solution, visualize::{Color, Renderer}};
 
-    println!("Puzzle 1 result: {}", puzzle1(input_file));
-    println!("Puzzle 2 result: {}", puzzle2(input_file));
+type Marks = HashSet<(Point, Direction)>;
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = (Point, Grid<char>);
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(source: &Source, _params: &Params) -> Result<(Point, Grid<char>), ParseError> {
+        calc_map(source)
+    }
+
+    fn part1(input: &(Point, Grid<char>)) -> usize {
+        puzzle1(input)
+    }
+
+    fn part2(input: &(Point, Grid<char>)) -> usize {
+        puzzle2(input)
+    }
+
+    fn visualize((start_pos, map): &(Point, Grid<char>), renderer: &mut Renderer) -> bool {
+        let mut visited = map.map(|_| false);
+        let (mut pos, mut dir) = (*start_pos, Direction::Up);
+        let mut steps = 0;
+        loop {
+            visited[pos] = true;
+            renderer.frame(map, Some(pos), &format!("Step {}", steps), |point, &char| match char {
+                _ if point == pos => (dir.arrow(), Color::Red),
+                '#' => ('#', Color::Gray),
+                _ if visited[point] => ('X', Color::Yellow),
+                _ => ('.', Color::Plain),
+            });
+            match map.get(pos + dir) {
+                Some('#') => dir = dir.turn_right(),
+                Some(_) => {
+                    pos += dir;
+                    steps += 1;
+                },
+                None => return true
+            }
+        }
+    }
+
+    fn generate(rng: &mut Rng, size: usize, _params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(Some(generate_lab(rng, size)))
+    }
 }
 
 // Puzzle 1 function
-fn puzzle1(input_file: &str) -> usize {
-    let input = fs::read_to_string(input_file).expect("Failed to read input file");
-    let (start_pos, map) = calc_map(input);
-    let result = walk(start_pos, &map, HashSet::new(), (0, -1));
+fn puzzle1((start_pos, map): &(Point, Grid<char>)) -> usize {
+    let result = walk(*start_pos, map);
     result.len()
 }
 
-fn calc_map(input: String) -> ((usize, usize), HashMap<(usize, usize), char>) {
-    let mut start_pos = (0,0);
-    let mut map = HashMap::new();
-
-    for (y, line) in input.lines().enumerate() {
-        for (x, char) in line.chars().enumerate() {
-            if char == '^' {
-                start_pos = (x, y);
-            }
-            map.insert((x, y), char);
-        }
+fn calc_map(source: &Source) -> Result<(Point, Grid<char>), ParseError> {
+    let map = Grid::parse(source, &source.text)?;
+    let start_pos = map
+        .position(|&char| char == '^')
+        .ok_or_else(|| source.missing(&source.text, "the guard `^`"))?;
+    if let Some(Point(x, y)) = find_loop(start_pos, &map) {
+        // the guard has to leave the lab, or there is no route to count for part 1
+        let line = source.text.lines().nth(y as usize).unwrap_or_default();
+        let obstacle = line.get(x as usize..x as usize + 1).unwrap_or(line);
+        return Err(source.error(obstacle, "an obstacle that doesn't trap the guard in a loop"));
     }
-    (start_pos, map)
+    Ok((start_pos, map))
 }
 
-fn walk(
-    start_pos: (usize, usize),
-    map: &HashMap<(usize, usize), char>,
-    mut marks: HashSet<(usize, usize)>,
-    dir: (i32, i32)
-) -> HashSet<(usize, usize)> {
-    marks.insert(start_pos);
-
-    let x = (start_pos.0 as i32) + dir.0;
-    let y = (start_pos.1 as i32) + dir.1;
-    if x < 0 || y < 0 {
-        return marks;
+/// A `size` by `size` lab with an obstacle on one in 20 tiles. Obstacles that would trap the
+/// guard in a loop are taken out again, as the guard has to leave the lab for part 1.
+fn generate_lab(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let mut map = Grid::from_fn(size, size, |_| if rng.chance(1, 20) { '#' } else { '.' });
+    let start = Point(rng.below(size) as isize, rng.below(size) as isize);
+    map[start] = '^';
+    while let Some(obstacle) = find_loop(start, &map) {
+        map[obstacle] = '.';
     }
-    let new_pos = (x as usize, y as usize);
-    
-    match map.get(&new_pos) {
-        Some('#') => {
-            walk(start_pos, map, marks, rotate(dir))
-        },
-        Some(_) => {
-            walk(new_pos, map, marks, dir)
-        },
-        None => marks
+    map.to_string()
+}
+
+/// The obstacle at which the guard starting at `start` turns the same way a second time, if the guard walks in a loop.
+fn find_loop(start: Point, map: &Grid<char>) -> Option<Point> {
+    let mut turns = HashSet::new();
+    let (mut pos, mut dir) = (start, Direction::Up);
+    loop {
+        match map.get(pos + dir) {
+            Some('#') if !turns.insert((pos, dir)) => return Some(pos + dir),
+            Some('#') => dir = dir.turn_right(),
+            Some(_) => pos += dir,
+            None => return None,
+        }
     }
 }
 
-fn rotate(dir: (i32, i32)) -> (i32, i32) {
-    match dir {
-        (0, 1) => (-1, 0),
-        (0, -1) => (1, 0),
-        (1, 0) => (0, 1),
-        (-1, 0) => (0, -1),
-        _ => panic!("Invalid direction rotation: {:?}", dir)
+fn walk(start_pos: Point, map: &Grid<char>) -> HashSet<Point> {
+    let mut marks = HashSet::new();
+    let (mut pos, mut dir) = (start_pos, Direction::Up);
+    loop {
+        marks.insert(pos);
+        match map.get(pos + dir) {
+            Some('#') => dir = dir.turn_right(),
+            Some(_) => pos += dir,
+            None => return marks
+        }
     }
 }
 
 // Puzzle 2 function
-fn puzzle2(input_file: &str) -> usize {
-    let input = fs::read_to_string(input_file).expect("Failed to read input file");
-    let (start_pos, map) = calc_map(input);
-    let mut options = walk_dir(start_pos, &map, HashSet::new(), (0, -1), HashSet::new());
-    options.remove(&start_pos);
+fn puzzle2((start_pos, map): &(Point, Grid<char>)) -> usize {
+    let mut options = walk_dir(*start_pos, map);
+    options.remove(start_pos);
     options.len()
 }
 
-fn walk_dir(
-    start_pos: (usize, usize),
-    map: &HashMap<(usize, usize), char>,
-    mut marks: HashSet<((usize, usize), (i32, i32))>,
-    dir: (i32, i32),
-    mut options: HashSet<(usize, usize)>
-) -> HashSet<(usize, usize)> {
-    marks.insert((start_pos, dir));
-
-    let x = (start_pos.0 as i32) + dir.0;
-    let y = (start_pos.1 as i32) + dir.1;
-    if x < 0 || y < 0 {
-        return options;
-    }
-    let new_pos = (x as usize, y as usize);
-    
-    match map.get(&new_pos) {
-        Some('#') => {
-            walk_dir(start_pos, map, marks, rotate(dir), options)
-        },
-        Some(_) => {
-            // try walk right
-            if !options.contains(&new_pos)
-                && !contains_any_dir(new_pos, &marks)
-                && returns_to_path(start_pos, map, &marks, rotate(dir), HashSet::new(), new_pos) {
-                options.insert(new_pos);
-            }
-            walk_dir(new_pos, map, marks, dir, options)
-        },
-        None => options
+fn walk_dir(start_pos: Point, map: &Grid<char>) -> HashSet<Point> {
+    let mut marks = Marks::new();
+    let mut options = HashSet::new();
+    let (mut pos, mut dir) = (start_pos, Direction::Up);
+    loop {
+        marks.insert((pos, dir));
+
+        let new_pos = pos + dir;
+        match map.get(new_pos) {
+            Some('#') => dir = dir.turn_right(),
+            Some(_) => {
+                // try walk right
+                if !options.contains(&new_pos)
+                    && !contains_any_dir(new_pos, &marks)
+                    && returns_to_path(pos, map, &marks, dir.turn_right(), new_pos) {
+                    options.insert(new_pos);
+                }
+                pos = new_pos;
+            },
+            None => return options
+        }
     }
 }
 
-fn contains_any_dir(check_pos: (usize, usize), marks: &HashSet<((usize, usize), (i32, i32))>) -> bool {
-    let checks = [(0, 1), (0, -1), (1, 0), (-1, 0)];
-    for dir in checks {
-        if marks.contains(&(check_pos, dir)) {
-            return true;
-        }
-    }
-    false
+fn contains_any_dir(check_pos: Point, marks: &Marks) -> bool {
+    Direction::ALL
+        .into_iter()
+        .any(|dir| marks.contains(&(check_pos, dir)))
 }
 
 fn returns_to_path(
-    start_pos: (usize, usize),
-    map: &HashMap<(usize, usize), char>,
-    marks: &HashSet<((usize, usize), (i32, i32))>,
-    dir: (i32, i32),
-    mut visited_check: HashSet<((usize, usize), (i32, i32))>,
-    option: (usize, usize)
+    start_pos: Point,
+    map: &Grid<char>,
+    marks: &Marks,
+    dir: Direction,
+    option: Point
 ) -> bool {
-    let start_entry = (start_pos, dir);
-    if visited_check.contains(&start_entry) || marks.contains(&start_entry) {
-        return true;
-    }
-    visited_check.insert(start_entry);
-    let x = (start_pos.0 as i32) + dir.0;
-    let y = (start_pos.1 as i32) + dir.1;
-    if x < 0 || y < 0 {
-        return false;
-    }
-    let new_pos = (x as usize, y as usize);
-    if new_pos == option {
-        return returns_to_path(start_pos, map, marks, rotate(dir), visited_check, option);
-    }
-    match map.get(&new_pos) {
-        Some('#') => {
-            returns_to_path(start_pos, map, marks, rotate(dir), visited_check, option)
-        },
-        Some(_) => {
-            returns_to_path(new_pos, map, marks, dir, visited_check, option)
-        },
-        None => false
+    let mut visited_check = Marks::new();
+    let (mut pos, mut dir) = (start_pos, dir);
+    loop {
+        let entry = (pos, dir);
+        if visited_check.contains(&entry) || marks.contains(&entry) {
+            return true;
+        }
+        visited_check.insert(entry);
+        let new_pos = pos + dir;
+        if new_pos == option {
+            dir = dir.turn_right();
+            continue;
+        }
+        match map.get(new_pos) {
+            Some('#') => dir = dir.turn_right(),
+            Some(_) => pos = new_pos,
+            None => return false
+        }
     }
 }
 
@@ -159,12 +183,18 @@ mod tests {
     #[test]
     fn test_puzzle1() {
         // Replace with proper test logic
-        assert_eq!(puzzle1("./input_test/day_06.txt"), 41);
+        assert_eq!(puzzle1(&Day06::load("./input_test/day_06.txt").unwrap()), 41);
     }
 
     #[test]
     fn test_puzzle2() {
         // Replace with proper test logic
-        assert_eq!(puzzle2("./input_test/day_06.txt"), 6);
+        assert_eq!(puzzle2(&Day06::load("./input_test/day_06.txt").unwrap()), 6);
+    }
+
+    #[test]
+    fn test_parse_rejects_looping_guard() {
+        let error = Day06::parse(&Source::new("loop", ".#..\n...#\n#^..\n..#.\n"), &Day06::params()).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 2));
     }
 }
@@ -1,6 +1,6 @@
-use std::fs;
+use crate::{params::Params, parse::{ParseError, Source}, random::Rng, solution::Solution};
 
-struct Equations {
+pub struct Equations {
     result: usize,
     numbers: Vec<usize>
 }
@@ -20,7 +20,7 @@ impl Operation {
                 .any(|op| op
                     .safe_apply(cur_total, cur_num)
                     .filter(|&applied| applied <= eq.result)
-                    .map_or(false, |applied| op
+                    .is_some_and(|applied| op
                         .equals_total(eq, index + 1, applied, ops)
                     )
                 )
@@ -44,38 +44,50 @@ impl Equations {
     }
 }
 
-impl FromIterator<String> for Equations {
-    fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
-        let mut items = iter.into_iter();
-        let result = items
-            .next()
-            .expect("Expecting result in line")
-            .parse()
-            .expect("Expecting value to be parsable to usize!");
-        let numbers = items
-            .next()
-            .expect("Expecting tokens after result!")
+impl Equations {
+    fn parse(source: &Source, line: &str) -> Result<Equations, ParseError> {
+        let (result, numbers) = source.split_once(line, ": ")?;
+        let numbers = numbers
             .split_whitespace()
-            .map(|token| token.parse().expect("Expecting token to be parsable to usize!"))
-            .collect();
-        Equations {
-            result,
-            numbers
+            .map(|token| source.number(token))
+            .collect::<Result<Vec<usize>, _>>()?;
+        if numbers.is_empty() {
+            return Err(source.missing(line, "numbers after the result"));
         }
+        Ok(Equations {
+            result: source.number(result)?,
+            numbers
+        })
     }
 }
 
-// Main function
-pub fn day_07() {
-    let input_file = "./input/day_07.txt";
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Vec<Equations>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(source: &Source, _params: &Params) -> Result<Vec<Equations>, ParseError> {
+        parse_to_equations(source)
+    }
+
+    fn part1(input: &Vec<Equations>) -> usize {
+        puzzle1(input)
+    }
+
+    fn part2(input: &Vec<Equations>) -> usize {
+        puzzle2(input)
+    }
 
-    println!("Puzzle 1 result: {}", puzzle1(input_file));
-    println!("Puzzle 2 result: {}", puzzle2(input_file));
+    fn generate(rng: &mut Rng, size: usize, _params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(Some(generate_equations(rng, size)))
+    }
 }
 
 // Puzzle 1 function
-fn puzzle1(input_file: &str) -> usize {
-    parse_to_equations(input_file)
+fn puzzle1(equations: &[Equations]) -> usize {
+    equations
         .iter()
         .filter_map(|eq| eq
             .is_solvable_with(&[Operation::Mul, Operation::Add])
@@ -85,8 +97,8 @@ fn puzzle1(input_file: &str) -> usize {
 }
 
 // Puzzle 2 function
-fn puzzle2(input_file: &str) -> usize {
-    parse_to_equations(input_file)
+fn puzzle2(equations: &[Equations]) -> usize {
+    equations
         .iter()
         .filter_map(|eq| eq
             .is_solvable_with(&[Operation::Mul, Operation::Add, Operation::Pipe])
@@ -95,11 +107,37 @@ fn puzzle2(input_file: &str) -> usize {
         .sum()
 }
 
-fn parse_to_equations(path: &str) -> Vec<Equations> {
-    fs::read_to_string(path)
-        .expect("Failed to read input file")
+fn parse_to_equations(source: &Source) -> Result<Vec<Equations>, ParseError> {
+    source.text
         .lines()
-        .map(|line| line.split(": ").map(String::from).collect())
+        .map(|line| Equations::parse(source, line))
+        .collect()
+}
+
+/// `size` equations of two to ten mostly single digit numbers. Half of the results are worked
+/// out with random operators, so those equations can be solved.
+fn generate_equations(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let numbers: Vec<usize> = (0..rng.range(2..=10))
+                .map(|_| match rng.below(10) {
+                    0 => rng.range(100..=999),
+                    1 | 2 => rng.range(10..=99),
+                    _ => rng.range(1..=9),
+                })
+                .collect();
+            let result = rng
+                .chance(1, 2)
+                .then(|| numbers[1..].iter().try_fold(numbers[0], |total, &number| match rng.below(3) {
+                    0 => total.checked_add(number),
+                    1 => total.checked_mul(number),
+                    _ => format!("{}{}", total, number).parse().ok(),
+                }))
+                .flatten()
+                .unwrap_or_else(|| rng.range(1..=1_000_000));
+            let numbers: Vec<String> = numbers.iter().map(|number| number.to_string()).collect();
+            format!("{}: {}\n", result, numbers.join(" "))
+        })
         .collect()
 }
 
@@ -109,11 +147,11 @@ mod tests {
 
     #[test]
     fn test_puzzle1() {
-        assert_eq!(puzzle1("./input_test/day_07.txt"), 3749);
+        assert_eq!(puzzle1(&Day07::load("./input_test/day_07.txt").unwrap()), 3749);
     }
 
     #[test]
     fn test_puzzle2() {
-        assert_eq!(puzzle2("./input_test/day_07.txt"), 11387);
+        assert_eq!(puzzle2(&Day07::load("./input_test/day_07.txt").unwrap()), 11387);
     }
 }
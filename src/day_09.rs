@@ -1,4 +1,6 @@
-use std::{collections::VecDeque, fs, vec};
+use std::collections::VecDeque;
+
+use crate::{params::Params, parse::{ParseError, Source}, random::Rng, solution::Solution};
 
 #[derive(Clone, Debug)]
 struct DiskSpace {
@@ -17,20 +19,61 @@ impl DiskSpace {
     }
 }
 
-// Main function
-pub fn day_09() {
-    let input_file = "./input/day_09.txt";
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = Vec<usize>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(source: &Source, _params: &Params) -> Result<Vec<usize>, ParseError> {
+        let disk_map = source.text.trim_end();
+        disk_map
+            .char_indices()
+            .map(|(index, char)| source.parse(&disk_map[index..index + char.len_utf8()], "a digit"))
+            .collect()
+    }
 
-    println!("Puzzle 1 result: {}", puzzle1(input_file));
-    println!("Puzzle 2 result: {}", puzzle2(input_file));
+    fn part1(input: &Vec<usize>) -> usize {
+        puzzle1(input)
+    }
+
+    fn part2(input: &Vec<usize>) -> usize {
+        puzzle2(input)
+    }
+
+    fn generate(rng: &mut Rng, size: usize, _params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(Some(generate_disk_map(rng, size)))
+    }
+}
+
+/// A disk map of `size` digits: files of one to nine blocks with up to nine free blocks after each.
+fn generate_disk_map(rng: &mut Rng, size: usize) -> String {
+    let mut disk_map: String = (0..size)
+        .map(|index| {
+            let blocks = if index % 2 == 0 { rng.range(1..=9) } else { rng.range(0..=9) };
+            char::from_digit(blocks as u32, 10).unwrap()
+        })
+        .collect();
+    disk_map.push('\n');
+    disk_map
 }
 
 // Puzzle 1 function
-fn puzzle1(input_file: &str) -> usize {
-    let mut entries: VecDeque<Option<usize>> = fs::read_to_string(input_file)
-        .expect("Failed to read input file")
-        .chars()
-        .map(|char| char.to_digit(10).expect("Expecting all chars to be digits") as usize)
+fn puzzle1(disk_map: &[usize]) -> usize {
+    compact_blocks(disk_map, |_, _| {})
+        .iter()
+        .enumerate()
+        .map(|(index, file_id)| index * file_id)
+        .sum()
+}
+
+/// Moves file blocks one at a time from the end of the disk to the leftmost free block, calling
+/// `on_move` with the blocks in place so far and the rest of the disk after every move.
+fn compact_blocks(disk_map: &[usize], mut on_move: impl FnMut(&[usize], &VecDeque<Option<usize>>)) -> Vec<usize> {
+    let mut entries: VecDeque<Option<usize>> = disk_map
+        .iter()
+        .copied()
         .enumerate()
         .flat_map(|(file_id, count)| {
             if file_id % 2 == 0 {
@@ -50,25 +93,30 @@ fn puzzle1(input_file: &str) -> usize {
             while let Some(last_entry) = entries.pop_back() {
                 if let Some(last_entry_file_id) = last_entry {
                     populated.push(last_entry_file_id);
+                    on_move(&populated, &entries);
                     break;
                 }
             }  
         }
     }
-
     populated
+}
+
+// Puzzle 2 function
+fn puzzle2(disk_map: &[usize]) -> usize {
+    compact_files(disk_map, |_, _| {})
         .iter()
-        .enumerate()
-        .map(|(index, file_id)| index * file_id)
+        .flat_map(|entry| (0..entry.size)
+            .map(|id| (entry.start + id) * entry.file_id.unwrap()))
         .sum()
 }
 
-// Puzzle 2 function
-fn puzzle2(input_file: &str) -> usize {
-    let (_, mut entries, mut empty_spaces) = fs::read_to_string(input_file)
-        .expect("Failed to read input file")
-        .chars()
-        .map(|char| char.to_digit(10).expect("Expecting all chars to be digits") as usize)
+/// Moves whole files, highest id first, to the leftmost free space they fit in, calling
+/// `on_move` with the id of every file that moved and all files after it did.
+fn compact_files(disk_map: &[usize], mut on_move: impl FnMut(usize, &[DiskSpace])) -> Vec<DiskSpace> {
+    let (_, mut entries, mut empty_spaces) = disk_map
+        .iter()
+        .copied()
         .enumerate()
         .fold((0, vec![], vec![]), |(index, mut entries, mut empty_spaces), (start, size)| {
             let file_id = start / 2;
@@ -81,7 +129,8 @@ fn puzzle2(input_file: &str) -> usize {
         });
     
 
-    for entry in entries.iter_mut().rev() {
+    for index in (0..entries.len()).rev() {
+        let entry = &mut entries[index];
         if let Some(empty_space) = empty_spaces
             .iter_mut()
             .find(|space| space.size >= entry.size && space.start < entry.start)
@@ -89,26 +138,85 @@ fn puzzle2(input_file: &str) -> usize {
             entry.start = empty_space.start;
             empty_space.size -= entry.size;
             empty_space.start += entry.size;
+            let file_id = entry.file_id.unwrap();
+            on_move(file_id, &entries);
         }
     }
     entries
-        .iter()
-        .flat_map(|entry| (0..entry.size)
-            .map(|id| (entry.start + id) * entry.file_id.unwrap()))
-        .sum()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::snapshot::Snapshot;
 
     #[test]
     fn test_puzzle1() {
-        assert_eq!(puzzle1("./input_test/day_09.txt"), 1928);
+        assert_eq!(puzzle1(&Day09::load("./input_test/day_09.txt").unwrap()), 1928);
     }
 
     #[test]
     fn test_puzzle2() {
-        assert_eq!(puzzle2("./input_test/day_09.txt"), 2858);
+        assert_eq!(puzzle2(&Day09::load("./input_test/day_09.txt").unwrap()), 2858);
+    }
+
+    /// The disk the way the puzzle draws it, with the last digit of each file id.
+    fn render(blocks: impl IntoIterator<Item = Option<usize>>, size: usize) -> String {
+        let mut disk: String = blocks
+            .into_iter()
+            .map(|block| block.map_or('.', |file_id| char::from_digit((file_id % 10) as u32, 10).unwrap()))
+            .collect();
+        disk.extend(std::iter::repeat_n('.', size - disk.len()));
+        disk
+    }
+
+    fn render_files(files: &[DiskSpace], size: usize) -> String {
+        let mut blocks = vec![None; size];
+        for file in files {
+            blocks[file.start..file.start + file.size].fill(file.file_id);
+        }
+        render(blocks, size)
+    }
+
+    /// The blocks of the disk before compacting, `None` where they're free.
+    fn layout(disk_map: &[usize]) -> Vec<Option<usize>> {
+        disk_map
+            .iter()
+            .enumerate()
+            .flat_map(|(index, &count)| std::iter::repeat_n((index % 2 == 0).then_some(index / 2), count))
+            .collect()
+    }
+
+    #[test]
+    fn test_block_moves() {
+        let disk_map = Day09::load("./input_test/day_09.txt").unwrap();
+        let size = disk_map.iter().sum();
+        let mut snapshot = Snapshot::new();
+        snapshot.step("Initial disk", render(layout(&disk_map), size));
+        let mut moves = 0;
+        compact_blocks(&disk_map, |populated, rest| {
+            moves += 1;
+            let blocks = populated.iter().map(|&file_id| Some(file_id)).chain(rest.iter().copied());
+            snapshot.step(format_args!("Block move {}", moves), render(blocks, size));
+        });
+        snapshot.assert("day_09_blocks");
+    }
+
+    #[test]
+    fn test_file_moves() {
+        let disk_map = Day09::load("./input_test/day_09.txt").unwrap();
+        let size = disk_map.iter().sum();
+        let mut snapshot = Snapshot::new();
+        snapshot.step("Initial disk", render(layout(&disk_map), size));
+        compact_files(&disk_map, |file_id, files| {
+            snapshot.step(format_args!("Move file {}", file_id), render_files(files, size));
+        });
+        snapshot.assert("day_09_files");
+    }
+
+    #[test]
+    fn test_small_disk() {
+        // 0..111....22222 compacts to 022111222 block by block, but no file fits a gap whole
+        assert_eq!(Day09::solve("12345"), Ok((60, 132)));
     }
 }
@@ -1,115 +1,86 @@
-use std::{collections::{HashMap, HashSet}, fs, ops::{Add, Sub}};
+use crate::{grid::{Grid, Point}, params::Params, parse::{ParseError, Source}, random::Rng, search::{self, Search}, solution::Solution};
 
-#[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone)]
-struct Point(usize, usize);
+pub struct Day10;
 
-impl Point {
-    fn get_surroundings(&self) -> Vec<Point> {
-        [
-            self.checked_add(&Point(0, 1)),
-            self.checked_add(&Point(1, 0)),
-            self.checked_sub(&Point(0, 1)),
-            self.checked_sub(&Point(1, 0))
-        ]
-        .into_iter()
-        .flatten()
-        .collect()
-    }
+impl Solution for Day10 {
+    type Input = (Vec<Point>, Grid<u32>);
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-    fn checked_add(&self, rhs: &Point) -> Option<Point> {
-        let x = self.0.checked_add(rhs.0)?;
-        let y = self.1.checked_add(rhs.1)?;
-        Some(Point(x, y))
+    fn parse(source: &Source, _params: &Params) -> Result<(Vec<Point>, Grid<u32>), ParseError> {
+        read_map(source)
     }
 
-    fn checked_sub(&self, rhs: &Point) -> Option<Point> {
-        let x = self.0.checked_sub(rhs.0)?;
-        let y = self.1.checked_sub(rhs.1)?;
-        Some(Point(x, y))
+    fn part1(input: &(Vec<Point>, Grid<u32>)) -> usize {
+        puzzle1(input)
     }
-}
 
-// Main function
-pub fn day_10() {
-    let input_file = "./input/day_10.txt";
+    fn part2(input: &(Vec<Point>, Grid<u32>)) -> usize {
+        puzzle2(input)
+    }
 
-    println!("Puzzle 1 result: {}", puzzle1(input_file));
-    println!("Puzzle 2 result: {}", puzzle2(input_file));
+    fn generate(rng: &mut Rng, size: usize, _params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(Some(generate_topography(rng, size)))
+    }
 }
 
 // Puzzle 1 function
-fn puzzle1(input_file: &str) -> usize {
-    let (nines, map) = read_map(input_file);
+fn puzzle1((nines, map): &(Vec<Point>, Grid<u32>)) -> usize {
     nines
         .iter()
-        .flat_map(|nine_coords| count_surrounding_lowers(9, nine_coords, &map))
-        .count()
+        .map(|&nine| trailheads(&search_down_from(nine, map), map).count())
+        .sum()
 }
 
-fn read_map(input_file: &str) -> (Vec<Point>, HashMap<Point, u32>) {
-    fs::read_to_string(input_file)
-        .expect("Failed to read input file")
-        .lines()
-        .enumerate()
-        .flat_map(move |(y, line)| 
-            line
-                .chars()
-                .map(|char| 
-                    char
-                        .to_digit(10)
-                        .expect("Expecting char to be digit!")
-                )
-                .enumerate()
-                .map(move |(x, height)| (Point(x, y), height))
-        )
-        .fold((vec![], HashMap::new()), |(mut nines, mut map), (coords, height)| {
-            if height == 9 {
-                nines.push(coords);
-            }
-            map.insert(coords, height);
-            (nines, map)
-        })
+/// Follows the map down from `nine` one height at a time, so every path the search finds is a
+/// trail and all of them are equally long.
+fn search_down_from(nine: Point, map: &Grid<u32>) -> Search<Point, usize> {
+    let lower = |&point: &Point| map.neighbors4(point).filter(move |&next| map[next] + 1 == map[point]);
+    search::bfs([nine], lower, |_| false)
 }
 
-fn count_surrounding_lowers(cur_height: u32, cur_coords: &Point, map: &HashMap<Point, u32>) -> HashSet<Point> {
-    if cur_height == 0 {
-        return HashSet::from([*cur_coords]);
-    }
-    cur_coords
-        .get_surroundings()
-        .iter()
-        .filter_map(|coord| 
-            map
-                .get(coord)
-                .filter(|&&height| cur_height - 1 == height)
-                .map(|&height| count_surrounding_lowers(height, coord, map))
-        )
-        .flatten()
-        .collect()
+fn trailheads<'a>(search: &'a Search<Point, usize>, map: &'a Grid<u32>) -> impl Iterator<Item = &'a Point> {
+    search.reached().map(|(point, _)| point).filter(|&&point| map[point] == 0)
 }
 
-// Puzzle 2 function
-fn puzzle2(input_file: &str) -> usize {
-    let (nines, map) = read_map(input_file);
-    nines
+fn read_map(source: &Source) -> Result<(Vec<Point>, Grid<u32>), ParseError> {
+    let map = Grid::parse_with(source, &source.text, |cell| source.parse(cell, "a height digit"))?;
+    let nines = map
         .iter()
-        .map(|nine_coords| count_surrounding_lowers_unique(9, nine_coords, &map))
-        .sum()
+        .filter(|(_, &height)| height == 9)
+        .map(|(coords, _)| coords)
+        .collect();
+    Ok((nines, map))
 }
 
-fn count_surrounding_lowers_unique(cur_height: u32, cur_coords: &Point, map: &HashMap<Point, u32>) -> usize {
-    if cur_height == 0 {
-        return 1;
+/// A `size` by `size` map where most tiles are one higher than the tile left of or above them,
+/// so there are plenty of trails.
+fn generate_topography(rng: &mut Rng, size: usize) -> String {
+    let mut map = Grid::new(size, size, 0);
+    for y in 0..size as isize {
+        for x in 0..size as isize {
+            let lower: Vec<u32> = [Point(x - 1, y), Point(x, y - 1)]
+                .into_iter()
+                .filter_map(|point| map.get(point).copied())
+                .collect();
+            map[Point(x, y)] = if lower.is_empty() || rng.chance(1, 4) {
+                rng.below(10) as u32
+            } else {
+                (rng.choose(&lower) + 1) % 10
+            };
+        }
     }
-    cur_coords
-        .get_surroundings()
+    map.map(|&height| char::from_digit(height, 10).unwrap()).to_string()
+}
+
+// Puzzle 2 function
+fn puzzle2((nines, map): &(Vec<Point>, Grid<u32>)) -> usize {
+    nines
         .iter()
-        .filter_map(|coord| 
-            map
-                .get(coord)
-                .filter(|&&height| cur_height - 1 == height)
-                .map(|&height| count_surrounding_lowers_unique(height, coord, map))
-        )
+        .map(|&nine| {
+            let search = search_down_from(nine, map);
+            trailheads(&search, map).map(|trailhead| search.count_paths_to(trailhead)).sum::<usize>()
+        })
         .sum()
 }
 
@@ -119,11 +90,11 @@ mod tests {
 
     #[test]
     fn test_puzzle1() {
-        assert_eq!(puzzle1("./input_test/day_10.txt"), 36);
+        assert_eq!(puzzle1(&Day10::load("./input_test/day_10.txt").unwrap()), 36);
     }
 
     #[test]
     fn test_puzzle2() {
-        assert_eq!(puzzle2("./input_test/day_10.txt"), 81);
+        assert_eq!(puzzle2(&Day10::load("./input_test/day_10.txt").unwrap()), 81);
     }
 }
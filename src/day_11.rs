@@ -1,7 +1,9 @@
-use std::{collections::HashMap, fs};
+use std::collections::HashMap;
+
+use crate::{params::Params, parse::{ParseError, Source}, random::Rng, solution::Solution};
 
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
-enum Stone {
+pub enum Stone {
     Zero,
     Split(usize, usize),
     Multiply(usize)
@@ -12,8 +14,8 @@ impl Stone {
         if num == 0 {
             Stone::Zero
         } else {
-            let num_digits = (num as f64).log10().floor() as usize + 1;
-            if num_digits % 2 == 0 {
+            let num_digits = num.ilog10() as usize + 1;
+            if num_digits.is_multiple_of(2) {
                 let half_size = num_digits / 2;
                 let divisor = 10_usize.pow(half_size as u32);
                 Stone::Split(num / divisor, num % divisor)
@@ -27,7 +29,7 @@ impl Stone {
         match self {
             Stone::Zero => vec![Stone::from(1)],
             Stone::Split(l, r) => vec![Stone::from(*l), Stone::from(*r)],
-            Stone::Multiply(m) => vec![Stone::from(*m * 2024)],
+            Stone::Multiply(m) => vec![Stone::from(m.checked_mul(2024).expect("Expecting stones up to MAX_STONE not to overflow"))],
         }
     }
 
@@ -61,42 +63,93 @@ impl Stone {
     }
 }
 
-// Main function
-pub fn day_11() {
-    let input_file = "./input/day_11.txt";
+/// The stones in a row, and how often to blink at them in each part.
+#[derive(Debug)]
+pub struct Arrangement {
+    stones: Vec<Stone>,
+    blinks1: usize,
+    blinks2: usize,
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Arrangement;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("blinks1", "25"), ("blinks2", "75")];
+
+    fn parse(source: &Source, params: &Params) -> Result<Arrangement, ParseError> {
+        Ok(Arrangement {
+            stones: read_to_stones(source)?,
+            blinks1: params.get("blinks1")?,
+            blinks2: params.get("blinks2")?,
+        })
+    }
+
+    fn part1(input: &Arrangement) -> usize {
+        puzzle1(input)
+    }
+
+    fn part2(input: &Arrangement) -> usize {
+        puzzle2(input)
+    }
 
-    println!("Puzzle 1 result: {}", puzzle1(input_file));
-    println!("Puzzle 2 result: {}", puzzle2(input_file));
+    fn generate(rng: &mut Rng, size: usize, _params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(Some(generate_stones(rng, size)))
+    }
 }
 
 // Puzzle 1 function
-fn puzzle1(input_file: &str) -> usize {
-    read_to_stones(input_file)
+fn puzzle1(arrangement: &Arrangement) -> usize {
+    arrangement.stones
         .iter()
-        .flat_map(|stone| stone.step_times(25))
+        .flat_map(|stone| stone.step_times(arrangement.blinks1))
         .count()
 }
 
 // Puzzle 2 function
-fn puzzle2(input_file: &str) -> usize {
+fn puzzle2(arrangement: &Arrangement) -> usize {
     let mut stone_paths: HashMap<(Stone, usize), usize> = HashMap::new();
-    read_to_stones(input_file)
+    arrangement.stones
         .iter()
-        .map(|stone| stone.step_mapped(&mut stone_paths, 75))
+        .map(|stone| stone.step_mapped(&mut stone_paths, arrangement.blinks2))
         .sum()
 }
 
-fn read_to_stones(input_file: &str) -> Vec<Stone> {
-    fs::read_to_string(input_file)
-        .expect("Failed to read input file")
+/// Stones with more digits could overflow while blinking: an odd number of digits grows by up to
+/// four when multiplied, and by three more if it's still odd, before it splits.
+const MAX_STONE: usize = 999_999_999_999;
+
+fn read_to_stones(source: &Source) -> Result<Vec<Stone>, ParseError> {
+    source.text
         .split_whitespace()
-        .map(|str| Stone::from(str.parse().expect("String must be usize!")))
+        .map(|str| {
+            let number = source.number(str)?;
+            if number > MAX_STONE {
+                return Err(source.error(str, format_args!("a number up to {}", MAX_STONE)));
+            }
+            Ok(Stone::from(number))
+        })
         .collect()
 }
 
+/// `size` stones engraved with numbers of one to seven digits.
+fn generate_stones(rng: &mut Rng, size: usize) -> String {
+    let stones: Vec<String> = (0..size)
+        .map(|_| {
+            let digits = rng.range(1..=7) as u32;
+            rng.below(10usize.pow(digits)).to_string()
+        })
+        .collect();
+    stones.join(" ") + "\n"
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::property::{self, Config};
 
     #[test]
     fn test_stone_from() {
@@ -118,13 +171,33 @@ mod tests {
         assert_eq!(Stone::Zero, step_2_2);
     }
 
+    #[test]
+    fn test_read_rejects_stones_that_overflow() {
+        let err = Day11::parse(&Source::new("test", "125 9999999999999999\n"), &Day11::params()).unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (5, "a number up to 999999999999"));
+        // the largest stones blink without overflowing, which they would within the first few blinks
+        let params = Day11::params().with("blinks2", 25).unwrap();
+        let arrangement = Day11::parse(&Source::new("test", "999999999999 99999999999 99999999999\n"), &params).unwrap();
+        assert!(puzzle2(&arrangement) > 0);
+    }
+
     #[test]
     fn test_puzzle1() {
-        assert_eq!(puzzle1("./input_test/day_11.txt"), 55312);
+        assert_eq!(puzzle1(&Day11::load("./input_test/day_11.txt").unwrap()), 55312);
     }
 
     #[test]
     fn test_puzzle2() {
-        assert_eq!(puzzle2("./input_test/day_11.txt"), 55312);
+        let params = Day11::params().with("blinks2", 25).unwrap();
+        assert_eq!(puzzle2(&Day11::load_with("./input_test/day_11.txt", &params).unwrap()), 55312);
+    }
+
+    #[test]
+    fn test_memoized_blinks_agree_with_naive() {
+        property::assert_agree::<Day11>("day_11_blinks", Config { cases: 20, size: 5 }, |arrangement| {
+            arrangement.stones.iter().try_for_each(|stone| {
+                property::agree("stones after 15 blinks", stone.step_mapped(&mut HashMap::new(), 15), stone.step_times(15).len())
+            })
+        });
     }
 }
@@ -1,136 +1,142 @@
-use std::{collections::{HashMap, HashSet, VecDeque}, fs, ops::Neg};
-
-#[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone, Debug)]
-struct Point(i32, i32);
-
-impl Point {
-    fn get_surroundings(&self) -> Vec<Option<Point>> {
-        let (right, bot, left, top) = self.get_surroundings_clockwise();
-        vec![
-            right,
-            bot,
-            left,
-            top,
-        ]
+use std::collections::HashSet;
+
+use crate::{grid::{Direction, Grid, Point}, params::Params, parse::{ParseError, Source}, random::Rng, search, solution::Solution, visualize::{Color, Renderer}};
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Grid<char>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    const QUERIES: &'static [(&'static str, &'static str)] = &[
+        ("region <x,y>", "The plant, area, perimeter, sides and prices of the region with the plot at x,y"),
+    ];
+
+    fn parse(source: &Source, _params: &Params) -> Result<Grid<char>, ParseError> {
+        read_to_map(source)
     }
 
-    fn get_surroundings_clockwise(&self) -> (Option<Point>, Option<Point>, Option<Point>, Option<Point>) {
-        (
-            self.checked_add(&Point(1, 0)),
-            self.checked_sub(&Point(0, 1)),
-            self.checked_sub(&Point(1, 0)),
-            self.checked_add(&Point(0, 1)),
-        )
+    fn part1(input: &Grid<char>) -> i32 {
+        puzzle1(input)
     }
 
-    fn checked_add(&self, rhs: &Point) -> Option<Point> {
-        let x = self.0.checked_add(rhs.0)?;
-        let y = self.1.checked_add(rhs.1)?;
-        Some(Point(x, y))
+    fn part2(input: &Grid<char>) -> i32 {
+        puzzle2(input)
     }
 
-    fn checked_sub(&self, rhs: &Point) -> Option<Point> {
-        let x = self.0.checked_sub(rhs.0)?;
-        let y = self.1.checked_sub(rhs.1)?;
-        Some(Point(x, y))
+    fn visualize(input: &Grid<char>, renderer: &mut Renderer) -> bool {
+        let regions = find_regions(input);
+        let mut filled = input.map(|_| false);
+        for (index, region) in regions.iter().enumerate() {
+            region.iter().for_each(|&point| filled[point] = true);
+            let plant = region.iter().next().map_or(' ', |&point| input[point]);
+            let caption = format!("Region {}/{}: {}, area {}", index + 1, regions.len(), plant, region.len());
+            renderer.frame(input, region.iter().next().copied(), &caption, |point, &plant| if filled[point] {
+                (plant, plant_color(plant))
+            } else {
+                ('.', Color::Plain)
+            });
+        }
+        true
+    }
+
+    fn query(input: &Grid<char>, name: &str, args: &[&str]) -> Result<String, String> {
+        match (name, args) {
+            ("region", [point]) => describe_region(input, point.parse()?),
+            _ => Err(String::from("usage: region <x,y>")),
+        }
     }
-}
 
-// Main function
-pub fn day_12() {
-    let input_file = "./input/day_12.txt";
+    fn generate(rng: &mut Rng, size: usize, _params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(Some(generate_garden(rng, size)))
+    }
+}
 
-    println!("Puzzle 1 result: {}", puzzle1(input_file));
-    println!("Puzzle 2 result: {}", puzzle2(input_file));
+/// Gives every plant type its own color, spread out so that neighboring letters look different.
+fn plant_color(plant: char) -> Color {
+    let index = (plant as u32 % 32) * 7;
+    let channel = |shift: u32| 70 + ((index * shift) % 186) as u8;
+    Color::Rgb(channel(3), channel(5), channel(11))
 }
 
 // Puzzle 1 function
-fn puzzle1(input_file: &str) -> i32 {
-    read_to_map(input_file)
+fn puzzle1(map: &Grid<char>) -> i32 {
+    find_regions(map)
         .into_iter()
-        .flat_map(|(_, mut points)| {
-            find_connected_regions(&mut points)
-            .into_iter()
-            .map(move |region| region.len() as i32 * count_region_borders(&region))
-        })
+        .map(|region| region.len() as i32 * count_region_borders(&region))
         .sum()
 }
 
 fn count_region_borders(points: &HashSet<Point>) -> i32 {
     points
         .iter()
-        .flat_map(|point| point.get_surroundings())
-        .filter(|point| !point.is_some_and(|point| points.contains(&point)))
+        .flat_map(|point| point.neighbors4())
+        .filter(|point| !points.contains(point))
         .count() as i32
 }
 
-fn find_connected_regions(points: &mut HashSet<Point>) -> Vec<HashSet<Point>> {
-    if points.len() == 0 {
-        return vec![];
+fn find_regions(map: &Grid<char>) -> Vec<HashSet<Point>> {
+    let mut visited = map.map(|_| false);
+    let mut regions = vec![];
+    for (start, plant) in map.iter() {
+        if visited[start] {
+            continue;
+        }
+        let same_plant = |&point: &Point| map.neighbors4(point).filter(|&next| map[next] == *plant);
+        let region: HashSet<Point> = search::bfs([start], same_plant, |_| false).reached().map(|(&point, _)| point).collect();
+        region.iter().for_each(|&point| visited[point] = true);
+        regions.push(region);
     }
-    let mut totals = vec![];
+    regions
+}
 
-    while points.len() > 0 {
-        totals.push(find_next_connected_region(points));
-    }
-    totals
+fn describe_region(map: &Grid<char>, point: Point) -> Result<String, String> {
+    let plant = map.get(point).ok_or(format!("{} is outside the garden", point))?;
+    let region = find_regions(map).into_iter().find(|region| region.contains(&point)).expect("Expecting every plot in a region");
+    let (area, perimeter, sides) = (region.len() as i32, count_region_borders(&region), count_region_sides(&region));
+    Ok(format!(
+        "Region of {} at {}: area {}, perimeter {}, {} sides, price {}, or {} with the bulk discount",
+        plant, point, area, perimeter, sides, area * perimeter, area * sides
+    ))
 }
 
-fn find_next_connected_region(points: &mut HashSet<Point>) -> HashSet<Point> {
-    let mut result = HashSet::new();
-    let first = *points.iter().next().unwrap();
-    points.remove(&first);
-    let mut queue = VecDeque::new();
-    queue.push_back(first);
-    result.insert(first);
-    while let Some(entry) = queue.pop_front() {
-        (queue, result) = entry
-            .get_surroundings()
-            .into_iter()
-            .flatten()
-            .flat_map(|point| points.remove(&point).then_some(point))
-            .fold((queue, result), |(mut queue, mut result), key_val| {
-                queue.push_back(key_val);
-                result.insert(key_val);
-                (queue, result)
-            });
-    }
-    result
+fn read_to_map(source: &Source) -> Result<Grid<char>, ParseError> {
+    Grid::parse(source, &source.text)
 }
 
-fn read_to_map(input_file: &str) -> HashMap<char, HashSet<Point>> {
-    fs::read_to_string(input_file)
-        .expect("Failed to read input file")
-        .lines()
-        .enumerate()
-        .fold(HashMap::new(), |map, (y, line)| {
-            line
-                .chars()
-                .enumerate()
-                .fold(map, |mut map, (x, char)| {
-                    let entry = map.entry(char).or_default();
-                    entry.insert(Point(x as i32, y as i32));
-                    map
-            })
-        })
+/// A `size` by `size` garden where most plots grow the plant left of or above them, so the
+/// regions come in all shapes.
+fn generate_garden(rng: &mut Rng, size: usize) -> String {
+    let mut garden = Grid::new(size, size, 'A');
+    for y in 0..size as isize {
+        for x in 0..size as isize {
+            let neighbors: Vec<char> = [Point(x - 1, y), Point(x, y - 1)]
+                .into_iter()
+                .filter_map(|point| garden.get(point).copied())
+                .collect();
+            garden[Point(x, y)] = if neighbors.is_empty() || rng.chance(1, 4) {
+                char::from(b'A' + rng.below(26) as u8)
+            } else {
+                *rng.choose(&neighbors)
+            };
+        }
+    }
+    garden.to_string()
 }
 
 // Puzzle 2 function
-fn puzzle2(input_file: &str) -> i32 {
-    read_to_map(input_file)
+fn puzzle2(map: &Grid<char>) -> i32 {
+    find_regions(map)
         .into_iter()
-        .flat_map(|(_, mut points)| {
-            find_connected_regions(&mut points)
-                .into_iter()
-                .map(move |region| region.len() as i32 * count_region_sides(&region))
-        })
+        .map(|region| region.len() as i32 * count_region_sides(&region))
         .sum()
 }
 
 fn count_region_sides(points: &HashSet<Point>) -> i32 {
     let mut border_points = find_border_points(points);
     let mut lines_result = vec![];
-    while border_points.len() > 0 {
+    while !border_points.is_empty() {
         check_line(&mut border_points, &mut lines_result);
     }
     lines_result.len() as i32
@@ -140,14 +146,10 @@ fn find_border_points(points: &HashSet<Point>) -> HashSet<(Point, Point)> {
     points
         .iter()
         .flat_map(|point| point
-            .get_surroundings()
-            .into_iter()
-            .map(|surr_point| surr_point
-                .filter(|surr_point| !points.contains(surr_point))
-                .map(|surr_point| (*point, surr_point))
-            )
+            .neighbors4()
+            .filter(|surr_point| !points.contains(surr_point))
+            .map(|surr_point| (*point, surr_point))
         )
-        .flatten()
         .collect()
 }
 
@@ -157,13 +159,8 @@ fn check_line(outer_points: &mut HashSet<(Point, Point)>, lines: &mut Vec<(Point
         .next()
         .unwrap();
     outer_points.remove(&start);
-    let up = Point(0, -1);
-    let down = Point(0, 1);
-    let left = Point(-1, 0);
-    let right = Point(1, 0);
-
     let vert_line = 
-        match (find_end_point(outer_points, start, up), find_end_point(outer_points, start, down)) {
+        match (find_end_point(outer_points, start, Direction::Up), find_end_point(outer_points, start, Direction::Down)) {
             (None, None) => None,
             (Some(start), Some(end)) => Some((start, end)),
             (None, Some(end))
@@ -171,7 +168,7 @@ fn check_line(outer_points: &mut HashSet<(Point, Point)>, lines: &mut Vec<(Point
         };
 
     let hori_line =
-        match (find_end_point(outer_points, start, left), find_end_point(outer_points, start, right)) {
+        match (find_end_point(outer_points, start, Direction::Left), find_end_point(outer_points, start, Direction::Right)) {
             (None, None) => None,
             (Some(start), Some(end)) => Some((start, end)),
             (None, Some(end))
@@ -181,19 +178,19 @@ fn check_line(outer_points: &mut HashSet<(Point, Point)>, lines: &mut Vec<(Point
     if hori_line.is_none() && vert_line.is_none() {
         lines.push((start.0, start.0));
     } else {
-        hori_line.map(|line| lines.push(line));
-        vert_line.map(|line| lines.push(line));
+        lines.extend(hori_line);
+        lines.extend(vert_line);
     }
     
 }
 
-fn find_end_point(points: &mut HashSet<(Point, Point)>, next_pos: (Point, Point), dir: Point) -> Option<Point> {
+fn find_end_point(points: &mut HashSet<(Point, Point)>, next_pos: (Point, Point), dir: Direction) -> Option<Point> {
     let mut end = None;
-    let mut next_pos = (next_pos.0.checked_add(&dir).unwrap(), next_pos.1.checked_add(&dir).unwrap());
+    let mut next_pos = (next_pos.0 + dir, next_pos.1 + dir);
     while let Some(&target) = points.get(&next_pos) {
         points.remove(&target);
         end = Some(target.0);
-        next_pos = (next_pos.0.checked_add(&dir).unwrap(), next_pos.1.checked_add(&dir).unwrap());
+        next_pos = (next_pos.0 + dir, next_pos.1 + dir);
     }
     end
 }
@@ -209,11 +206,22 @@ mod tests {
     }
     #[test]
     fn test_puzzle1() {
-        assert_eq!(puzzle1("./input_test/day_12.txt"), 1930);
+        assert_eq!(puzzle1(&Day12::load("./input_test/day_12_2.txt").unwrap()), 1930);
     }
 
     #[test]
     fn test_puzzle2() {
-        assert_eq!(puzzle2("./input_test/day_12.txt"), 368);
+        assert_eq!(puzzle2(&Day12::load("./input_test/day_12.txt").unwrap()), 368);
+    }
+
+    #[test]
+    fn test_query_region() {
+        let map = Day12::load("./input_test/day_12_2.txt").unwrap();
+        assert_eq!(
+            Day12::query(&map, "region", &["3,0"]),
+            Ok(String::from("Region of R at (3,0): area 12, perimeter 18, 10 sides, price 216, or 120 with the bulk discount"))
+        );
+        assert_eq!(Day12::query(&map, "region", &["10,0"]), Err(String::from("(10,0) is outside the garden")));
+        assert!(Day12::query(&map, "region", &[]).is_err());
     }
 }
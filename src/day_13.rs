@@ -1,7 +1,7 @@
-use std::{cmp::Ordering, fs, ops::{Add, Div, Mul, Rem}, usize};
+use crate::{params::Params, parse::{ParseError, Source}, random::Rng, solution::Solution};
 
 #[derive(Debug)]
-struct ClawMachine {
+pub struct ClawMachine {
     a: (usize, usize),
     b: (usize, usize),
     prize: (usize, usize)
@@ -16,11 +16,20 @@ impl ClawMachine {
         }
     } 
 
-    fn solve(&self) -> Option<usize> {
-        let ClawMachine{ a, b, prize } = self;
+    fn solve(&self) -> Option<u128> {
+        self.solve_moved(0)
+    }
+
+    /// The cheapest way to win with the prize `offset` further away on both axes.
+    fn solve_moved(&self, offset: usize) -> Option<u128> {
+        // the determinant and numerators may be negative, so solve in signed space; every value
+        // is below 2^32 and the offset below 2^64, so no product of them overflows
+        let signed = |(x, y): (usize, usize)| (x as i128, y as i128);
+        let (a, b) = (signed(self.a), signed(self.b));
+        let prize = (self.prize.0 as i128 + offset as i128, self.prize.1 as i128 + offset as i128);
         let det = a.0 * b.1 - a.1 * b.0;
         if det == 0 {
-            return None;
+            return solve_collinear(a, b, prize);
         }
 
         let num_a = prize.0 * b.1 - prize.1 * b.0;
@@ -29,110 +38,185 @@ impl ClawMachine {
             return None;
         }
 
-        let int_a = num_a / det;
-        let int_b = num_b / det;
+        let int_a = u128::try_from(num_a / det).ok()?;
+        let int_b = u128::try_from(num_b / det).ok()?;
     
-        Some(int_a as usize * 3 + int_b as usize)
-    }
-
-}
-
-impl FromIterator<String> for ClawMachine {
-    fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
-        let mut str_iter = iter.into_iter();
-
-        let str_a = str_iter
-            .next()
-            .expect("Expecting Button A");
-        let mut str_a = str_a
-            .split_whitespace()
-            .skip(2)
-            .map(|str| str
-                .replace(",", "")
-                .replace("X+", "")
-                .replace("Y+", ""));
-        let a = (
-            str_a.next().expect("Expecting X for A").parse().expect("Expecting X for A to be usize"),
-            str_a.next().expect("Expecting Y for A").parse().expect("Expecting Y for A to be usize")
-        );
-
-        let str_b = str_iter
-            .next()
-            .expect("Expecting Button B");
-        let mut str_b = str_b
-            .split_whitespace()
-            .skip(2)
-            .map(|str| str
-                .replace(",", "")
-                .replace("X+", "")
-                .replace("Y+", ""));
-        let b = (
-            str_b.next().expect("Expecting X for B").parse().expect("Expecting X for B to be usize"),
-            str_b.next().expect("Expecting Y for B").parse().expect("Expecting Y for B to be usize")
-        );
-
-        let str_prize = str_iter
-            .next()
-            .expect("Expecting prize");
-        let mut str_prize = str_prize
-            .split_whitespace()
-            .skip(1)
-            .map(|str| 
-                str
-                    .replace("X=", "")
-                    .replace("Y=", "")
-                    .replace(",", "")
-            );
-        let prize = (
-            str_prize.next().expect("Expecting X for Prize").parse().expect("Expecting X for Prize to be usize"),
-            str_prize.next().expect("Expecting Y for Prize").parse().expect("Expecting Y for Prize to be usize")
-        );
-        ClawMachine::new(a, b, prize)
-    }
-}
-
-// Main function
-pub fn day_13() {
-    let input_file = "./input/day_13.txt";
-
-    println!("Puzzle 1 result: {}", puzzle1(input_file));
-    println!("Puzzle 2 result: {}", puzzle2(input_file));
+        Some(int_a * 3 + int_b)
+    }
+
+}
+
+/// The cheapest way to win when both buttons move the claw along the same line. The presses
+/// are worked out on one axis and then have to hit the prize on the other one too.
+fn solve_collinear(a: (i128, i128), b: (i128, i128), prize: (i128, i128)) -> Option<u128> {
+    let (presses_a, presses_b) = if a.0 != 0 || b.0 != 0 {
+        cheapest_presses(a.0, b.0, prize.0)?
+    } else {
+        cheapest_presses(a.1, b.1, prize.1)?
+    };
+    if (presses_a * a.0 + presses_b * b.0, presses_a * a.1 + presses_b * b.1) != prize {
+        return None;
+    }
+    Some(presses_a as u128 * 3 + presses_b as u128)
+}
+
+/// The presses of A (moving `step_a`) and B (moving `step_b`) that reach `target` at the lowest cost.
+fn cheapest_presses(step_a: i128, step_b: i128, target: i128) -> Option<(i128, i128)> {
+    match (step_a, step_b) {
+        (0, 0) => Some((0, 0)),
+        // a button that doesn't move the claw is never worth pressing
+        (0, _) => (target % step_b == 0).then_some((0, target / step_b)),
+        (_, 0) => (target % step_a == 0).then_some((target / step_a, 0)),
+        // A costs 3 tokens, so B gets further per token unless A moves more than three times as far
+        _ if step_a <= step_b * 3 => {
+            let presses_a = fewest_presses(step_a, step_b, target)?;
+            Some((presses_a, (target - presses_a * step_a) / step_b))
+        },
+        _ => {
+            let presses_b = fewest_presses(step_b, step_a, target)?;
+            Some(((target - presses_b * step_b) / step_a, presses_b))
+        }
+    }
+}
+
+/// The fewest presses of a button moving `step` after which the other button, moving `other`,
+/// can cover the rest of the way to `target`.
+fn fewest_presses(step: i128, other: i128, target: i128) -> Option<i128> {
+    let (gcd, inverse) = gcd_and_inverse(step, other);
+    if target % gcd != 0 {
+        return None;
+    }
+    let modulus = other / gcd;
+    let presses = (target / gcd % modulus) * inverse % modulus;
+    (presses * step <= target).then_some(presses)
+}
+
+/// The greatest common divisor of `value` and `modulus`, and the inverse of `value / gcd` modulo
+/// `modulus / gcd`, by the extended Euclidean algorithm.
+fn gcd_and_inverse(value: i128, modulus: i128) -> (i128, i128) {
+    let (mut old_r, mut r) = (value, modulus);
+    let (mut old_s, mut s) = (1, 0);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+    }
+    (old_r, old_s.rem_euclid(modulus / old_r))
+}
+
+impl ClawMachine {
+    fn parse(source: &Source, block: &str) -> Result<ClawMachine, ParseError> {
+        let mut lines = block.lines();
+        let a = parse_pair(source, source.next(&mut lines, block, "a `Button A` line")?, "Button A: ", "+")?;
+        let b = parse_pair(source, source.next(&mut lines, block, "a `Button B` line")?, "Button B: ", "+")?;
+        let prize = parse_pair(source, source.next(&mut lines, block, "a `Prize` line")?, "Prize: ", "=")?;
+        Ok(ClawMachine::new(a, b, prize))
+    }
+}
+
+fn parse_pair(source: &Source, line: &str, label: &str, sign: &str) -> Result<(usize, usize), ParseError> {
+    let (x, y) = source.split_once(source.strip_prefix(line, label)?, ", ")?;
+    let x = source.strip_prefix(x, &format!("X{}", sign))?;
+    let y = source.strip_prefix(y, &format!("Y{}", sign))?;
+    Ok((parse_value(source, x)?, parse_value(source, y)?))
+}
+
+/// Button moves and prizes are bounded so [`ClawMachine::solve`] can't overflow.
+fn parse_value(source: &Source, token: &str) -> Result<usize, ParseError> {
+    let value: u32 = source.parse(token, format_args!("a number up to {}", u32::MAX))?;
+    Ok(value as usize)
+}
+
+/// All claw machines, and how far the prizes really are for puzzle 2.
+#[derive(Debug)]
+pub struct Arcade {
+    machines: Vec<ClawMachine>,
+    offset: usize,
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Arcade;
+    type Answer1 = u128;
+    type Answer2 = u128;
+
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("offset", "10000000000000")];
+
+    fn parse(source: &Source, params: &Params) -> Result<Arcade, ParseError> {
+        Ok(Arcade {
+            machines: read_claw_machines(source)?,
+            offset: params.get("offset")?,
+        })
+    }
+
+    fn part1(input: &Arcade) -> u128 {
+        puzzle1(input)
+    }
+
+    fn part2(input: &Arcade) -> u128 {
+        puzzle2(input)
+    }
+
+    fn generate(rng: &mut Rng, size: usize, _params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(Some(generate_machines(rng, size)))
+    }
 }
 
 // Puzzle 1 function
-fn puzzle1(input_file: &str) -> usize {
-    read_claw_machines(input_file)
+fn puzzle1(arcade: &Arcade) -> u128 {
+    arcade.machines
         .iter()
         .filter_map(|machine| machine.solve())
         .sum()
 }
 
-fn read_claw_machines(input_file: &str) -> Vec<ClawMachine> {
-    fs::read_to_string(input_file)
-        .expect("Failed to read input file")
-        .split("\n\n")
-        .map(|str| str
-            .lines()
-            .map(|str| String::from(str)).collect())
+fn read_claw_machines(source: &Source) -> Result<Vec<ClawMachine>, ParseError> {
+    source
+        .blocks(&source.text)
+        .map(|block| ClawMachine::parse(source, block))
         .collect()
 }
 
-// Puzzle 2 function
-fn puzzle2(input_file: &str) -> usize {
-    read_claw_machines(input_file)
-        .into_iter()
-        .map(|machine| ClawMachine {
-            a: machine.a,
-            b: machine.b,
-            prize: (machine.prize.0 + 10000000000000, machine.prize.1 + 10000000000000)
+/// `size` claw machines; the prize of half of them can be won with up to 100 presses of each
+/// button. One in four machines has buttons that move the claw in the same direction.
+fn generate_machines(rng: &mut Rng, size: usize) -> String {
+    let machines: Vec<String> = (0..size)
+        .map(|_| {
+            let (a, b) = if rng.chance(1, 4) {
+                let direction = (rng.range(1..=9), rng.range(1..=9));
+                let (times_a, times_b) = (rng.range(1..=11), rng.range(1..=11));
+                ((direction.0 * times_a, direction.1 * times_a), (direction.0 * times_b, direction.1 * times_b))
+            } else {
+                ((rng.range(10..=99), rng.range(10..=99)), (rng.range(10..=99), rng.range(10..=99)))
+            };
+            let prize = if rng.chance(1, 2) {
+                let (presses_a, presses_b) = (rng.range(0..=100), rng.range(0..=100));
+                (a.0 * presses_a + b.0 * presses_b, a.1 * presses_a + b.1 * presses_b)
+            } else {
+                (rng.range(1000..=20000), rng.range(1000..=20000))
+            };
+            format!(
+                "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}\n",
+                a.0, a.1, b.0, b.1, prize.0, prize.1
+            )
         })
-        .filter_map(|machine| machine.solve())
+        .collect();
+    machines.join("\n")
+}
+
+// Puzzle 2 function
+fn puzzle2(arcade: &Arcade) -> u128 {
+    arcade.machines
+        .iter()
+        .filter_map(|machine| machine.solve_moved(arcade.offset))
         .sum()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::property::{self, Config};
 
     #[test]
     fn test_high_solve() {
@@ -165,14 +249,75 @@ mod tests {
         assert_eq!(machine.solve(), None);
     }
 
+    #[test]
+    fn test_collinear_buttons() {
+        let machine = ClawMachine::new((10, 10), (20, 20), (40, 40));
+        assert_eq!(machine.solve(), Some(2));
+        let machine = ClawMachine::new((40, 40), (10, 10), (80, 80));
+        assert_eq!(machine.solve(), Some(6));
+        let machine = ClawMachine::new((6, 4), (9, 6), (21, 14));
+        assert_eq!(machine.solve(), Some(7));
+        let machine = ClawMachine::new((10, 10), (20, 20), (40, 41));
+        assert_eq!(machine.solve(), None);
+        let machine = ClawMachine::new((0, 0), (3, 5), (6, 10));
+        assert_eq!(machine.solve(), Some(2));
+    }
+
+    #[test]
+    fn test_large_values() {
+        let source = Source::new("test", "Button A: X+9999999999, Y+9999999999\nButton B: X+9999999999, Y+1\nPrize: X=5, Y=5\n");
+        let err = Day13::parse(&source, &Day13::params()).unwrap_err();
+        assert_eq!((err.line, err.expected.as_str()), (1, "a number up to 4294967295"));
+
+        let max = u32::MAX as usize;
+        let machine = ClawMachine { a: (max, max), b: (max, 1), prize: (max, max) };
+        assert_eq!(machine.solve(), Some(3));
+        let machine = ClawMachine { a: (1, 0), b: (0, 1), prize: (max, max) };
+        assert_eq!(machine.solve_moved(usize::MAX), Some((usize::MAX as u128 + max as u128) * 4));
+    }
+
     #[test]
     fn test_puzzle1() {
-        assert_eq!(puzzle1("./input_test/day_13.txt"), 480);
+        assert_eq!(puzzle1(&Day13::load("./input_test/day_13.txt").unwrap()), 480);
     }
 
     #[test]
     fn test_puzzle2() {
-        // this breaks
-        assert_eq!(puzzle2("./input_test/day_13.txt"), 480);
+        assert_eq!(puzzle2(&Day13::load("./input_test/day_13.txt").unwrap()), 875318608908);
+        // without the offset puzzle 2 is puzzle 1
+        let params = Day13::params().with("offset", 0).unwrap();
+        assert_eq!(puzzle2(&Day13::load_with("./input_test/day_13.txt", &params).unwrap()), 480);
+    }
+
+    /// The cheapest way to win by trying every number of presses of A, for comparison with the
+    /// closed form [`ClawMachine::solve`].
+    fn solve_by_trying(machine: &ClawMachine) -> Option<u128> {
+        let most_a = [(machine.prize.0, machine.a.0), (machine.prize.1, machine.a.1)]
+            .into_iter()
+            .filter_map(|(prize, step)| prize.checked_div(step))
+            .min()
+            .unwrap_or(0);
+        (0..=most_a)
+            .filter_map(|presses_a| {
+                let rest = (
+                    machine.prize.0.checked_sub(presses_a * machine.a.0)?,
+                    machine.prize.1.checked_sub(presses_a * machine.a.1)?,
+                );
+                let presses_b = [(rest.0, machine.b.0), (rest.1, machine.b.1)]
+                    .into_iter()
+                    .find_map(|(rest, step)| rest.checked_div(step))
+                    .unwrap_or(0);
+                (rest == (presses_b * machine.b.0, presses_b * machine.b.1)).then_some(presses_a as u128 * 3 + presses_b as u128)
+            })
+            .min()
+    }
+
+    #[test]
+    fn test_solve_agrees_with_trying() {
+        property::assert_agree::<Day13>("day_13_solve", Config::default(), |arcade| {
+            arcade.machines
+                .iter()
+                .try_for_each(|machine| property::agree(&format!("{:?}", machine), machine.solve(), solve_by_trying(machine)))
+        });
     }
 }
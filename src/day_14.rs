@@ -1,80 +1,25 @@
-use std::{cmp::Ordering, collections::HashMap, fs, io, ops::{Add, Div, Mul, Rem}};
+use std::num::NonZeroUsize;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-struct Vec2D(isize, isize);
+use crate::{grid::{Grid, Point}, params::Params, parse::{ParseError, Source}, random::Rng, solution::Solution, visualize::{Color, Renderer}};
 
-impl Mul<isize> for Vec2D {
-    type Output = Vec2D;
-
-    fn mul(self, rhs: isize) -> Self::Output {
-        Vec2D(self.0 * rhs, self.1 * rhs)
-    }
-}
-
-impl Add for Vec2D {
-    type Output = Vec2D;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Vec2D(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
-
-impl Rem for Vec2D {
-    type Output = Vec2D;
-
-    fn rem(self, rhs: Self) -> Self::Output {
-        Vec2D(self.0 % rhs.0, self.1 % rhs.1)
-    }
-}
-
-impl Div<isize> for Vec2D {
-    type Output = Vec2D;
-
-    fn div(self, rhs: isize) -> Self::Output {
-        Vec2D(self.0 / rhs, self.1 / rhs)
-    }
-}
-
-impl FromIterator<String> for Vec2D {
-    fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
-        let mut iter = iter.into_iter();
-        let x = iter.next().expect("Expecting first coord to be value").parse().expect("Expecting first coord to be isize");
-        let y = iter.next().expect("Expecting second coord to be value").parse().expect("Expecting second coord to be isize");
-        Vec2D(x, y)
-    }
-}
-
-struct Robot {
-    position: Vec2D,
-    velocity: Vec2D,
-    limit: Vec2D
+#[derive(Clone, Debug)]
+pub struct Robot {
+    position: Point,
+    velocity: Point,
+    limit: Point
 }
 
 impl Robot {
     fn do_move(&self, iterations: isize) -> Robot {
-        let mut new_pos = (self.position + self.velocity * iterations) % self.limit;
-        if new_pos.0 < 0 {
-            new_pos.0 += self.limit.0;
-        }
-        if new_pos.1 < 0 {
-            new_pos.1 += self.limit.1;
-        }
         Robot {
-            position: new_pos,
+            position: (self.position + self.velocity * iterations).rem_euclid(self.limit),
             limit: self.limit,
             velocity: self.velocity
         }
     }
 
     fn step(&mut self) {
-        let mut new_pos = (self.position + self.velocity) % self.limit;
-        if new_pos.0 < 0 {
-            new_pos.0 += self.limit.0;
-        }
-        if new_pos.1 < 0 {
-            new_pos.1 += self.limit.1;
-        }
-        self.position = new_pos
+        self.position = (self.position + self.velocity).rem_euclid(self.limit)
     }
 
     fn is_left(&self) -> bool {
@@ -93,106 +38,195 @@ impl Robot {
     }
 }
 
-// Main function
-pub fn day_14() {
-    let input_file = "./input/day_14.txt";
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Vec<Robot>;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-    println!("Puzzle 1 result: {}", puzzle1(input_file));
-    println!("Puzzle 2 result: {}", puzzle2(input_file));
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("width", "101"), ("height", "103")];
+
+    fn parse(source: &Source, params: &Params) -> Result<Vec<Robot>, ParseError> {
+        let (width, height): (NonZeroUsize, NonZeroUsize) = (params.get("width")?, params.get("height")?);
+        read_to_bots(source, Point(width.get() as isize, height.get() as isize))
+    }
+
+    fn part1(input: &Vec<Robot>) -> usize {
+        puzzle1(input)
+    }
+
+    fn part2(input: &Vec<Robot>) -> usize {
+        puzzle2(input)
+    }
+
+    fn visualize(input: &Vec<Robot>, renderer: &mut Renderer) -> bool {
+        let mut bots = input.to_vec();
+        let Some(limit) = bots.first().map(|bot| bot.limit) else {
+            return false;
+        };
+        // showing every second until the positions repeat would take ages, so only show the seconds where the
+        // robots cluster more than ever before, ending at the picture if there is one
+        let picture = puzzle2(input);
+        let mut lowest = safety_factor(&bots);
+        renderer.frame(&count_robots(&bots), None, &format!("Initial state, safety factor {}", lowest), robot_cell);
+        for second in 1..=limit.0 * limit.1 {
+            bots.iter_mut().for_each(|bot| bot.step());
+            let factor = safety_factor(&bots);
+            if factor < lowest || second as usize == picture {
+                lowest = lowest.min(factor);
+                let caption = format!("After {} seconds, safety factor {}", second, factor);
+                renderer.frame(&count_robots(&bots), None, &caption, robot_cell);
+            }
+            if second as usize == picture {
+                break;
+            }
+        }
+        true
+    }
+
+    fn generate(rng: &mut Rng, size: usize, params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(Some(generate_robots(rng, size, params.get("width")?, params.get("height")?)))
+    }
 }
 
 // Puzzle 1 function
-fn puzzle1(input_file: &str) -> usize {
-    let (lefts, rights): (Vec<Robot>, Vec<Robot>) = read_to_bots(input_file)
-        .into_iter()
-        .map(|bot| bot.do_move(100))
+fn puzzle1(bots: &[Robot]) -> usize {
+    let moved: Vec<Robot> = bots.iter().map(|bot| bot.do_move(100)).collect();
+    safety_factor(&moved)
+}
+
+/// The product of how many robots are in each quadrant, leaving out the ones in between.
+fn safety_factor(bots: &[Robot]) -> usize {
+    let (lefts, rights): (Vec<&Robot>, Vec<&Robot>) = bots
+        .iter()
         .filter(|bot| !bot.is_on_edge())
         .partition(|bot| bot.is_left());
-    let (left_tops, left_bottoms): (Vec<Robot>, Vec<Robot>) = lefts.into_iter().partition(|bot| bot.is_top());
-    let (right_tops, right_bottoms): (Vec<Robot>, Vec<Robot>) = rights.into_iter().partition(|bot| bot.is_top());
+    let (left_tops, left_bottoms): (Vec<&Robot>, Vec<&Robot>) = lefts.into_iter().partition(|bot| bot.is_top());
+    let (right_tops, right_bottoms): (Vec<&Robot>, Vec<&Robot>) = rights.into_iter().partition(|bot| bot.is_top());
     left_bottoms.len() * left_tops.len() * right_bottoms.len() * right_tops.len()
 }
 
-fn read_to_bots(input_file: &str) -> Vec<Robot> {
-    fs::read_to_string(input_file)
-        .expect("Failed to read input file")
+fn read_to_bots(source: &Source, limit: Point) -> Result<Vec<Robot>, ParseError> {
+    source.text
         .lines()
         .map(|line| {
-            let mut vecs: Vec<Vec2D> = line
-                .split_whitespace()
-                .map(|vec| vec
-                    .replace("p=", "")
-                    .replace("v=", "")
-                    .split(",")
-                    .map(|str| String::from(str))
-                    .collect()
-                )
-                .collect();
-            Robot {
-                position: vecs.swap_remove(0),
-                velocity: vecs.swap_remove(0),
-                limit: Vec2D(101, 103)//11, 7)
+            let (position, velocity) = source.split_once(line, " ")?;
+            let position = source.strip_prefix(position, "p=")?;
+            let point = parse_point(source, position)?;
+            if !(0..limit.0).contains(&point.0) || !(0..limit.1).contains(&point.1) {
+                return Err(source.error(position, format_args!("a position on the {}x{} floor", limit.0, limit.1)));
             }
+            Ok(Robot {
+                position: point,
+                velocity: parse_point(source, source.strip_prefix(velocity, "v=")?)?,
+                limit
+            })
         })
         .collect()
 }
 
+fn parse_point(source: &Source, coords: &str) -> Result<Point, ParseError> {
+    let (x, y) = source.split_once(coords, ",")?;
+    Ok(Point(source.number(x)?, source.number(y)?))
+}
+
+/// `size` robots anywhere on the `width` by `height` floor, moving up to 99 tiles a second each way.
+fn generate_robots(rng: &mut Rng, size: usize, width: usize, height: usize) -> String {
+    (0..size)
+        .map(|_| format!(
+            "p={},{} v={},{}\n",
+            rng.below(width.max(1)),
+            rng.below(height.max(1)),
+            rng.signed(-99..=99),
+            rng.signed(-99..=99)
+        ))
+        .collect()
+}
+
 // Puzzle 2 function
-fn puzzle2(input_file: &str) -> usize {
-    let mut bots = read_to_bots(input_file);
-    let mut iteration = 0;
-    loop {
+/// The robots only form the picture when none of them share a tile, so look for the first such second.
+/// Positions repeat after width * height seconds, so if that never happens the answer is 0.
+fn puzzle2(bots: &[Robot]) -> usize {
+    let mut bots = bots.to_vec();
+    let Some(limit) = bots.first().map(|bot| bot.limit) else {
+        return 0;
+    };
+    for second in 1..=(limit.0 * limit.1) as usize {
         bots.iter_mut().for_each(|bot| bot.step());
-        let map: HashMap<Vec2D, usize> = bots
-            .iter()
-            .fold(HashMap::new(), |mut map, bot| {
-                let entry = map.entry(bot.position).or_default();
-                *entry += 1;
-                map
-            });
-        if iteration > 5 && (iteration - 6) % 101 == 0 {
-            println!("After {} seconds:", iteration + 1);
-            print_map(&map);
-            let mut input = String::new(); // Create a mutable String to store the input
-            io::stdin()
-                .read_line(&mut input) // Read input and store it in the `input` variable
-                .expect("Failed to read line");
-
-            if input == "x\n" {
-                break;
-            }
+        if count_robots(&bots).iter().all(|(_, &count)| count <= 1) {
+            return second;
         }
-        iteration += 1;
     }
-    iteration
+    0
 }
 
-fn print_map(map: &HashMap<Vec2D, usize>) {
-    for y in 0..103 {
-        for x in 0..101 {
-            if let Some(num) = map.get(&Vec2D(x, y)) {
-                print!("{}", num);
-            } else {
-                print!(".");
-            }
-        }
-        println!();
+fn count_robots(bots: &[Robot]) -> Grid<usize> {
+    let limit = bots.first().map_or(Point(0, 0), |bot| bot.limit);
+    bots
+        .iter()
+        .fold(Grid::new(limit.0 as usize, limit.1 as usize, 0), |mut map, bot| {
+            map[bot.position] += 1;
+            map
+        })
+}
 
+fn robot_cell(_: Point, &count: &usize) -> (char, Color) {
+    match count {
+        0 => ('.', Color::Plain),
+        count => (char::from_digit(count as u32 % 10, 10).unwrap(), Color::Green),
     }
-    println!();
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{property::{self, Config}, snapshot::Snapshot};
+
+    fn example() -> Vec<Robot> {
+        let params = Day14::params().with("width", 11).unwrap().with("height", 7).unwrap();
+        Day14::load_with("./input_test/day_14.txt", &params).unwrap()
+    }
 
     #[test]
     fn test_puzzle1() {
-        assert_eq!(puzzle1("./input_test/day_14.txt"), 12);
+        assert_eq!(puzzle1(&example()), 12);
     }
 
     #[test]
     fn test_puzzle2() {
-        assert_eq!(puzzle2("./input_test/day_14.txt"), 0);
+        assert_eq!(puzzle2(&example()), 1);
+    }
+
+    #[test]
+    fn test_seconds() {
+        let mut bots = example();
+        let floor = |bots: &[Robot]| count_robots(bots).map(|count| robot_cell(Point(0, 0), count).0);
+        let mut snapshot = Snapshot::new();
+        snapshot.step("Initial state", floor(&bots));
+        for second in 1..=100 {
+            bots.iter_mut().for_each(|bot| bot.step());
+            snapshot.step(format_args!("After {} seconds", second), floor(&bots));
+        }
+        snapshot.assert("day_14_seconds");
+    }
+
+    #[test]
+    fn test_read_rejects_robots_off_the_floor() {
+        let params = Day14::params().with("width", 11).unwrap().with("height", 7).unwrap();
+        let err = Day14::parse(&Source::new("test", "p=0,4 v=3,-3\np=11,3 v=-1,-3\n"), &params).unwrap_err();
+        assert_eq!((err.line, err.expected.as_str(), err.found.as_str()), (2, "a position on the 11x7 floor", "`11,3`"));
+        assert!(Day14::parse(&Source::new("test", "p=0,4 v=3,-3\n"), &params.with("width", 0).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_move_agrees_with_steps() {
+        property::assert_agree::<Day14>("day_14_move", Config::default(), |bots| {
+            bots.iter().try_for_each(|bot| {
+                let mut stepped = bot.clone();
+                (0..100).for_each(|_| stepped.step());
+                property::agree("position after 100 seconds", bot.do_move(100).position, stepped.position)
+            })
+        });
     }
 }
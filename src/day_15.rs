@@ -1,186 +1,179 @@
-use std::{collections::HashMap, fs, io::{self, Write}, ops::{Add, AddAssign, Sub, SubAssign}};
 use std::collections::HashSet;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-struct Vec2D(isize, isize);
+use crate::{grid::{Direction, Grid, Point}, params::Params, parse::{ParseError, Source}, random::Rng, solution::Solution, trace, visualize::{Color, Renderer}};
 
-impl Add for Vec2D {
-    type Output = Vec2D;
+pub struct Day15;
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Vec2D(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
+impl Solution for Day15 {
+    type Input = (Grid<char>, Vec<Direction>);
+    type Answer1 = isize;
+    type Answer2 = isize;
 
-impl AddAssign for Vec2D {
-    fn add_assign(&mut self, rhs: Self) {
-        *self = *self + rhs;
+    fn parse(source: &Source, _params: &Params) -> Result<(Grid<char>, Vec<Direction>), ParseError> {
+        read_map_commands(source)
     }
-}
 
-impl SubAssign for Vec2D {
-    fn sub_assign(&mut self, rhs: Self) {
-        *self = *self - rhs;
+    fn part1(input: &(Grid<char>, Vec<Direction>)) -> isize {
+        puzzle1(input)
     }
-}
-
-impl Sub for Vec2D {
-    type Output = Vec2D;
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        Vec2D(self.0 - rhs.0, self.1 - rhs.1)
+    fn part2(input: &(Grid<char>, Vec<Direction>)) -> isize {
+        puzzle2(input)
     }
-}
 
-// Main function
-pub fn day_15() {
-    let input_file = "./input/day_15.txt";
+    fn visualize((map, commands): &(Grid<char>, Vec<Direction>), renderer: &mut Renderer) -> bool {
+        let mut map = map.clone();
+        let mut robot_pos = find_start(&map);
+        for (index, &command) in commands.iter().enumerate() {
+            robot_pos = move_robot(&mut map, robot_pos, command);
+            let caption = format!("Move {}/{}: {}", index + 1, commands.len(), command.arrow());
+            renderer.frame(&map, Some(robot_pos), &caption, |_, &char| match char {
+                '@' => ('@', Color::Red),
+                'O' => ('O', Color::Yellow),
+                '#' => ('#', Color::Gray),
+                _ => (char, Color::Plain),
+            });
+        }
+        true
+    }
 
-    println!("Puzzle 1 result: {}", puzzle1(input_file));
-    println!("Puzzle 2 result: {}", puzzle2(input_file));
+    fn generate(rng: &mut Rng, size: usize, _params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(Some(generate_warehouse(rng, size)))
+    }
 }
 
 // Puzzle 1 function
-fn puzzle1(input_file: &str) -> isize {
-    let (mut map, commands) = read_map_commands(input_file);
+fn puzzle1((map, commands): &(Grid<char>, Vec<Direction>)) -> isize {
+    let mut map = map.clone();
     let mut robot_pos = find_start(&map);
-    for command in commands {
-        let mut potential_target = robot_pos;
-        let mut chars_to_insert = vec![];
-        let should_move = loop {
-            potential_target += command;
-            let next_map_entry = map.get(&potential_target);
-            
-            match next_map_entry {
-                Some('.') => {
-                    break true;
-                },
-                Some('O') => {
-                    chars_to_insert.push('O');
-                },
-                Some('#') => {
-                    chars_to_insert.clear();
-                    break false;   
-                },
-                _ => panic!("Expected valid character at {:?} but got {:?}", potential_target, next_map_entry)
-            }
-        };
+    for &command in commands {
+        robot_pos = move_robot(&mut map, robot_pos, command);
+    }
+    calc_gps_pos(&map)
+}
+
+fn move_robot(map: &mut Grid<char>, robot_pos: Point, command: Direction) -> Point {
+    let mut potential_target = robot_pos;
+    let mut chars_to_insert = vec![];
+    let should_move = loop {
+        potential_target += command;
+        let next_map_entry = map.get(potential_target);
         
-        if should_move {
-            while let Some(char) = chars_to_insert.pop() {
-                map.entry(potential_target).and_modify(|map_pos| *map_pos = char);
-                potential_target -= command
-            }
-            map.entry(robot_pos).and_modify(|map_pos| *map_pos = '.');
-            robot_pos += command;
-            map.entry(robot_pos).and_modify(|map_pos| *map_pos = '@');
+        match next_map_entry {
+            Some('.') => {
+                break true;
+            },
+            Some('O') => {
+                chars_to_insert.push('O');
+            },
+            Some('#') => {
+                chars_to_insert.clear();
+                break false;   
+            },
+            _ => panic!("Expected valid character at {:?} but got {:?}", potential_target, next_map_entry)
         }
-        print_map(&map);
+    };
+    
+    if !should_move {
+        return robot_pos;
     }
-    calc_gps_pos(&map)
+    while let Some(char) = chars_to_insert.pop() {
+        map[potential_target] = char;
+        potential_target -= command
+    }
+    map[robot_pos] = '.';
+    map[robot_pos + command] = '@';
+    robot_pos + command
 }
 
-fn find_start(map: &HashMap<Vec2D, char>) -> Vec2D {
-    *map
-        .iter()
-        .find(|(_, &char)| char == '@')
+fn find_start(map: &Grid<char>) -> Point {
+    map
+        .position(|&char| char == '@')
         .expect("Expecting start position to be in map!")
-        .0
 }
 
-fn read_map_commands(input_file: &str) -> (HashMap<Vec2D, char>, Vec<Vec2D>) {
-    let inputs = fs::read_to_string(input_file)
-        .expect("Failed to read input file");
-    let mut inputs: Vec<&str> = inputs
-        .split("\n\n")
-        .collect();
-
-    let map_str = inputs.swap_remove(0);
-    let map: HashMap<Vec2D, char> = read_map(map_str);
-    let command_str = inputs.swap_remove(0);
-    let commands: Vec<Vec2D> = read_commands(command_str);
-        
-    (map, commands)
-
+fn read_map_commands(source: &Source) -> Result<(Grid<char>, Vec<Direction>), ParseError> {
+    let (map_str, command_str) = source.sections(&source.text)?;
+    let map = Grid::parse(source, map_str)?;
+    check_warehouse(source, map_str)?;
+    if map.position(|&char| char == '@').is_none() {
+        return Err(source.missing(map_str, "the robot `@`"));
+    }
+    let commands = read_commands(source, command_str)?;
+    Ok((map, commands))
 }
 
-fn read_map(map_str: &str) -> HashMap<Vec2D, char> {
-    map_str
-        .lines()
-        .enumerate()
-        .flat_map(move |(y, line)| line
-            .chars()
-            .enumerate()
-            .map(move |(x, char)| (Vec2D(x as isize, y as isize), char)))
-        .collect()    
+/// The moves only stay on the map when the warehouse is walled in, and they only know walls,
+/// boxes, floor and a single robot.
+fn check_warehouse(source: &Source, map_str: &str) -> Result<(), ParseError> {
+    let last_line = map_str.lines().count().saturating_sub(1);
+    let mut robots = 0;
+    for (y, line) in map_str.lines().enumerate() {
+        for (index, char) in line.char_indices() {
+            let tile = &line[index..index + char.len_utf8()];
+            let on_edge = y == 0 || y == last_line || index == 0 || index + tile.len() == line.len();
+            if on_edge && char != '#' {
+                return Err(source.error(tile, "a wall `#` around the warehouse"));
+            }
+            if !"#.O@".contains(char) {
+                return Err(source.error(tile, "a wall `#`, a box `O`, floor `.` or the robot `@`"));
+            }
+            robots += usize::from(char == '@');
+            if robots > 1 {
+                return Err(source.error(tile, "a single robot `@`"));
+            }
+        }
+    }
+    Ok(())
 }
 
-fn read_commands(command_str: &str) -> Vec<Vec2D> {
+fn read_commands(source: &Source, command_str: &str) -> Result<Vec<Direction>, ParseError> {
     command_str
         .lines()
-        .flat_map(|line| line.chars())
-        .map(|char| match char {
-            '<' => Vec2D(-1, 0),
-            'v' => Vec2D(0, 1),
-            '>' => Vec2D(1, 0),
-            '^' => Vec2D(0, -1),
-            _ => panic!("Unexpected command!")
-        })
-        .collect()
-}
-
-fn read_map_wide_commands(input_file: &str) -> (HashMap<Vec2D, char>, Vec<Vec2D>) {
-    let inputs = fs::read_to_string(input_file)
-        .expect("Failed to read input file");
-    let mut inputs: Vec<&str> = inputs
-        .split("\n\n")
-        .collect();
-
-    let map_str = inputs.swap_remove(0);
-    let map: HashMap<Vec2D, char> = read_map_wide(map_str);
-    let command_str = inputs.swap_remove(0);
-    let commands: Vec<Vec2D> = read_commands(command_str);
-        
-    (map, commands)
-}
-fn read_map_wide(map_str: &str) -> HashMap<Vec2D, char> {
-    map_str
-        .lines()
-        .enumerate()
-        .flat_map(move |(y, line)| line
-            .chars()
-            .enumerate()
-            .flat_map(move |(x, char)| {
-                let first_loc = Vec2D(x as isize * 2, y as isize);
-                let second_loc = Vec2D(x as isize * 2 + 1, y as isize);
-                match char {
-                    '@' => [(first_loc, '@'), (second_loc, '.')],
-                    'O' => [(first_loc, '['), (second_loc, ']')],
-                    x => [(first_loc, char), (second_loc, char)]
-                }
-            })
+        .flat_map(|line| line
+            .char_indices()
+            .map(move |(index, char)| Direction::from_arrow(char)
+                .ok_or_else(|| source.error(&line[index..index + char.len_utf8()], "a move `^`, `>`, `v` or `<`"))
+            )
         )
-        .collect()    
+        .collect()
 }
 
-fn print_map(map: &HashMap<Vec2D, char>) {
-    let mut stdout_handle = io::stdout().lock();
-    for y in 0..10 {
-        for x in 0..20 {
-            if let Some(ch) = map.get(&Vec2D(x, y)) {
-                write!(stdout_handle, "{}", ch).unwrap();
-            } else {
-                write!(stdout_handle, ".").unwrap();
-            }
+/// A `size` by `size` warehouse, walled in, with boxes on about a quarter of the floor, and
+/// 4 * `size`² moves for the robot.
+fn generate_warehouse(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(3);
+    let edge = size as isize - 1;
+    let mut map = Grid::from_fn(size, size, |Point(x, y)| {
+        if x == 0 || y == 0 || x == edge || y == edge || rng.chance(1, 20) {
+            '#'
+        } else if rng.chance(1, 4) {
+            'O'
+        } else {
+            '.'
         }
-        writeln!(stdout_handle).unwrap();
+    });
+    map[Point(rng.range(1..=size - 2) as isize, rng.range(1..=size - 2) as isize)] = '@';
+    let moves: Vec<char> = (0..4 * size * size).map(|_| rng.choose(&Direction::ALL).arrow()).collect();
+    let moves: String = moves.chunks(70).map(|line| line.iter().collect::<String>() + "\n").collect();
+    format!("{}\n{}", map, moves)
+}
 
+fn widen_map(map: &Grid<char>) -> Grid<char> {
+    let mut wide_map = Grid::new(map.width() * 2, map.height(), '.');
+    for (Point(x, y), &char) in map.iter() {
+        let (first, second) = match char {
+            '@' => ('@', '.'),
+            'O' => ('[', ']'),
+            _ => (char, char)
+        };
+        wide_map[Point(x * 2, y)] = first;
+        wide_map[Point(x * 2 + 1, y)] = second;
     }
-    writeln!(stdout_handle).unwrap();
-    stdout_handle.flush().unwrap();
+    wide_map
 }
 
-fn calc_gps_pos_with(map: &HashMap<Vec2D, char>, find_char: char) -> isize {
+fn calc_gps_pos_with(map: &Grid<char>, find_char: char) -> isize {
     map
         .iter()
         .filter(|(_, &char)| char == find_char)
@@ -188,55 +181,56 @@ fn calc_gps_pos_with(map: &HashMap<Vec2D, char>, find_char: char) -> isize {
         .sum()
 }
 
-fn calc_gps_pos(map: &HashMap<Vec2D, char>) -> isize {
+fn calc_gps_pos(map: &Grid<char>) -> isize {
     calc_gps_pos_with(map, 'O')
 }
 
-fn calc_gps_pos_wide(map: &HashMap<Vec2D, char>) -> isize {
+fn calc_gps_pos_wide(map: &Grid<char>) -> isize {
     calc_gps_pos_with(map, '[')
 }
 
 // Puzzle 2 function
-fn puzzle2(input_file: &str) -> isize {
-    let (mut map, commands) = read_map_wide_commands(input_file);
+fn puzzle2((map, commands): &(Grid<char>, Vec<Direction>)) -> isize {
+    let mut map = widen_map(map);
     let mut bot_pos = find_start(&map);
 
-    for command in commands {
-        // println!("Command: {:?}", command);
-        if let Some(switches) = execute_command(&mut map, bot_pos, command, true) {
-            let switches: HashSet<_> = switches.into_iter().collect();
-            let mut switches: Vec<_> = switches.into_iter().collect();
-            switches.sort_by(|&(a, _), &(b, _)| match command {
-                Vec2D(-1, 0) => a.0.cmp(&b.0),
-                Vec2D(1, 0) => b.0.cmp(&a.0),
-                Vec2D(0, 1) => b.1.cmp(&a.1),
-                Vec2D(0, -1) => a.1.cmp(&b.1),
-                _ => panic!("Unexpected command!"),
-            });
-            for (a, b) in switches {
-                let a_char = *map.get(&a).expect("Expecting switch position a to exist");
-                let b_char = *map.get(&b).expect("Expecting switch position b to exist");
-                map.entry(a).and_modify(|ch| *ch = b_char);
-                map.entry(b).and_modify(|ch| *ch = a_char);
-            }
-            bot_pos = bot_pos + command;
-        }
+    for &command in commands {
+        bot_pos = move_wide_robot(&mut map, bot_pos, command);
     }
 
     
     calc_gps_pos_wide(&map)
 }
 
-fn execute_command(map: &mut HashMap<Vec2D, char>, start_pos: Vec2D, command: Vec2D, check_side: bool) -> Option<Vec<(Vec2D, Vec2D)>> {
-    let cur_char = map.get(&start_pos).expect(&format!("Expecting valid position at {:?}", start_pos));
+fn move_wide_robot(map: &mut Grid<char>, bot_pos: Point, command: Direction) -> Point {
+    trace!("command {:?}", command);
+    let Some(switches) = execute_command(map, bot_pos, command, true) else {
+        return bot_pos;
+    };
+    let switches: HashSet<_> = switches.into_iter().collect();
+    let mut switches: Vec<_> = switches.into_iter().collect();
+    switches.sort_by(|&(a, _), &(b, _)| match command {
+        Direction::Left => a.0.cmp(&b.0),
+        Direction::Right => b.0.cmp(&a.0),
+        Direction::Down => b.1.cmp(&a.1),
+        Direction::Up => a.1.cmp(&b.1),
+    });
+    for (a, b) in switches {
+        map.swap(a, b);
+    }
+    bot_pos + command
+}
+
+fn execute_command(map: &mut Grid<char>, start_pos: Point, command: Direction, check_side: bool) -> Option<Vec<(Point, Point)>> {
+    let cur_char = map.get(start_pos).unwrap_or_else(|| panic!("Expecting valid position at {:?}", start_pos));
     let is_start = cur_char == &'@';
     let result = match cur_char {
         '@' => execute_command(map, start_pos + command, command, true),
         '.' => Some(vec![]),
         '[' | ']' => {
-            let dir = if cur_char == &'[' { Vec2D(1, 0) } else { Vec2D(-1, 0) };
+            let dir = if cur_char == &'[' { Direction::Right } else { Direction::Left };
             // check if command was horizontal or vertical
-            if command.0.abs() == 1 {
+            if command.is_horizontal() {
                 execute_command(map, start_pos + command, command, false)
             } else {
                 let base_result = execute_command(map, start_pos + command, command, true);
@@ -268,15 +262,50 @@ fn execute_command(map: &mut HashMap<Vec2D, char>, start_pos: Vec2D, command: Ve
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::snapshot::Snapshot;
 
     #[test]
     fn test_puzzle1() {
-        assert_eq!(puzzle1("./input_test/day_15.txt"), 10092);
+        assert_eq!(puzzle1(&Day15::load("./input_test/day_15.txt").unwrap()), 10092);
     }
 
     #[test]
     fn test_puzzle2() {
-        assert_eq!(puzzle2("./input_test/day_15.txt"), 9021);
+        assert_eq!(puzzle2(&Day15::load("./input_test/day_15.txt").unwrap()), 9021);
+    }
+
+    /// Runs the first 100 moves of the example with `move_robot`, recording the warehouse after each of them.
+    fn snapshot_moves(widen: bool, move_robot: fn(&mut Grid<char>, Point, Direction) -> Point) -> Snapshot {
+        let (map, commands) = Day15::load("./input_test/day_15.txt").unwrap();
+        let mut map = if widen { widen_map(&map) } else { map };
+        let mut robot_pos = find_start(&map);
+        let mut snapshot = Snapshot::new();
+        snapshot.step("Initial state", &map);
+        for command in commands.into_iter().take(100) {
+            robot_pos = move_robot(&mut map, robot_pos, command);
+            snapshot.step(format_args!("Move {}", command.arrow()), &map);
+        }
+        snapshot
+    }
+
+    #[test]
+    fn test_moves() {
+        snapshot_moves(false, move_robot).assert("day_15_moves");
+    }
+
+    #[test]
+    fn test_wide_moves() {
+        snapshot_moves(true, move_wide_robot).assert("day_15_wide_moves");
     }
 
+    #[test]
+    fn test_read_checks_warehouse() {
+        let expected = |text: &str| {
+            let err = Day15::parse(&Source::new("test", text), &Day15::params()).unwrap_err();
+            (err.line, err.column, err.expected)
+        };
+        assert_eq!(expected("####\n#@.#\n#O..\n####\n\n<\n"), (3, 4, String::from("a wall `#` around the warehouse")));
+        assert_eq!(expected("####\n#@x#\n####\n\n<\n"), (2, 3, String::from("a wall `#`, a box `O`, floor `.` or the robot `@`")));
+        assert_eq!(expected("####\n#@@#\n####\n\n<\n"), (2, 3, String::from("a single robot `@`")));
+    }
 }
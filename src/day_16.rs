@@ -1,218 +1,162 @@
-use std::{cmp::Ordering, collections::{BinaryHeap, HashMap, HashSet}, fs, ops::{Add, AddAssign, Sub, SubAssign}};
+use std::collections::HashSet;
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-struct State {
-    cost: usize,
-    position: (Vec2D, Vec2D),
-}
+use crate::{grid::{Direction, Grid, Point}, params::Params, parse::{ParseError, Source}, random::Rng, search::{self, Search}, solution::Solution, visualize::{Color, Renderer}};
 
-// Implement ordering for the priority queue
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.cost.cmp(&self.cost) // Reverse order for min-heap
-    }
-}
-
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
+/// Where the reindeer is and which way it faces.
+type State = (Point, Direction);
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-struct Vec2D(isize, isize);
+pub struct Day16;
 
-const RIGHT: usize = 1;
-const DIRECTIONS: [Vec2D; 4] = [
-    Vec2D(0, -1),
-    Vec2D(1, 0),
-    Vec2D(0, 1),
-    Vec2D(-1, 0),
-];
+impl Solution for Day16 {
+    type Input = Grid<char>;
+    /// The lowest score, or `none` if E can't be reached.
+    type Answer1 = String;
+    /// The number of tiles on any best path, or `none` if E can't be reached.
+    type Answer2 = String;
 
-impl Add for Vec2D {
-    type Output = Vec2D;
+    const QUERIES: &'static [(&'static str, &'static str)] = &[
+        ("cost <x,y> <direction>", "The lowest score to reach the tile at x,y facing the direction, like up or >"),
+    ];
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Vec2D(self.0 + rhs.0, self.1 + rhs.1)
+    fn parse(source: &Source, _params: &Params) -> Result<Grid<char>, ParseError> {
+        read_map(source)
     }
-}
 
-impl AddAssign for Vec2D {
-    fn add_assign(&mut self, rhs: Self) {
-        *self = *self + rhs;
+    fn part1(input: &Grid<char>) -> String {
+        puzzle1(input).map_or_else(|| String::from("none"), |score| score.to_string())
     }
-}
 
-impl SubAssign for Vec2D {
-    fn sub_assign(&mut self, rhs: Self) {
-        *self = *self - rhs;
+    fn part2(input: &Grid<char>) -> String {
+        puzzle2(input).map_or_else(|| String::from("none"), |tiles| tiles.to_string())
     }
-}
-
-impl Sub for Vec2D {
-    type Output = Vec2D;
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        Vec2D(self.0 - rhs.0, self.1 - rhs.1)
+    fn visualize(input: &Grid<char>, renderer: &mut Renderer) -> bool {
+        let (search, tiles) = find_best_path_tiles(input);
+        let cost_of = |tile: &Point| Direction::ALL
+            .iter()
+            .filter_map(|&dir| search.cost(&(*tile, dir)))
+            .min()
+            .unwrap_or_default();
+        let mut tiles: Vec<Point> = tiles.into_iter().collect();
+        tiles.sort_by_key(cost_of);
+
+        let mut shown = input.map(|_| false);
+        for (index, tile) in tiles.iter().enumerate() {
+            shown[*tile] = true;
+            let caption = format!("Tile {}/{}, score {}", index + 1, tiles.len(), cost_of(tile));
+            renderer.frame(input, Some(*tile), &caption, |point, &char| match char {
+                'S' | 'E' => (char, Color::Magenta),
+                '#' => ('#', Color::Gray),
+                _ if shown[point] => ('O', Color::Green),
+                _ => (' ', Color::Plain),
+            });
+        }
+        true
     }
-}
 
-// Main function
-pub fn day_16() {
-    let input_file = "./input/day_16.txt";
+    fn query(input: &Grid<char>, name: &str, args: &[&str]) -> Result<String, String> {
+        match (name, args) {
+            ("cost", [point, dir]) => describe_cost(input, point.parse()?, dir.parse()?),
+            _ => Err(String::from("usage: cost <x,y> <direction>")),
+        }
+    }
 
-    println!("Puzzle 1 result: {}", puzzle1(input_file));
-    println!("Puzzle 2 result: {}", puzzle2(input_file));
+    fn generate(rng: &mut Rng, size: usize, _params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(Some(generate_maze(rng, size)))
+    }
 }
 
 // Puzzle 1 function
-fn puzzle1(input_file: &str) -> usize {
-    let input = read_map(input_file) ;
-    let (start, end) = input.iter().fold((None, None), |(start, end), (coords, char)| {
-        match char {
-            'S' => (Some(*coords), end),
-            'E' => (start, Some(*coords)),
-            _ => (start, end),
-        }
-    });
-    let start = start.expect("No start found");
-    let end = end.expect("No end found");
-
-    let mut costs: HashMap<(Vec2D, Vec2D), usize> = input
-        .keys()
-        .cloned()
-        .flat_map(|coords| DIRECTIONS.into_iter().map(move |dir| (coords, dir)))
-        .map(|coords| (coords, usize::MAX))
-        .collect();
-    costs.get_mut(&(start, DIRECTIONS[RIGHT])).map(|cost| *cost = 0); 
-    update_costs(&mut costs, start, end);
-    DIRECTIONS.iter().flat_map(|dir| costs.get(&(end, *dir))).min().map(|x| *x).expect("Expecting a value")
+fn puzzle1(input: &Grid<char>) -> Option<usize> {
+    let end = input.position(|&char| char == 'E').expect("No end found");
+    search_maze(input, |&(point, _)| point == end).goal_cost()
 }
 
-fn update_costs(costs: &mut HashMap<(Vec2D, Vec2D), usize>, start: Vec2D, end: Vec2D) {
-    let mut heap = BinaryHeap::new();
-    heap.push(State { cost: 0, position: (start, DIRECTIONS[RIGHT])});
+/// Searches the maze from the start facing east until `is_goal` holds, or everywhere without a goal.
+fn search_maze(input: &Grid<char>, is_goal: impl FnMut(&State) -> bool) -> Search<State, usize> {
+    let start = input.position(|&char| char == 'S').expect("No start found");
+    search::dijkstra([(start, Direction::Right)], |&state| moves(input, state), is_goal)
+}
 
-    while let Some(smallest) = heap.pop() {
-        let State{ position, cost } = smallest;
-        if cost == usize::MAX {
-            break;
-        }
-        if position.0 == end {
-            break;
-        }
-        visit(smallest, costs)
-            .into_iter()
-            .for_each(|state| heap.push(state));
+/// Stepping forward costs 1 and turning left or right on the spot costs 1000.
+fn moves(input: &Grid<char>, (point, facing): State) -> Vec<(State, usize)> {
+    let mut moves = vec![((point, facing.turn_left()), 1000), ((point, facing.turn_right()), 1000)];
+    if input.get(point + facing).is_some_and(|&char| char != '#') {
+        moves.push(((point + facing, facing), 1));
     }
+    moves
 }
 
 // Puzzle 2 function
-fn puzzle2(input_file: &str) -> usize {
-    let input = read_map(input_file) ;
-    let (start, end) = input.iter().fold((None, None), |(start, end), (coords, char)| {
-        match char {
-            'S' => (Some(*coords), end),
-            'E' => (start, Some(*coords)),
-            _ => (start, end),
-        }
-    });
-    let start = start.expect("No start found");
-    let end = end.expect("No end found");
-
-    let mut costs: HashMap<(Vec2D, Vec2D), usize> = input
-        .keys()
-        .cloned()
-        .flat_map(|coords| DIRECTIONS.into_iter().map(move |dir| (coords, dir)))
-        .map(|coords| (coords, usize::MAX))
-        .collect();
-    costs.get_mut(&(start, DIRECTIONS[RIGHT])).map(|cost| *cost = 0); 
-    update_costs(&mut costs, start, end);
-
-    let costs: HashMap<(Vec2D, Vec2D), usize> = costs
-        .into_iter()
-        .filter(|&(_, cost)| cost != usize::MAX)
-        .collect();
-    
-    let cur_state = DIRECTIONS
-        .into_iter()
-        .filter_map(|dir| costs.get_key_value(&(end, dir)))
-        .min_by(|(_, cost), (_, cost_b)| cost.cmp(cost_b))
-        .map(|res| (*res.0, *res.1))
-        .expect("Expeting target state");
-
-    let path_points = check_path_to(&start, cur_state, &costs);
-    path_points.len() + 1 // Add the start point
+fn puzzle2(input: &Grid<char>) -> Option<usize> {
+    let tiles = find_best_path_tiles(input).1;
+    (!tiles.is_empty()).then_some(tiles.len())
 }
 
-fn check_path_to(goal: &Vec2D, cur_state: ((Vec2D, Vec2D), usize), costs: &HashMap<(Vec2D, Vec2D), usize>) -> HashSet<Vec2D> {
-    if goal == &cur_state.0.0 {
-        return HashSet::new();
-    }
-    let ((coord, facing), cost) = cur_state;
-
-    let neighbors: Vec<(Vec2D, Vec2D)> = DIRECTIONS
-        .iter()
-        .filter(|&&dir| dir != facing)
-        .map(|&dir| (coord, dir))
-        .collect();
-    let mut path_points: HashSet<Vec2D> = neighbors
-        .into_iter()
-        .filter_map(|pos| costs
-            .get(&pos)
-            .filter(|&&pos_cost| pos_cost + 1000 == cost)
-            .map(|cost| (pos, *cost))
-        )
-        .flat_map(|state| check_path_to(goal, state, costs))
-        .collect();
-
-    let prev_state = (coord - facing, facing);
-    let prev_cost = costs.get(&prev_state).filter(|&&prev_cost| prev_cost + 1 == cost);
-    if let Some(prev_cost) = prev_cost {
-        path_points.extend(check_path_to(goal, (prev_state, *prev_cost), costs));
-    }
-
-    path_points.insert(cur_state.0.0);
-    path_points
+/// Finds every tile on any of the cheapest paths, together with the search that led there.
+fn find_best_path_tiles(input: &Grid<char>) -> (Search<State, usize>, HashSet<Point>) {
+    let end = input.position(|&char| char == 'E').expect("No end found");
+    let search = search_maze(input, |&(point, _)| point == end);
+    let tiles = search.nodes_on_paths_to(search.goals()).into_iter().map(|(point, _)| point).collect();
+    (search, tiles)
+}
+
+fn describe_cost(input: &Grid<char>, point: Point, facing: Direction) -> Result<String, String> {
+    match input.get(point) {
+        None => return Err(format!("{} is outside the maze", point)),
+        Some('#') => return Err(format!("{} is a wall", point)),
+        Some(_) => {}
+    }
+    Ok(match search_maze(input, |_| false).cost(&(point, facing)) {
+        None => format!("{} facing {} can't be reached", point, facing.arrow()),
+        Some(cost) => format!("Lowest score at {} facing {}: {}", point, facing.arrow(), cost),
+    })
 }
 
-fn visit(state: State, costs: &mut HashMap<(Vec2D, Vec2D), usize>) -> Vec<State> {
-    let mut res = vec![];
-    let State { position: (coord, facing), cost } = state;
-    let forward = coord + facing;
-    costs.entry((forward, facing)).and_modify(|prev_cost| {
-        let new_cost = cost + 1;
-        if new_cost < *prev_cost {
-            *prev_cost = new_cost;
-            res.push(State { cost: new_cost, position: (forward, facing) });
+fn read_map(source: &Source) -> Result<Grid<char>, ParseError> {
+    let map = Grid::parse(source, &source.text)?;
+    for tile in ['S', 'E'] {
+        if map.position(|&char| char == tile).is_none() {
+            return Err(source.missing(&source.text, format_args!("a `{}` tile", tile)));
         }
-    });
-
-    let neighbors: Vec<(Vec2D, Vec2D)> = DIRECTIONS.iter().filter(|&&dir| dir != facing).map(|&dir| (coord, dir)).collect();
-    for neighbor in neighbors {
-        costs.entry(neighbor).and_modify(|prev_cost| {
-            let new_cost = cost + 1000;
-            if new_cost < *prev_cost {
-                *prev_cost = new_cost;
-                res.push(State { cost: new_cost, position: neighbor });
-            }
-        });
     }
-    res
+    Ok(map)
 }
 
-fn read_map(input_file: &str) -> HashMap<Vec2D, char> {
-    fs::read_to_string(input_file)
-        .expect("Failed to read input file")
-        .lines()
-        .enumerate()
-        .flat_map(move |(y, line)| line
-            .chars()
-            .enumerate()
-            .map(move |(x, c)| (Vec2D(x as isize, y as isize), c)))
-        .filter(|&(_, char)| char != '#')
-        .collect()
+/// A maze of `size` by `size` tiles, rounded up to an odd size of at least 5. A random depth
+/// first search carves it, then some walls between corridors are knocked out, so there are
+/// several ways, and often several best ones, from S to E.
+fn generate_maze(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(5) | 1;
+    let mut maze = Grid::new(size, size, '#');
+    let start = Point(1, size as isize - 2);
+    maze[start] = '.';
+    let mut stack = vec![start];
+    while let Some(&cell) = stack.last() {
+        let options: Vec<Point> = Direction::ALL
+            .into_iter()
+            .map(|dir| cell + dir.offset() * 2)
+            .filter(|&next| maze.get(next) == Some(&'#'))
+            .collect();
+        if options.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let next = *rng.choose(&options);
+        maze[(cell + next) / 2] = '.';
+        maze[next] = '.';
+        stack.push(next);
+    }
+    for y in 1..size as isize - 1 {
+        for x in 1..size as isize - 1 {
+            if (x + y) % 2 == 1 && rng.chance(1, 10) {
+                maze[Point(x, y)] = '.';
+            }
+        }
+    }
+    maze[start] = 'S';
+    maze[Point(size as isize - 2, 1)] = 'E';
+    maze.to_string()
 }
 
 #[cfg(test)]
@@ -221,16 +165,31 @@ mod tests {
 
     #[test]
     fn test_puzzle1() {
-        assert_eq!(puzzle1("./input_test/day_16.txt"), 7036);
+        assert_eq!(puzzle1(&Day16::load("./input_test/day_16.txt").unwrap()), Some(7036));
+    }
+
+    #[test]
+    fn test_unreachable_end() {
+        assert_eq!(Day16::solve("#####\n#S#E#\n#####\n"), Ok((String::from("none"), String::from("none"))));
     }
 
     #[test]
     fn test_puzzle2() {
-        assert_eq!(puzzle2("./input_test/day_16.txt"), 45);
+        assert_eq!(puzzle2(&Day16::load("./input_test/day_16.txt").unwrap()), Some(45));
     }
 
     #[test]
     fn test_puzzle2_2() {
-        assert_eq!(puzzle2("./input_test/day_16_2.txt"), 64);
+        assert_eq!(puzzle2(&Day16::load("./input_test/day_16_2.txt").unwrap()), Some(64));
+    }
+
+    #[test]
+    fn test_query_cost() {
+        let maze = Day16::load("./input_test/day_16.txt").unwrap();
+        assert_eq!(Day16::query(&maze, "cost", &["13,1", "up"]), Ok(String::from("Lowest score at (13,1) facing ^: 7036")));
+        assert_eq!(Day16::query(&maze, "cost", &["1,13", ">"]), Ok(String::from("Lowest score at (1,13) facing >: 0")));
+        assert_eq!(Day16::query(&maze, "cost", &["1,13", "up"]), Ok(String::from("Lowest score at (1,13) facing ^: 1000")));
+        assert_eq!(Day16::query(&maze, "cost", &["0,0", "up"]), Err(String::from("(0,0) is a wall")));
+        assert!(Day16::query(&maze, "cost", &["1,13"]).is_err());
     }
 }
@@ -1,6 +1,9 @@
-use std::{collections::{HashSet, VecDeque}, fs, iter::Rev, thread::sleep, time::Duration};
-#[derive(Copy, Clone, Hash, Eq, PartialEq)]
-struct Registry {
+use std::collections::HashSet;
+
+use crate::{params::Params, parse::{ParseError, Source}, random::Rng, solution::Solution};
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Registry {
     a: u64,
     b: u64,
     c: u64
@@ -17,10 +20,14 @@ impl Registry {
 }
 
 fn execute(registry: &mut Registry, exec_index: u64, operator: u64, operand: u64, out_val: &mut Vec<u64>, lookup: &mut HashSet<(Registry, u64)>) -> Option<u64> {
-    let combo_value = evaluate_operand(registry, operand);
+    // bxl, jnz and bxc read their operand literally, where 7 is fine
+    let combo_value = match operator {
+        1 | 3 | 4 => operand,
+        _ => evaluate_operand(registry, operand),
+    };
     match operator {
         0 => {
-            registry.a = registry.a >> combo_value;
+            registry.a = shift(registry.a, combo_value);
             Some(exec_index + 2)
         },
         1 => {
@@ -50,17 +57,22 @@ fn execute(registry: &mut Registry, exec_index: u64, operator: u64, operand: u64
             Some(exec_index + 2)
         },
         6 => {
-            registry.b = registry.a >> combo_value;
+            registry.b = shift(registry.a, combo_value);
             Some(exec_index + 2)
         },
         7 => {
-            registry.c = registry.a >> combo_value;
+            registry.c = shift(registry.a, combo_value);
             Some(exec_index + 2)
         },
         _ => panic!("Invalid operand: {}", operator)
     }
 }
 
+/// The division by a power of two of adv, bdv and cdv, which ends up at 0 for huge powers.
+fn shift(value: u64, by: u64) -> u64 {
+    u32::try_from(by).ok().and_then(|by| value.checked_shr(by)).unwrap_or(0)
+}
+
 fn evaluate_operand(registry: &Registry, val: u64) -> u64 {
     match val {
         0..=3 => val,
@@ -72,27 +84,44 @@ fn evaluate_operand(registry: &Registry, val: u64) -> u64 {
 }
 
 
-// Main function
-pub fn day_17() {
-    let input_file = "./input/day_17.txt";
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = (Registry, Vec<u64>);
+    type Answer1 = String;
+    type Answer2 = u64;
+
+    fn parse(source: &Source, _params: &Params) -> Result<(Registry, Vec<u64>), ParseError> {
+        read_registry_and_ops(source)
+    }
 
-    println!("Puzzle 1 result: {}", puzzle1(input_file));
-    println!("Puzzle 2 result: {}", puzzle2(input_file));
+    fn part1(input: &(Registry, Vec<u64>)) -> String {
+        puzzle1(input)
+    }
+
+    fn part2(input: &(Registry, Vec<u64>)) -> u64 {
+        puzzle2(input)
+    }
+
+    fn generate(rng: &mut Rng, size: usize, _params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(Some(generate_program(rng, size)))
+    }
 }
 
 // Puzzle 1 function
-fn puzzle1(input_file: &str) -> String {
-    let (registry, ops) = read_registry_and_ops(input_file);
-    
-    let out_vals = execute_operations(registry, &ops).expect("Expecting output values for puzzle 1");
-    out_vals
-        .iter()
-        .map(|val| val.to_string())
-        .collect::<Vec<String>>()
-        .join(",")
+fn puzzle1((registry, ops): &(Registry, Vec<u64>)) -> String {
+    // a program that never halts or halts without printing has no output to join
+    execute_operations(*registry, ops)
+        .filter(|out_vals| !out_vals.is_empty())
+        .map(|out_vals| out_vals
+            .iter()
+            .map(|val| val.to_string())
+            .collect::<Vec<String>>()
+            .join(","))
+        .unwrap_or_else(|| String::from("none"))
 }
 
-fn execute_operations(mut registry: Registry, ops: &Vec<u64>) -> Option<Vec<u64>> {
+fn execute_operations(mut registry: Registry, ops: &[u64]) -> Option<Vec<u64>> {
     let mut pointer = 0;
     let mut out_vals: Vec<u64> = Vec::new();
     let mut lookup: HashSet<(Registry, u64)> = HashSet::new();
@@ -104,56 +133,74 @@ fn execute_operations(mut registry: Registry, ops: &Vec<u64>) -> Option<Vec<u64>
     Some(out_vals)
 }
 
-fn read_registry_and_ops(input_file: &str) -> (Registry, Vec<u64>) {
-    let input = fs::read_to_string(input_file).expect("Failed to read input file");
-    let mut registries = input
-        .lines()
-        .take(3)
-        .flat_map(|line| line
-            .split_whitespace()
-            .skip(2)
-            .map(|str| str.parse().expect("Expecting u64 for registry entry")));
+fn read_registry_and_ops(source: &Source) -> Result<(Registry, Vec<u64>), ParseError> {
+    let (registers, program) = source.sections(&source.text)?;
+    let mut lines = registers.lines();
+    let mut register = |name: &str| {
+        let line = source.next(&mut lines, registers, format_args!("a `Register {}` line", name))?;
+        source.number(source.strip_prefix(line, &format!("Register {}: ", name))?)
+    };
     let registry = Registry {
-        a: registries.next().expect("Expecting registry entry A"),
-        b: registries.next().expect("Expecting registry entry B"),
-        c: registries.next().expect("Expecting registry entry C")
+        a: register("A")?,
+        b: register("B")?,
+        c: register("C")?
     };
-    let program = input
-        .lines()
-        .skip(4)
-        .next()
-        .expect("Expecting program line")
-        .split_whitespace()
-        .skip(1)
-        .flat_map(|str| str
-            .split(',')
-            .flat_map(|num| num
-                .parse()
-                .map_err(|_| "Expecting u64 for program entry")))
-        .collect();
-    (registry, program)
+    let program = source.strip_prefix(program.trim_end(), "Program: ")?;
+    let tokens: Vec<&str> = program.split(',').collect();
+    let ops = tokens
+        .iter()
+        .map(|&token| match source.number(token)? {
+            value @ 0..=7 => Ok(value),
+            _ => Err(source.error(token, "a 3-bit number from 0 to 7")),
+        })
+        .collect::<Result<Vec<u64>, _>>()?;
+    // adv, bst, out, bdv and cdv take a combo operand, which can't be 7, and jumping to an odd
+    // address would read operands as instructions
+    for (pair, instruction) in ops.chunks(2).enumerate() {
+        match instruction {
+            [0 | 2 | 5 | 6 | 7, 7] => return Err(source.error(tokens[pair * 2 + 1], "a combo operand from 0 to 6")),
+            [3, target] if target % 2 == 1 => return Err(source.error(tokens[pair * 2 + 1], "an even jump target")),
+            _ => {}
+        }
+    }
+    if ops.len() % 2 == 1 {
+        return Err(source.missing(program, "an operand after the last instruction"));
+    }
+    Ok((registry, ops))
 }
 
-// Puzzle 2 function
-fn puzzle2(input_file: &str) -> u64 {
-    let (_, ops) = read_registry_and_ops(input_file);
+/// A program shaped like the real ones: it mixes the lowest three bits of `A` with higher ones,
+/// prints the result and shifts `A` down until it is 0. `A` starts with `size` octal digits, at
+/// most 21.
+fn generate_program(rng: &mut Rng, size: usize) -> String {
+    let digits = size.clamp(1, 21) as u32;
+    let a = rng.range(8usize.pow(digits - 1)..=8usize.pow(digits) - 1);
+    let program = [2, 4, 1, rng.below(8), 7, 5, 1, rng.below(8), 4, rng.below(8), 5, 5, 0, 3, 3, 0];
+    let program: Vec<String> = program.iter().map(|op| op.to_string()).collect();
+    format!("Register A: {}\nRegister B: 0\nRegister C: 0\n\nProgram: {}\n", a, program.join(","))
+}
 
+// Puzzle 2 function
+fn puzzle2((_, ops): &(Registry, Vec<u64>)) -> u64 {
     (0..8)
-        .map(|i| find_next(i, &ops))
+        .map(|i| find_next(i, ops))
         .filter(|&val| val != 0)
         .min()
         .unwrap_or_default()
 }
 
-fn find_next(a: u64, ops: &Vec<u64>) -> u64 {
+fn find_next(a: u64, ops: &[u64]) -> u64 {
     execute_operations(Registry::new(a), ops)
-        .filter(|out_vals| ops.ends_with(&out_vals))
+        .filter(|out_vals| ops.ends_with(out_vals))
         .map(|out_vals| {
             if ops.len() == out_vals.len() {
                 vec![a]
             } else {
+                // for `a == 0` the first candidate is `a` itself, which would recurse forever
                 (0..8)
-                    .map(|j| find_next(j + (a << 3), ops))
+                    .map(|j| j + (a << 3))
+                    .filter(|&next| next != a)
+                    .map(|next| find_next(next, ops))
                     .filter(|found_val| *found_val != 0)
                     .collect()
             }
@@ -161,7 +208,7 @@ fn find_next(a: u64, ops: &Vec<u64>) -> u64 {
         .unwrap_or_default()
         .into_iter()
         .min()
-        .expect("No result found!")
+        .unwrap_or_default()
 }
 
 #[cfg(test)]
@@ -170,11 +217,37 @@ mod tests {
 
     #[test]
     fn test_puzzle1() {
-        assert_eq!(puzzle1("./input_test/day_17.txt"), "4,6,3,5,6,3,5,2,1,0");
+        assert_eq!(puzzle1(&Day17::load("./input_test/day_17.txt").unwrap()), "4,6,3,5,6,3,5,2,1,0");
+    }
+
+    #[test]
+    fn test_read_checks_program() {
+        let found = |program: &str| {
+            let text = format!("Register A: 7\nRegister B: 0\nRegister C: 0\n\nProgram: {}\n", program);
+            let err = Day17::parse(&Source::new("test", text), &Day17::params()).unwrap_err();
+            (err.column, err.expected, err.found)
+        };
+        assert_eq!(found("0,1,8,0"), (14, String::from("a 3-bit number from 0 to 7"), String::from("`8`")));
+        assert_eq!(found("1,7,5,7"), (16, String::from("a combo operand from 0 to 6"), String::from("`7`")));
+        assert_eq!(found("3,1,5,4"), (12, String::from("an even jump target"), String::from("`1`")));
+        assert_eq!(found("0,1,5"), (15, String::from("an operand after the last instruction"), String::from("end of line")));
+    }
+
+    #[test]
+    fn test_literal_operand_seven() {
+        let program = "Register A: 2024\nRegister B: 0\nRegister C: 0\n\nProgram: 1,7,5,5\n";
+        assert_eq!(puzzle1(&Day17::parse(&Source::new("test", program), &Day17::params()).unwrap()), "7");
+        assert_eq!(shift(u64::MAX, 64), 0);
     }
 
     #[test]
-    fn test_puzzle2() {
-        assert_eq!(puzzle2("./input/day_17.txt"), 107416870455451);
+    fn test_no_output() {
+        let output = |program: &str| {
+            let text = format!("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: {}\n", program);
+            puzzle1(&Day17::parse(&Source::new("test", text), &Day17::params()).unwrap())
+        };
+        // jumps back to itself forever, as `A` never changes
+        assert_eq!(output("3,0"), "none");
+        assert_eq!(output("1,1"), "none");
     }
 }
@@ -1,45 +1,112 @@
-use std::fs;
+use std::collections::HashSet;
 
-// Main function
-pub fn day_18() {
-    let input_file = "./input/day_18.txt";
+use crate::{grid::{Grid, Point}, params::Params, parse::{ParseError, Source}, random::Rng, search, solution::Solution};
 
-    println!("Puzzle 1 result: {}", puzzle1(input_file));
-    println!("Puzzle 2 result: {}", puzzle2(input_file));
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = (usize, usize, Vec<(usize, usize)>);
+    /// The fewest steps to the exit, or `none` if the fallen bytes cut it off.
+    type Answer1 = String;
+    type Answer2 = String;
+
+    /// The memory space is `size` + 1 cells wide and high, and part 1 looks at the first `bytes` bytes.
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("size", "70"), ("bytes", "1024")];
+
+    fn parse(source: &Source, params: &Params) -> Result<(usize, usize, Vec<(usize, usize)>), ParseError> {
+        let size = params.get("size")?;
+        Ok((size, params.get("bytes")?, read_bytes(source, size)?))
+    }
+
+    fn part1(input: &(usize, usize, Vec<(usize, usize)>)) -> String {
+        puzzle1(input).map_or_else(|| String::from("none"), |steps| steps.to_string())
+    }
+
+    fn part2(input: &(usize, usize, Vec<(usize, usize)>)) -> String {
+        puzzle2(input)
+    }
+
+    fn generate(rng: &mut Rng, size: usize, params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(Some(generate_bytes(rng, size, params.get("size")?, params.get("bytes")?)))
+    }
 }
 
-#[cfg(test)]
-fn get_grid_size() -> usize {
-    6
+fn read_bytes(source: &Source, size: usize) -> Result<Vec<(usize, usize)>, ParseError> {
+    source.text
+        .lines()
+        .map(|line| {
+            let (x, y) = source.split_once(line, ",")?;
+            let byte = (source.number(x)?, source.number(y)?);
+            if byte.0 > size || byte.1 > size {
+                return Err(source.error(line, format_args!("a position in the {0}x{0} memory space", size + 1)));
+            }
+            Ok(byte)
+        })
+        .collect()
 }
 
-#[cfg(not(test))]
-fn get_grid_size() -> usize {
-    70
+/// `count` bytes falling onto distinct cells of a memory space `size` + 1 cells wide, never onto
+/// the start or the exit. The first `fallen` of them miss a random staircase from the start to
+/// the exit, so part 1 always finds a way out.
+fn generate_bytes(rng: &mut Rng, count: usize, size: usize, fallen: usize) -> String {
+    let mut staircase = HashSet::from([(0, 0)]);
+    let (mut x, mut y) = (0, 0);
+    while (x, y) != (size, size) {
+        if y == size || (x < size && rng.chance(1, 2)) {
+            x += 1;
+        } else {
+            y += 1;
+        }
+        staircase.insert((x, y));
+    }
+    let mut cells: Vec<(usize, usize)> = (0..=size)
+        .flat_map(|y| (0..=size).map(move |x| (x, y)))
+        .filter(|&cell| cell != (0, 0) && cell != (size, size))
+        .collect();
+    rng.shuffle(&mut cells);
+    let (mut bytes, on_staircase): (Vec<_>, Vec<_>) = cells.into_iter().partition(|cell| !staircase.contains(cell));
+    let mut later = bytes.split_off(fallen.min(bytes.len()));
+    later.extend(on_staircase);
+    rng.shuffle(&mut later);
+    bytes.extend(later);
+    bytes.truncate(count);
+    bytes.iter().map(|(x, y)| format!("{},{}\n", x, y)).collect()
 }
 
 // Puzzle 1 function
-fn puzzle1(input_file: &str) -> usize {
-    let input: Vec<(usize, usize)> = fs::read_to_string(input_file)
-        .expect("Failed to read input file")
-        .lines()
-        .map(|line| {
-            let mut coord_iter = 
-                line
-                .split(',')
-                .map(|num| num
-                    .parse()
-                    .expect("Expecting number")
-                );
-            (coord_iter.next().expect("Expecting x coord"), coord_iter.next().expect("Expecting y coord"))
-        }).collect();
-    0
+fn puzzle1((size, fallen, bytes): &(usize, usize, Vec<(usize, usize)>)) -> Option<usize> {
+    steps_to_exit(*size, &bytes[..(*fallen).min(bytes.len())])
+}
+
+/// The fewest steps from the top left corner to the bottom right one, avoiding the `corrupted`
+/// cells, None if they cut the exit off.
+fn steps_to_exit(size: usize, corrupted: &[(usize, usize)]) -> Option<usize> {
+    let mut memory = Grid::new(size + 1, size + 1, false);
+    for &(x, y) in corrupted {
+        memory[Point(x as isize, y as isize)] = true;
+    }
+    let exit = Point(size as isize, size as isize);
+    let memory = &memory;
+    let open = move |&point: &Point| memory.neighbors4(point).filter(move |&next| !memory[next]);
+    search::bfs([Point(0, 0)], open, |&point| point == exit).goal_cost()
 }
 
 // Puzzle 2 function
-fn puzzle2(input_file: &str) -> usize {
-    let input = fs::read_to_string(input_file).expect("Failed to read input file");
-    0
+fn puzzle2((size, _fallen, bytes): &(usize, usize, Vec<(usize, usize)>)) -> String {
+    // the exit stays cut off once it is, so search for the fewest bytes that do it
+    let (mut reachable, mut blocked) = (0, bytes.len() + 1);
+    while blocked - reachable > 1 {
+        let middle = (reachable + blocked) / 2;
+        if steps_to_exit(*size, &bytes[..middle]).is_some() {
+            reachable = middle;
+        } else {
+            blocked = middle;
+        }
+    }
+    match bytes.get(reachable) {
+        Some((x, y)) if blocked <= bytes.len() => format!("{},{}", x, y),
+        _ => String::from("none"),
+    }
 }
 
 #[cfg(test)]
@@ -48,11 +115,34 @@ mod tests {
 
     #[test]
     fn test_puzzle1() {
-        assert_eq!(puzzle1("./input_test/day_18.txt"), 0);
+        let params = Day18::params().with("size", 6).unwrap().with("bytes", 12).unwrap();
+        assert_eq!(puzzle1(&Day18::load_with("./input_test/day_18.txt", &params).unwrap()), Some(22));
+        assert_eq!(Day18::part1(&(6, 12, vec![(1, 0), (0, 1)])), "none");
     }
 
     #[test]
     fn test_puzzle2() {
-        assert_eq!(puzzle2("./input_test/day_18.txt"), 0);
+        let params = Day18::params().with("size", 6).unwrap().with("bytes", 12).unwrap();
+        assert_eq!(puzzle2(&Day18::load_with("./input_test/day_18.txt", &params).unwrap()), "6,1");
+        assert_eq!(puzzle2(&(6, 12, vec![(1, 0), (2, 2)])), "none");
+    }
+
+    #[test]
+    fn test_read_rejects_bytes_outside_the_memory_space() {
+        let params = Day18::params().with("size", 6).unwrap();
+        let err = Day18::parse(&Source::new("bytes", "1,2\n7,0\n"), &params).unwrap_err();
+        assert_eq!((err.line, err.expected.as_str()), (2, "a position in the 7x7 memory space"));
+    }
+
+    #[test]
+    fn test_generated_bytes_leave_a_way_out() {
+        let params = Day18::params().with("size", 6).unwrap().with("bytes", 30).unwrap();
+        for seed in 0..20 {
+            let text = Day18::generate(&mut Rng::new(seed), 40, &params).unwrap().unwrap();
+            let input = Day18::parse(&Source::new("generated", &text), &params).unwrap();
+            assert_eq!(input.2.len(), 40);
+            assert!(puzzle1(&input).is_some_and(|steps| steps >= 12));
+            assert_ne!(puzzle2(&input), "none");
+        }
     }
 }
@@ -0,0 +1,150 @@
+use std::fs;
+
+use aoc2024::{params::Params, parse::Source, solution::{self, Part}};
+
+use crate::{answers::Verdict, toml};
+
+pub const EXAMPLES_FILE: &str = "./input_test/examples.toml";
+
+/// One example input from `input_test/`, with the answers the puzzle text gives for it.
+#[derive(Debug, PartialEq)]
+pub struct Example {
+    pub day: u8,
+    pub file: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+    pub params: Vec<(String, String)>,
+    pub ignore: Option<String>,
+}
+
+impl Example {
+    pub fn path(&self) -> String {
+        format!("./input_test/{}", self.file)
+    }
+
+    /// Overrides `params` with the ones this example sets.
+    pub fn apply_params(&self, params: &mut Params) -> Result<(), String> {
+        for (name, value) in &self.params {
+            params.set(name, value).map_err(|err| format!("{}: {}", self.file, err))?;
+        }
+        Ok(())
+    }
+
+    pub fn expected(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part1.as_deref(),
+            Part::Two => self.part2.as_deref(),
+        }
+    }
+}
+
+pub fn load(path: &str) -> Result<Vec<Example>, String> {
+    let content = fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+    parse(&content).map_err(|err| format!("{}: {}", path, err))
+}
+
+pub fn parse(content: &str) -> Result<Vec<Example>, String> {
+    toml::parse(content)?
+        .into_iter()
+        .map(|table| {
+            if table.name != "example" {
+                return Err(format!("line {}: expected [[example]], got [{}]", table.line, table.name));
+            }
+            let text = |key: &str| table.get(key).map(toml::Value::as_text);
+            let day = text("day")
+                .and_then(|day| day.parse().ok())
+                .ok_or(format!("line {}: example needs a numeric day", table.line))?;
+            let file = text("file").ok_or(format!("line {}: example needs a file", table.line))?;
+            let params = table
+                .entries
+                .iter()
+                .filter_map(|(key, value)| key
+                    .strip_prefix("params.")
+                    .map(|param| (String::from(param), value.as_text())))
+                .collect();
+            Ok(Example {
+                day,
+                file,
+                part1: text("part1"),
+                part2: text("part2"),
+                params,
+                ignore: text("ignore"),
+            })
+        })
+        .collect()
+}
+
+/// Solves the example and compares every part that has an expected answer.
+pub fn check(example: &Example) -> Result<Vec<(Part, String, Verdict)>, String> {
+    let entry = solution::find(example.day).ok_or(format!("No solution registered for day {}", example.day))?;
+    let mut params = entry.solution.params();
+    example.apply_params(&mut params)?;
+    let input = Source::read(&example.path())
+        .and_then(|source| entry.solution.parse(&source, &params))
+        .map_err(|err| err.to_string())?;
+    Ok(Part::BOTH
+        .into_iter()
+        .filter_map(|part| {
+            let expected = example.expected(part)?;
+            let result = entry.solve(input.as_ref(), part);
+            let verdict = if result == expected {
+                Verdict::Ok
+            } else {
+                Verdict::Wrong(String::from(expected))
+            };
+            Some((part, result, verdict))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_examples() {
+        let examples = parse("[[example]]\nday = 14\nfile = \"day_14.txt\"\npart1 = 12\nparams.width = 11\nignore = \"later\"\n\n[[example]]\nday = 17\nfile = \"day_17.txt\"\npart1 = \"4,6,3\"\n").unwrap();
+        assert_eq!(examples, vec![
+            Example {
+                day: 14,
+                file: String::from("day_14.txt"),
+                part1: Some(String::from("12")),
+                part2: None,
+                params: vec![(String::from("width"), String::from("11"))],
+                ignore: Some(String::from("later"))
+            },
+            Example {
+                day: 17,
+                file: String::from("day_17.txt"),
+                part1: Some(String::from("4,6,3")),
+                part2: None,
+                params: vec![],
+                ignore: None
+            }
+        ]);
+        assert!(parse("[[sample]]\nday = 1\nfile = \"a.txt\"").is_err());
+        assert!(parse("[[example]]\nfile = \"a.txt\"").is_err());
+        assert!(parse("[[example]]\nday = 1").is_err());
+    }
+
+    #[test]
+    fn test_all_examples() {
+        let examples = load(EXAMPLES_FILE).unwrap();
+        let mut failures = vec![];
+        for example in examples.iter().filter(|example| example.ignore.is_none()) {
+            for (part, result, verdict) in check(example).unwrap() {
+                if verdict != Verdict::Ok {
+                    failures.push(format!("{} part {}: got {} [{}]", example.file, part, result, verdict));
+                }
+            }
+        }
+        assert!(failures.is_empty(), "Failing examples:\n{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn test_examples_exist() {
+        for example in load(EXAMPLES_FILE).unwrap() {
+            assert!(fs::metadata(example.path()).is_ok(), "Missing example file {}", example.path());
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! A small mutation fuzzer for the days. Every case takes an input from a corpus, usually the
+//! day's example and a generated input, mangles it a few times, parses the result and solves both
+//! parts if it parsed. Parsers have to answer anything with an input or a
+//! [`ParseError`](crate::parse::ParseError), and whatever they accept the parts have to solve, so
+//! every panic is a crash.
+
+use std::{
+    cell::RefCell,
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::{params::Params, parse::Source, property, random::Rng, solution::Entry};
+
+/// Bits of the input formats of all days, so mutations produce inputs that get past the first checks.
+const TOKENS: &[&str] = &[
+    "0", "1", "-1", "99999999999999999999999", "\n", "\n\n", "\r\n", " ", "   ", "\t", ",", ":", ": ", "=", "+",
+    "|", "#", ".", "@", "^", "S", "E", "O", "[", "]", "<", ">", "v", "mul(", "do()", "don't()", "p=", " v=",
+    "Button A: X+", "Button B: X+", ", Y+", "Prize: X=", ", Y=", "Register A: ", "Register B: ", "Register C: ",
+    "Program: ", "é", "\u{1f384}",
+];
+
+#[derive(Debug, PartialEq)]
+pub struct Crash {
+    pub input: String,
+    pub message: String,
+}
+
+thread_local! {
+    static LAST_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Keeps panics from printing while fuzzing, remembering where they happened for the crash report instead.
+pub fn quiet_panics() {
+    panic::set_hook(Box::new(|info| {
+        LAST_PANIC.with(|last| *last.borrow_mut() = Some(info.to_string()));
+    }));
+}
+
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    LAST_PANIC
+        .with(|last| last.borrow_mut().take())
+        .unwrap_or_else(|| property::panic_message(panic))
+}
+
+/// Applies one to four random edits to `text`: deleting, repeating or replacing a few
+/// characters, inserting a token, cutting the text short, or dropping or swapping lines.
+pub fn mutate(rng: &mut Rng, text: &str) -> String {
+    let mut chars: Vec<char> = text.chars().collect();
+    for _ in 0..rng.range(1..=4) {
+        let at = rng.below(chars.len() + 1);
+        let end = (at + rng.range(1..=8)).min(chars.len());
+        match rng.below(7) {
+            0 => {
+                chars.drain(at..end);
+            }
+            1 => {
+                let repeated = chars[at..end].to_vec();
+                chars.splice(at..at, repeated);
+            }
+            2 if at < chars.len() => chars[at] = char::from(rng.range(0x20..=0x7e) as u8),
+            3 => chars.truncate(at),
+            4 | 5 => {
+                let mut lines: Vec<String> = chars.iter().collect::<String>().split('\n').map(String::from).collect();
+                let (line, other) = (rng.below(lines.len()), rng.below(lines.len()));
+                if rng.chance(1, 2) {
+                    lines.remove(line);
+                } else {
+                    lines.swap(line, other);
+                }
+                chars = lines.join("\n").chars().collect();
+            }
+            _ => {
+                chars.splice(at..at, rng.choose(TOKENS).chars());
+            }
+        }
+    }
+    chars.into_iter().collect()
+}
+
+/// Parses and solves `cases` mutations of the inputs in `corpus` with `params`, returning the
+/// crashes, one per distinct panic.
+pub fn fuzz(entry: &Entry, corpus: &[String], params: &Params, cases: usize, rng: &mut Rng) -> Vec<Crash> {
+    assert!(!corpus.is_empty(), "Expecting at least one input to start from");
+    let mut crashes: Vec<Crash> = vec![];
+    for _ in 0..cases {
+        let base = rng.choose(corpus);
+        let input = mutate(rng, base);
+        let solved = panic::catch_unwind(AssertUnwindSafe(|| {
+            // the input is dropped right here, so a panicking drop counts too
+            if let Ok(parsed) = entry.solution.parse(&Source::new("fuzzed", &input), params) {
+                entry.solution.part1(parsed.as_ref());
+                entry.solution.part2(parsed.as_ref());
+            }
+        }));
+        if let Err(panic) = solved {
+            let message = panic_message(panic.as_ref());
+            if crashes.iter().all(|crash| crash.message != message) {
+                crashes.push(Crash { input, message });
+            }
+        }
+    }
+    crashes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::SOLUTIONS;
+
+    #[test]
+    fn test_mutate() {
+        let text = "p=0,4 v=3,-3\np=6,3 v=-1,-3\n";
+        let mutations = |seed, text| {
+            let mut rng = Rng::new(seed);
+            (0..100).map(|_| mutate(&mut rng, text)).collect::<Vec<_>>()
+        };
+        assert_eq!(mutations(4, text), mutations(4, text));
+        // swapping a line with itself or cutting at the very end changes nothing, but that's rare
+        assert!(mutations(4, text).iter().filter(|&mutation| mutation != text).count() > 90);
+        assert!(mutations(4, "").iter().all(|mutation| mutation.len() < 100));
+    }
+
+    #[test]
+    fn test_days_survive_fuzzing() {
+        for entry in SOLUTIONS {
+            let mut corpus = vec![Source::read(&entry.example_file()).unwrap().text];
+            // days don't have to come with a generator
+            corpus.extend(entry.solution.generate(&mut Rng::new(0), 8, &entry.solution.params()).unwrap());
+            // every case is solved too, so keep the count low enough for a debug build, and blink less
+            // on day 11, which counts every stone of part 1 one by one
+            let params = match entry.day {
+                11 => entry.solution.params().with("blinks1", 15).unwrap().with("blinks2", 25).unwrap(),
+                _ => entry.solution.params(),
+            };
+            let crashes = fuzz(entry, &corpus, &params, 100, &mut Rng::new(u64::from(entry.day)));
+            assert_eq!(crashes, vec![], "day {} has crashes", entry.day);
+        }
+    }
+}
@@ -0,0 +1,461 @@
+//! 2D grid helpers shared by the map based days.
+
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign, Div, Index, IndexMut, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use crate::parse::{ParseError, Source};
+
+/// A position or offset on a grid. Signed, so offsets and positions just outside the grid are representable.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct Point(pub isize, pub isize);
+
+/// Offsets to all eight surrounding cells, clockwise starting at the top.
+pub const NEIGHBORS_8: [Point; 8] = [
+    Point(0, -1),
+    Point(1, -1),
+    Point(1, 0),
+    Point(1, 1),
+    Point(0, 1),
+    Point(-1, 1),
+    Point(-1, 0),
+    Point(-1, -1),
+];
+
+/// Offsets to the four diagonal cells, clockwise starting at the top right.
+pub const DIAGONALS: [Point; 4] = [Point(1, -1), Point(1, 1), Point(-1, 1), Point(-1, -1)];
+
+impl Point {
+    /// Wraps the point into `0..bounds` on both axes, as if the grid repeated infinitely.
+    pub fn rem_euclid(self, bounds: Point) -> Point {
+        Point(self.0.rem_euclid(bounds.0), self.1.rem_euclid(bounds.1))
+    }
+
+    /// The four orthogonally adjacent points, in [`Direction::ALL`] order.
+    pub fn neighbors4(self) -> impl Iterator<Item = Point> {
+        Direction::ALL.into_iter().map(move |dir| self + dir)
+    }
+
+    pub fn neighbors8(self) -> impl Iterator<Item = Point> {
+        NEIGHBORS_8.into_iter().map(move |offset| self + offset)
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{})", self.0, self.1)
+    }
+}
+
+/// Reads `x,y`, optionally in parentheses like it is displayed.
+impl FromStr for Point {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Point, String> {
+        let inner = text.strip_prefix('(').and_then(|rest| rest.strip_suffix(')')).unwrap_or(text);
+        inner
+            .split_once(',')
+            .and_then(|(x, y)| Some(Point(x.trim().parse().ok()?, y.trim().parse().ok()?)))
+            .ok_or(format!("expected a point like 3,4, got `{}`", text))
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Point;
+
+    fn mul(self, rhs: isize) -> Self::Output {
+        Point(self.0 * rhs, self.1 * rhs)
+    }
+}
+
+impl Div<isize> for Point {
+    type Output = Point;
+
+    fn div(self, rhs: isize) -> Self::Output {
+        Point(self.0 / rhs, self.1 / rhs)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Self::Output {
+        Point(-self.0, -self.1)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All directions, clockwise starting at the top.
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    pub fn offset(self) -> Point {
+        match self {
+            Direction::Up => Point(0, -1),
+            Direction::Right => Point(1, 0),
+            Direction::Down => Point(0, 1),
+            Direction::Left => Point(-1, 0),
+        }
+    }
+
+    pub fn turn_right(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn turn_left(self) -> Direction {
+        self.turn_right().opposite()
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.turn_right().turn_right()
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, Direction::Left | Direction::Right)
+    }
+
+    pub fn arrow(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        }
+    }
+
+    /// Parses the arrow characters `^`, `>`, `v` and `<`.
+    pub fn from_arrow(char: char) -> Option<Direction> {
+        match char {
+            '^' => Some(Direction::Up),
+            '>' => Some(Direction::Right),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+}
+
+/// Reads an arrow like `>` or a name like `right`.
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Direction, String> {
+        let mut chars = text.chars();
+        let arrow = chars.next().filter(|_| chars.next().is_none()).and_then(Direction::from_arrow);
+        let name = match text.to_lowercase().as_str() {
+            "up" => Some(Direction::Up),
+            "right" => Some(Direction::Right),
+            "down" => Some(Direction::Down),
+            "left" => Some(Direction::Left),
+            _ => None,
+        };
+        arrow.or(name).ok_or(format!("expected a direction like up or >, got `{}`", text))
+    }
+}
+
+impl Add<Direction> for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Direction) -> Self::Output {
+        self + rhs.offset()
+    }
+}
+
+impl AddAssign<Direction> for Point {
+    fn add_assign(&mut self, rhs: Direction) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<Direction> for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Direction) -> Self::Output {
+        self - rhs.offset()
+    }
+}
+
+impl SubAssign<Direction> for Point {
+    fn sub_assign(&mut self, rhs: Direction) {
+        *self = *self - rhs;
+    }
+}
+
+/// A dense, row-major grid of cells.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> where T: Clone {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// A grid with every cell set by `cell`, row by row.
+    pub fn from_fn(width: usize, height: usize, mut cell: impl FnMut(Point) -> T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: (0..width * height)
+                .map(|index| cell(Point((index % width) as isize, (index / width) as isize)))
+                .collect(),
+        }
+    }
+
+    /// Parses one row per line of `text`, a slice of the source, handing every character to `cell` as a slice
+    /// so it can report errors at its position.
+    pub fn parse_with(
+        source: &Source,
+        text: &str,
+        mut cell: impl FnMut(&str) -> Result<T, ParseError>
+    ) -> Result<Grid<T>, ParseError> {
+        let mut width = None;
+        let mut cells = vec![];
+        let mut height = 0;
+        for line in text.lines() {
+            let mut row_width = 0;
+            for (index, char) in line.char_indices() {
+                if width.is_some_and(|width| row_width == width) {
+                    return Err(source.error(&line[index..], "end of line"));
+                }
+                cells.push(cell(&line[index..index + char.len_utf8()])?);
+                row_width += 1;
+            }
+            let width = *width.get_or_insert(row_width);
+            if row_width < width {
+                return Err(source.missing(line, format_args!("a row of {} cells", width)));
+            }
+            height += 1;
+        }
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        point.0 >= 0 && point.1 >= 0 && (point.0 as usize) < self.width && (point.1 as usize) < self.height
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        self.contains(point).then(|| point.1 as usize * self.width + point.0 as usize)
+    }
+
+    fn point_of(&self, index: usize) -> Point {
+        Point((index % self.width) as isize, (index / self.width) as isize)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|index| &mut self.cells[index])
+    }
+
+    /// Sets the cell at `point`, returning whether it was within the grid.
+    pub fn set(&mut self, point: Point, value: T) -> bool {
+        self.get_mut(point).map(|cell| *cell = value).is_some()
+    }
+
+    pub fn swap(&mut self, a: Point, b: Point) {
+        let a = self.index_of(a).unwrap_or_else(|| panic!("Expecting {} to be within the grid", a));
+        let b = self.index_of(b).unwrap_or_else(|| panic!("Expecting {} to be within the grid", b));
+        self.cells.swap(a, b);
+    }
+
+    /// All cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| (self.point_of(index), cell))
+    }
+
+    /// Finds the first cell, row by row, that matches `predicate`.
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Point> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .map(|index| self.point_of(index))
+    }
+
+    /// The orthogonally adjacent points that are within the grid.
+    pub fn neighbors4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point.neighbors4().filter(|&neighbor| self.contains(neighbor))
+    }
+
+    /// All surrounding points, including diagonals, that are within the grid.
+    pub fn neighbors8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point.neighbors8().filter(|&neighbor| self.contains(neighbor))
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl Grid<char> {
+    pub fn parse(source: &Source, text: &str) -> Result<Grid<char>, ParseError> {
+        Grid::parse_with(source, text, |cell| Ok(cell.chars().next().unwrap_or_default()))
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &Self::Output {
+        self.get(point).unwrap_or_else(|| panic!("Expecting {} to be within the grid", point))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut Self::Output {
+        self.get_mut(point).unwrap_or_else(|| panic!("Expecting {} to be within the grid", point))
+    }
+}
+
+impl Display for Grid<char> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.cells.chunks(self.width.max(1)) {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_ops() {
+        assert_eq!(Point(8, 1) - Point(5, 2), Point(3, -1));
+        assert_eq!(Point(8, 1) + Point(3, -1), Point(11, 0));
+        assert_eq!(Point(2, -3) * 3, Point(6, -9));
+        assert_eq!(Point(-1, 12).rem_euclid(Point(11, 7)), Point(10, 5));
+        assert_eq!(Point(1, 1) + Direction::Up, Point(1, 0));
+    }
+
+    #[test]
+    fn test_direction_turns() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.opposite(), Direction::Right);
+        assert_eq!(Direction::from_arrow('v'), Some(Direction::Down));
+        assert_eq!(Direction::from_arrow('x'), None);
+        assert!(Direction::ALL.into_iter().all(|dir| Direction::from_arrow(dir.arrow()) == Some(dir)));
+    }
+
+    #[test]
+    fn test_parse_points_and_directions() {
+        assert_eq!("3,4".parse(), Ok(Point(3, 4)));
+        assert_eq!("(-1, 12)".parse(), Ok(Point(-1, 12)));
+        assert_eq!("3".parse::<Point>(), Err(String::from("expected a point like 3,4, got `3`")));
+        assert_eq!("Left".parse(), Ok(Direction::Left));
+        assert_eq!("^".parse(), Ok(Direction::Up));
+        assert!("^^".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn test_grid_parse_and_access() {
+        let source = Source::new("grid.txt", "ab\ncd\nef");
+        let mut grid = Grid::parse(&source, &source.text).unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid[Point(1, 2)], 'f');
+        assert_eq!(grid.get(Point(2, 0)), None);
+        assert_eq!(grid.get(Point(-1, 0)), None);
+        assert_eq!(grid.position(|&char| char == 'd'), Some(Point(1, 1)));
+        assert!(grid.set(Point(0, 0), 'x'));
+        assert!(!grid.set(Point(0, 3), 'x'));
+        grid.swap(Point(0, 0), Point(1, 2));
+        assert_eq!(grid.to_string(), "fb\ncd\nex\n");
+    }
+
+    #[test]
+    fn test_grid_neighbors() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(grid.neighbors4(Point(0, 0)).collect::<Vec<_>>(), vec![Point(1, 0), Point(0, 1)]);
+        assert_eq!(grid.neighbors4(Point(1, 1)).count(), 4);
+        assert_eq!(grid.neighbors8(Point(0, 0)).count(), 3);
+        assert_eq!(grid.neighbors8(Point(1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn test_grid_parse_errors() {
+        let source = Source::new("grid.txt", "abc\nde\n");
+        let err = Grid::parse(&source, &source.text).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.expected, "a row of 3 cells");
+
+        let source = Source::new("grid.txt", "ab\ncde\n");
+        let err = Grid::parse(&source, &source.text).unwrap_err();
+        assert_eq!((err.line, err.column, err.found.as_str()), (2, 3, "`e`"));
+
+        let source = Source::new("grid.txt", "12\n3x\n");
+        let err = Grid::parse_with(&source, &source.text, |cell| source.parse::<u32>(cell, "a digit")).unwrap_err();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (2, 2, "a digit"));
+    }
+}
@@ -0,0 +1,302 @@
+//! Just enough HTTP/1.1 to talk to the puzzle server, and to stand in for it in tests. Plain
+//! `http://` requests go over a [`TcpStream`]; std has no TLS, so `https://` ones are handed to
+//! `curl`.
+
+use std::{
+    fmt::Write as _,
+    io::{BufRead, BufReader, Write},
+    net::TcpStream,
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Url {
+    pub secure: bool,
+    pub host: String,
+    pub port: u16,
+    /// Everything after the host, without a trailing slash, so paths can be appended.
+    pub path: String,
+}
+
+impl Url {
+    pub fn parse(url: &str) -> Result<Url, String> {
+        let invalid = || format!("invalid URL {}, expected http://host[:port][/path] or https://...", url);
+        let (secure, rest) = match url.split_once("://") {
+            Some(("http", rest)) => (false, rest),
+            Some(("https", rest)) => (true, rest),
+            _ => return Err(invalid()),
+        };
+        let (authority, path) = rest.split_once('/').map_or((rest, ""), |(authority, path)| (authority, path));
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| invalid())?),
+            None => (authority, if secure { 443 } else { 80 }),
+        };
+        if host.is_empty() {
+            return Err(invalid());
+        }
+        let path = path.trim_end_matches('/');
+        Ok(Url {
+            secure,
+            host: String::from(host),
+            port,
+            path: if path.is_empty() { String::new() } else { format!("/{}", path) },
+        })
+    }
+
+    fn authority(&self) -> String {
+        match (self.secure, self.port) {
+            (false, 80) | (true, 443) => self.host.clone(),
+            _ => format!("{}:{}", self.host, self.port),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: String,
+    /// The path below the server's base URL, starting with a slash.
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn get(path: &str) -> Request {
+        Request { method: String::from("GET"), path: String::from(path), headers: vec![], body: String::new() }
+    }
+
+    /// A POST of a form with the given fields, which must not need escaping.
+    pub fn post_form(path: &str, fields: &[(&str, &str)]) -> Request {
+        let body = fields.iter().map(|(name, value)| format!("{}={}", name, value)).collect::<Vec<_>>().join("&");
+        Request {
+            method: String::from("POST"),
+            path: String::from(path),
+            headers: vec![(String::from("Content-Type"), String::from("application/x-www-form-urlencoded"))],
+            body,
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Request {
+        self.headers.push((String::from(name), String::from(value)));
+        self
+    }
+
+    /// The value of a header, matching its name regardless of case.
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// The value of a field of a form posted with [`Request::post_form`].
+    pub fn form_field(&self, name: &str) -> Option<&str> {
+        self.body.split('&').find_map(|field| field.strip_prefix(name)?.strip_prefix('='))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: String,
+    pub body: String,
+}
+
+impl Response {
+    pub fn new(status: u16, content_type: &str, body: impl Into<String>) -> Response {
+        Response { status, content_type: String::from(content_type), body: body.into() }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            500 => "Internal Server Error",
+            _ => "Unknown",
+        };
+        format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            reason,
+            self.content_type,
+            self.body.len(),
+            self.body
+        )
+        .into_bytes()
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(header, _)| header.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// Reads a status or request line and the headers up to the blank line after them.
+fn read_head(reader: &mut impl BufRead) -> Result<(String, Vec<(String, String)>), String> {
+    let mut read_line = || -> Result<String, String> {
+        let mut line = String::new();
+        reader.read_line(&mut line).map_err(|err| format!("failed to read the HTTP message: {}", err))?;
+        Ok(String::from(line.trim_end_matches(['\r', '\n'])))
+    };
+    let first = read_line()?;
+    if first.is_empty() {
+        return Err(String::from("empty HTTP message"));
+    }
+    let mut headers = vec![];
+    loop {
+        let line = read_line()?;
+        if line.is_empty() {
+            return Ok((first, headers));
+        }
+        let (name, value) = line.split_once(':').ok_or(format!("invalid HTTP header {}", line))?;
+        headers.push((String::from(name.trim()), String::from(value.trim())));
+    }
+}
+
+fn read_body(reader: &mut impl BufRead, headers: &[(String, String)]) -> Result<String, String> {
+    let failed = |err: std::io::Error| format!("failed to read the HTTP body: {}", err);
+    let mut body = vec![];
+    if find_header(headers, "Transfer-Encoding").is_some_and(|encoding| encoding.eq_ignore_ascii_case("chunked")) {
+        loop {
+            let mut size = String::new();
+            reader.read_line(&mut size).map_err(failed)?;
+            let size = size.trim().split(';').next().unwrap_or("");
+            let size = usize::from_str_radix(size, 16).map_err(|_| format!("invalid chunk size {}", size))?;
+            let mut chunk = vec![0; size + 2];
+            reader.read_exact(&mut chunk).map_err(failed)?;
+            if size == 0 {
+                break;
+            }
+            body.extend_from_slice(&chunk[..size]);
+        }
+    } else if let Some(length) = find_header(headers, "Content-Length") {
+        let length = length.parse().map_err(|_| format!("invalid Content-Length {}", length))?;
+        body.resize(length, 0);
+        reader.read_exact(&mut body).map_err(failed)?;
+    } else {
+        reader.read_to_end(&mut body).map_err(failed)?;
+    }
+    String::from_utf8(body).map_err(|_| String::from("the HTTP body isn't UTF-8"))
+}
+
+/// Reads a request as a server receives it.
+pub fn read_request(reader: &mut impl BufRead) -> Result<Request, String> {
+    let (line, headers) = read_head(reader)?;
+    let mut parts = line.split(' ');
+    let (Some(method), Some(path), Some(_version)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(format!("invalid HTTP request line {}", line));
+    };
+    // a request without a length has no body, rather than one that lasts until the connection closes
+    let body = if find_header(&headers, "Content-Length").is_some() || find_header(&headers, "Transfer-Encoding").is_some() {
+        read_body(reader, &headers)?
+    } else {
+        String::new()
+    };
+    Ok(Request { method: String::from(method), path: String::from(path), headers, body })
+}
+
+/// Reads a response as a client receives it.
+pub fn read_response(reader: &mut impl BufRead) -> Result<Response, String> {
+    let (line, headers) = read_head(reader)?;
+    let status = line
+        .split(' ')
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or(format!("invalid HTTP status line {}", line))?;
+    let content_type = find_header(&headers, "Content-Type").unwrap_or("").to_string();
+    Ok(Response { status, content_type, body: read_body(reader, &headers)? })
+}
+
+/// Sends `request` to the server at `base`, prefixing its path with the one of `base`.
+pub fn send(base: &Url, request: &Request) -> Result<Response, String> {
+    if base.secure {
+        return send_with_curl(base, request);
+    }
+    let failed = |err: std::io::Error| format!("failed to talk to {}: {}", base.authority(), err);
+    let mut stream = TcpStream::connect((base.host.as_str(), base.port)).map_err(failed)?;
+    stream.set_read_timeout(Some(TIMEOUT)).map_err(failed)?;
+    let mut head = format!("{} {}{} HTTP/1.1\r\nHost: {}\r\n", request.method, base.path, request.path, base.authority());
+    for (name, value) in &request.headers {
+        write!(head, "{}: {}\r\n", name, value).unwrap();
+    }
+    write!(head, "Content-Length: {}\r\nConnection: close\r\n\r\n", request.body.len()).unwrap();
+    stream.write_all(head.as_bytes()).and_then(|()| stream.write_all(request.body.as_bytes())).map_err(failed)?;
+    read_response(&mut BufReader::new(stream))
+}
+
+/// Sends an https request with `curl`. The headers go through its standard input, so the session
+/// token doesn't show up in the process list.
+fn send_with_curl(base: &Url, request: &Request) -> Result<Response, String> {
+    let url = format!("https://{}{}{}", base.authority(), base.path, request.path);
+    let mut config = String::new();
+    for (name, value) in &request.headers {
+        writeln!(config, "header = \"{}: {}\"", name, value.replace('\\', "\\\\").replace('"', "\\\"")).unwrap();
+    }
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--config", "-", "--max-time", "30"])
+        .args(["--request", &request.method, "--write-out", "\n%{http_code} %{content_type}"]);
+    if !request.body.is_empty() {
+        command.args(["--data-raw", &request.body]);
+    }
+    let mut child = command
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("https needs curl, which failed to start: {}", err))?;
+    child.stdin.take().expect("Expecting curl's stdin").write_all(config.as_bytes()).map_err(|err| err.to_string())?;
+    let output = child.wait_with_output().map_err(|err| err.to_string())?;
+    if !output.status.success() {
+        return Err(format!("curl failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    let output = String::from_utf8(output.stdout).map_err(|_| String::from("the HTTP body isn't UTF-8"))?;
+    let (body, status) = output.rsplit_once('\n').ok_or("curl didn't report the status")?;
+    let (status, content_type) = status.split_once(' ').unwrap_or((status, ""));
+    let status = status.parse().map_err(|_| format!("curl reported an invalid status {}", status))?;
+    Ok(Response { status, content_type: String::from(content_type), body: String::from(body) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url() {
+        assert_eq!(
+            Url::parse("https://adventofcode.com").unwrap(),
+            Url { secure: true, host: String::from("adventofcode.com"), port: 443, path: String::new() }
+        );
+        assert_eq!(
+            Url::parse("http://127.0.0.1:8024/mock/").unwrap(),
+            Url { secure: false, host: String::from("127.0.0.1"), port: 8024, path: String::from("/mock") }
+        );
+        assert_eq!(Url::parse("http://localhost:8024").unwrap().authority(), "localhost:8024");
+        assert!(Url::parse("adventofcode.com").is_err());
+        assert!(Url::parse("ftp://adventofcode.com").is_err());
+        assert!(Url::parse("http://:80").is_err());
+        assert!(Url::parse("http://host:port").is_err());
+    }
+
+    #[test]
+    fn test_read_messages() {
+        let request = "POST /2024/day/1/answer HTTP/1.1\r\nHost: localhost\r\ncookie: session=abc\r\nContent-Length: 17\r\n\r\nlevel=1&answer=11trailing";
+        let request = read_request(&mut request.as_bytes()).unwrap();
+        assert_eq!((request.method.as_str(), request.path.as_str()), ("POST", "/2024/day/1/answer"));
+        assert_eq!(request.get_header("Cookie"), Some("session=abc"));
+        assert_eq!(request.body, "level=1&answer=11");
+        assert_eq!((request.form_field("level"), request.form_field("answer")), (Some("1"), Some("11")));
+
+        let response = Response::new(404, "text/plain", "Not found");
+        assert_eq!(read_response(&mut response.to_bytes().as_slice()), Ok(response));
+        let chunked = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n3 4\n\r\n5\r\n4 3\n\n\r\n0\r\n\r\n";
+        assert_eq!(read_response(&mut chunked.as_bytes()).unwrap().body, "3 4\n4 3\n\n");
+        assert!(read_response(&mut "".as_bytes()).is_err());
+        assert!(read_request(&mut "GET\r\n\r\n".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,537 @@
+//! Still images and animations of grids, encoded by hand: binary PPM, PNG with uncompressed
+//! deflate blocks, and GIF with its LZW compression. Every cell of a grid becomes a square of pixels.
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::grid::{Grid, Point};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Draws every cell of `grid` as a `scale` by `scale` square in the color `color` picks for it.
+    pub fn from_grid<T>(grid: &Grid<T>, scale: usize, color: impl Fn(Point, &T) -> Rgb) -> Image {
+        let width = grid.width() * scale;
+        let height = grid.height() * scale;
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..grid.height() {
+            let row: Vec<Rgb> = (0..grid.width())
+                .flat_map(|x| {
+                    let point = Point(x as isize, y as isize);
+                    std::iter::repeat_n(color(point, &grid[point]), scale)
+                })
+                .collect();
+            for _ in 0..scale {
+                pixels.extend_from_slice(&row);
+            }
+        }
+        Image { width, height, pixels }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[cfg(test)]
+    pub fn pixel(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Binary PPM (P6), the simplest format most image viewers open.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        for &Rgb(r, g, b) in &self.pixels {
+            bytes.extend([r, g, b]);
+        }
+        bytes
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut header = vec![];
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8 bit truecolor, default compression, filtering and no interlacing
+        header.extend([8, 2, 0, 0, 0]);
+
+        // every scanline starts with its filter type, 0 = none
+        let mut scanlines = Vec::with_capacity(self.height * (self.width * 3 + 1));
+        for row in self.pixels.chunks(self.width.max(1)) {
+            scanlines.push(0);
+            for &Rgb(r, g, b) in row {
+                scanlines.extend([r, g, b]);
+            }
+        }
+
+        let mut bytes = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+        png_chunk(&mut bytes, b"IHDR", &header);
+        png_chunk(&mut bytes, b"IDAT", &zlib_stored(&scanlines));
+        png_chunk(&mut bytes, b"IEND", &[]);
+        bytes
+    }
+}
+
+fn png_chunk(bytes: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    bytes.extend((data.len() as u32).to_be_bytes());
+    let start = bytes.len();
+    bytes.extend(kind);
+    bytes.extend(data);
+    let crc = crc32(&bytes[start..]);
+    bytes.extend(crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+/// Wraps `data` in a zlib stream of uncompressed deflate blocks, which hold at most 65535 bytes each.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0x78, 0x01];
+    let mut blocks = data.chunks(65535).peekable();
+    if blocks.peek().is_none() {
+        bytes.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        bytes.push(last as u8);
+        bytes.extend(len.to_le_bytes());
+        bytes.extend((!len).to_le_bytes());
+        bytes.extend(block);
+    }
+    bytes.extend(adler32(data).to_be_bytes());
+    bytes
+}
+
+/// Packs variable width codes into bytes, least significant bit first as GIF wants them.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u32) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += width;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+const MAX_CODE: u16 = 4096;
+
+fn lzw_compress(indices: &[u8], min_code_size: u32) -> Vec<u8> {
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+    let mut writer = BitWriter::default();
+    let mut codes: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut width = min_code_size + 1;
+    let mut next = end + 1;
+    writer.write(clear, width);
+
+    let Some((&first, rest)) = indices.split_first() else {
+        writer.write(end, width);
+        return writer.finish();
+    };
+    let mut prefix = first as u16;
+    for &index in rest {
+        if let Some(&code) = codes.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+        writer.write(prefix, width);
+        if next == MAX_CODE {
+            // the table is full, so start over
+            writer.write(clear, width);
+            codes.clear();
+            width = min_code_size + 1;
+            next = end + 1;
+        } else {
+            if next == 1 << width {
+                width += 1;
+            }
+            codes.insert((prefix, index), next);
+            next += 1;
+        }
+        prefix = index as u16;
+    }
+    writer.write(prefix, width);
+    if next == 1 << width && width < 12 {
+        width += 1;
+    }
+    writer.write(end, width);
+    writer.finish()
+}
+
+/// Encodes a looping GIF one frame at a time, so an animation never has to be held in memory.
+/// Every frame brings its own color table, so each one may use up to 256 colors.
+pub struct GifWriter<W: Write> {
+    out: W,
+    /// Hundredths of a second between frames.
+    delay: u16,
+    /// The size of the first frame, which every later one must have too.
+    size: Option<(u16, u16)>,
+    frames: usize,
+}
+
+impl<W: Write> GifWriter<W> {
+    /// Shows `fps` frames per second, or as fast as the viewer can at 0.
+    pub fn new(out: W, fps: u32) -> GifWriter<W> {
+        let delay = 100u32.checked_div(fps).map_or(0, |delay| delay.max(1) as u16);
+        GifWriter { out, delay, size: None, frames: 0 }
+    }
+
+    pub fn push(&mut self, frame: &Image) -> Result<(), String> {
+        let (Ok(width), Ok(height)) = (u16::try_from(frame.width()), u16::try_from(frame.height())) else {
+            return Err(format!("{}x{} pixels is too large for a GIF", frame.width(), frame.height()));
+        };
+        match self.size {
+            Some(size) if size != (width, height) => {
+                return Err(String::from("all frames of an animation must have the same size"));
+            }
+            Some(_) => {}
+            None => {
+                let mut header = b"GIF89a".to_vec();
+                header.extend(width.to_le_bytes());
+                header.extend(height.to_le_bytes());
+                // no global color table, the frames have their own
+                header.extend([0, 0, 0]);
+                // NETSCAPE2.0 extension: loop forever
+                header.extend(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+                self.out.write_all(&header).map_err(|err| err.to_string())?;
+                self.size = Some((width, height));
+            }
+        }
+
+        let mut palette: Vec<Rgb> = vec![];
+        let mut lookup: HashMap<Rgb, u8> = HashMap::new();
+        for &pixel in &frame.pixels {
+            if let Entry::Vacant(vacant) = lookup.entry(pixel) {
+                if palette.len() == 256 {
+                    return Err(String::from("a GIF frame can't have more than 256 colors"));
+                }
+                vacant.insert(palette.len() as u8);
+                palette.push(pixel);
+            }
+        }
+        // the color table holds 2^(n+1) entries, at least 4 since LZW needs a code size of 2 or more
+        let table_bits = (palette.len().max(4) as u32).next_power_of_two().trailing_zeros();
+        palette.resize(1 << table_bits, Rgb(0, 0, 0));
+
+        // graphic control extension with the delay in hundredths of a second
+        let mut bytes = vec![0x21, 0xf9, 0x04, 0x00];
+        bytes.extend(self.delay.to_le_bytes());
+        bytes.extend([0x00, 0x00]);
+
+        bytes.push(0x2c);
+        bytes.extend([0, 0, 0, 0]);
+        bytes.extend(width.to_le_bytes());
+        bytes.extend(height.to_le_bytes());
+        bytes.push(0x80 | (table_bits as u8 - 1));
+        for &Rgb(r, g, b) in &palette {
+            bytes.extend([r, g, b]);
+        }
+
+        let indices: Vec<u8> = frame.pixels.iter().map(|pixel| lookup[pixel]).collect();
+        bytes.push(table_bits as u8);
+        for block in lzw_compress(&indices, table_bits).chunks(255) {
+            bytes.push(block.len() as u8);
+            bytes.extend(block);
+        }
+        bytes.push(0);
+        self.out.write_all(&bytes).map_err(|err| err.to_string())?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Ends the animation, returning the output and how many frames it has.
+    pub fn finish(mut self) -> Result<(W, usize), String> {
+        if self.frames == 0 {
+            return Err(String::from("there are no frames to export"));
+        }
+        self.out.write_all(&[0x3b]).and_then(|_| self.out.flush()).map_err(|err| err.to_string())?;
+        Ok((self.out, self.frames))
+    }
+}
+
+/// Encodes `frames` as a looping GIF showing `fps` frames per second, or as fast as the viewer can at 0.
+pub fn encode_gif(frames: &[Image], fps: u32) -> Result<Vec<u8>, String> {
+    let mut writer = GifWriter::new(vec![], fps);
+    for frame in frames {
+        writer.push(frame)?;
+    }
+    Ok(writer.finish()?.0)
+}
+
+enum Target {
+    /// Keeps only frame number `frame` (1-based), or the latest one if `None`.
+    Still { frame: Option<usize>, image: Option<Image>, png: bool },
+    /// Encodes every frame right away, into a file that is created with the first one.
+    Gif { fps: u32, writer: Option<GifWriter<BufWriter<File>>> },
+}
+
+/// Saves frames to a file as they are drawn, in the format the file's extension asks for:
+/// every frame for `.gif`, or a single one for `.png` and `.ppm`.
+pub struct Export {
+    path: String,
+    target: Target,
+    frames: usize,
+    error: Option<String>,
+}
+
+impl Export {
+    /// Exports to `path`, keeping only frame number `frame` (1-based, the last one by default) for still images.
+    pub fn new(path: &str, frame: Option<usize>, fps: u32) -> Result<Export, String> {
+        let target = match Path::new(path).extension().and_then(|extension| extension.to_str()) {
+            Some("gif") => Target::Gif { fps, writer: None },
+            Some("png") => Target::Still { frame, image: None, png: true },
+            Some("ppm") => Target::Still { frame, image: None, png: false },
+            _ => return Err(format!("{}: unknown image format, expected .gif, .png or .ppm", path)),
+        };
+        Ok(Export { path: String::from(path), target, frames: 0, error: None })
+    }
+
+    /// Records the next frame, calling `draw` only if the export keeps it.
+    pub fn push(&mut self, draw: impl FnOnce() -> Image) {
+        self.frames += 1;
+        if self.error.is_some() {
+            return;
+        }
+        match &mut self.target {
+            Target::Still { frame: Some(number), .. } if *number != self.frames => {}
+            Target::Still { image, .. } => *image = Some(draw()),
+            Target::Gif { fps, writer } => {
+                let writer = match writer {
+                    Some(writer) => writer,
+                    None => match File::create(&self.path) {
+                        Ok(file) => writer.insert(GifWriter::new(BufWriter::new(file), *fps)),
+                        Err(err) => {
+                            self.error = Some(format!("{}: {}", self.path, err));
+                            return;
+                        }
+                    },
+                };
+                if let Err(err) = writer.push(&draw()) {
+                    self.error = Some(err);
+                }
+            }
+        }
+    }
+
+    /// Writes out what was kept, returning how many frames were saved.
+    pub fn finish(self) -> Result<usize, String> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        match self.target {
+            Target::Still { frame, image, png } => {
+                let image = image.ok_or_else(|| match frame {
+                    Some(number) => format!("there is no frame {}, only {}", number, self.frames),
+                    None => String::from("there are no frames to export"),
+                })?;
+                let bytes = if png { image.to_png() } else { image.to_ppm() };
+                fs::write(&self.path, bytes).map_err(|err| format!("{}: {}", self.path, err))?;
+                Ok(1)
+            }
+            Target::Gif { writer, .. } => {
+                let writer = writer.ok_or_else(|| String::from("there are no frames to export"))?;
+                Ok(writer.finish()?.1)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Rgb = Rgb(0, 0, 0);
+    const WHITE: Rgb = Rgb(255, 255, 255);
+
+    fn checkerboard(size: usize, scale: usize) -> Image {
+        Image::from_grid(&Grid::new(size, size, ()), scale, |Point(x, y), _| if (x + y) % 2 == 0 { BLACK } else { WHITE })
+    }
+
+    #[test]
+    fn test_from_grid_and_ppm() {
+        let image = checkerboard(2, 2);
+        assert_eq!((image.width(), image.height()), (4, 4));
+        assert_eq!(image.pixel(1, 1), BLACK);
+        assert_eq!(image.pixel(2, 1), WHITE);
+        assert_eq!(image.pixel(2, 2), BLACK);
+
+        let ppm = checkerboard(1, 1).to_ppm();
+        assert_eq!(ppm, b"P6\n1 1\n255\n\x00\x00\x00");
+    }
+
+    #[test]
+    fn test_png_checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+
+        let png = checkerboard(2, 1).to_png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x00\x00\x02\x00\x00\x00\x02"));
+        assert!(png.ends_with(b"\x00\x00\x00\x00IEND\xae\x42\x60\x82"));
+        // two scanlines of a filter byte and two pixels, stored in a single final block
+        let data = &png[41..];
+        assert_eq!(&data[..7], &[0x78, 0x01, 1, 14, 0, !14, 0xff]);
+        assert_eq!(&data[7..21], &[0, 0, 0, 0, 255, 255, 255, 0, 255, 255, 255, 0, 0, 0]);
+    }
+
+    /// A straightforward GIF LZW decoder, to check the encoder against.
+    fn lzw_decompress(bytes: &[u8], min_code_size: u32) -> Vec<u8> {
+        let clear = 1usize << min_code_size;
+        let mut table: Vec<Vec<u8>> = vec![];
+        let mut width = min_code_size + 1;
+        let mut previous: Option<usize> = None;
+        let mut output = vec![];
+        let (mut buffer, mut bits, mut position) = (0u32, 0, 0);
+        loop {
+            while bits < width {
+                buffer |= (bytes[position] as u32) << bits;
+                position += 1;
+                bits += 8;
+            }
+            let code = (buffer & ((1 << width) - 1)) as usize;
+            buffer >>= width;
+            bits -= width;
+
+            if code == clear {
+                table = (0..clear).map(|index| vec![index as u8]).chain([vec![], vec![]]).collect();
+                width = min_code_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == clear + 1 {
+                return output;
+            }
+            let entry = match (table.get(code), previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) => {
+                    let mut entry = table[previous].clone();
+                    entry.push(table[previous][0]);
+                    entry
+                }
+                (None, None) => panic!("invalid first code {}", code),
+            };
+            if let Some(previous) = previous {
+                let mut added = table[previous].clone();
+                added.push(entry[0]);
+                table.push(added);
+                if table.len() == 1 << width && width < 12 {
+                    width += 1;
+                }
+            }
+            output.extend(&entry);
+            previous = Some(code);
+        }
+    }
+
+    #[test]
+    fn test_lzw_round_trip() {
+        let mut indices: Vec<u8> = (0..20_000u32).map(|index| ((index * 7919) % 13 % 4) as u8).collect();
+        indices.extend([3; 5000]);
+        let compressed = lzw_compress(&indices, 2);
+        assert!(compressed.len() < indices.len());
+        assert_eq!(lzw_decompress(&compressed, 2), indices);
+
+        let short = [0, 1, 0, 1, 0, 1, 2];
+        assert_eq!(lzw_decompress(&lzw_compress(&short, 2), 2), short);
+        assert_eq!(lzw_decompress(&lzw_compress(&[], 2), 2), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_gif_layout() {
+        let frames = [checkerboard(2, 1), checkerboard(2, 1)];
+        let gif = encode_gif(&frames, 10).unwrap();
+        // 2x2 pixels without a global color table
+        assert!(gif.starts_with(b"GIF89a\x02\x00\x02\x00\x00\x00\x00"));
+        // every frame has a local table of 4 colors, black first
+        let descriptor = b"\x2c\x00\x00\x00\x00\x02\x00\x02\x00\x81\x00\x00\x00\xff\xff\xff\x00\x00\x00\x00\x00\x00";
+        assert_eq!(gif.windows(descriptor.len()).filter(|&window| window == descriptor).count(), 2);
+        assert!(gif.windows(8).any(|window| window == [0x21, 0xf9, 0x04, 0x00, 10, 0, 0, 0]));
+        assert_eq!(gif.last(), Some(&0x3b));
+
+        assert!(encode_gif(&[], 10).is_err());
+        assert!(encode_gif(&[checkerboard(2, 1), checkerboard(3, 1)], 10).is_err());
+    }
+
+    #[test]
+    fn test_export_keeps_one_still_frame() {
+        let path = std::env::temp_dir().join(format!("aoc2024_export_{}.ppm", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut export = Export::new(path, Some(2), 0).unwrap();
+        let mut drawn = 0;
+        for size in 1..=3 {
+            export.push(|| {
+                drawn += 1;
+                checkerboard(size, 1)
+            });
+        }
+        assert_eq!(drawn, 1);
+        assert_eq!(export.finish(), Ok(1));
+        assert_eq!(fs::read(path).unwrap(), checkerboard(2, 1).to_ppm());
+        fs::remove_file(path).unwrap();
+
+        let mut export = Export::new(path, Some(5), 0).unwrap();
+        export.push(|| checkerboard(1, 1));
+        assert_eq!(export.finish(), Err(String::from("there is no frame 5, only 1")));
+        assert!(Export::new("robots.jpg", None, 0).is_err());
+    }
+
+    #[test]
+    fn test_export_streams_gif() {
+        let path = std::env::temp_dir().join(format!("aoc2024_export_{}.gif", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut export = Export::new(path, None, 10).unwrap();
+        export.push(|| checkerboard(2, 1));
+        export.push(|| checkerboard(2, 1));
+        assert_eq!(export.finish(), Ok(2));
+        let frames = [checkerboard(2, 1), checkerboard(2, 1)];
+        assert_eq!(fs::read(path).unwrap(), encode_gif(&frames, 10).unwrap());
+        fs::remove_file(path).unwrap();
+    }
+}
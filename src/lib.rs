@@ -0,0 +1,33 @@
+//! Solutions for Advent of Code 2024. Every day is a [`solution::Solution`] that parses its input
+//! from a [`parse::Source`] and solves both parts; [`solution::SOLUTIONS`] lists them all.
+
+pub mod day_01;
+pub mod day_02;
+pub mod day_03;
+pub mod day_04;
+pub mod day_05;
+pub mod day_06;
+pub mod day_07;
+pub mod day_08;
+pub mod day_09;
+pub mod day_10;
+pub mod day_11;
+pub mod day_12;
+pub mod day_13;
+pub mod day_14;
+pub mod day_15;
+pub mod day_16;
+pub mod day_17;
+pub mod day_18;
+pub mod fuzz;
+pub mod grid;
+pub mod image;
+pub mod log;
+pub mod params;
+pub mod parse;
+pub mod property;
+pub mod random;
+pub mod search;
+pub mod snapshot;
+pub mod solution;
+pub mod visualize;
@@ -0,0 +1,194 @@
+//! Diagnostics of the solutions, kept apart from their answers. Messages go through the
+//! [`error!`](crate::error), [`warn!`](crate::warn), [`info!`](crate::info),
+//! [`debug!`](crate::debug) and [`trace!`](crate::trace) macros, are tagged with the module that
+//! logged them, like `day_05`, and are written to stderr or a log file once they pass the
+//! [`Filter`]. Until [`init`] is called only warnings and errors are shown, on stderr.
+
+use std::{
+    fmt::{Arguments, Display},
+    io::{self, Write},
+    str::FromStr,
+    sync::{Mutex, OnceLock},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    pub const ALL: [Level; 5] = [Level::Error, Level::Warn, Level::Info, Level::Debug, Level::Trace];
+
+    fn name(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name().to_uppercase())
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Level, String> {
+        Level::ALL
+            .into_iter()
+            .find(|level| level.name().eq_ignore_ascii_case(value))
+            .ok_or_else(|| format!("unknown log level {}, expected one of error, warn, info, debug, trace", value))
+    }
+}
+
+/// The most detailed level to log, overall and for single targets such as `day_05`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Filter {
+    default: Level,
+    targets: Vec<(String, Level)>,
+}
+
+impl Filter {
+    pub fn new(default: Level) -> Filter {
+        Filter { default, targets: vec![] }
+    }
+
+    /// Reads a comma separated list of levels for targets, like `day_05=trace`, and at most one
+    /// level for everything else, like `info`. Unlisted targets keep `default`.
+    pub fn parse(spec: &str, default: Level) -> Result<Filter, String> {
+        let mut filter = Filter::new(default);
+        for directive in spec.split(',').map(str::trim).filter(|directive| !directive.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) if !target.is_empty() => filter.targets.push((String::from(target), level.parse()?)),
+                Some(_) => return Err(format!("missing log target in {}", directive)),
+                None => filter.default = directive.parse()?,
+            }
+        }
+        Ok(filter)
+    }
+
+    /// The level for `target`, taken from the longest listed target that it is or lies within.
+    pub fn level(&self, target: &str) -> Level {
+        self.targets
+            .iter()
+            .filter(|(name, _)| target == name || target.strip_prefix(name.as_str()).is_some_and(|rest| rest.starts_with("::")))
+            .max_by_key(|(name, _)| name.len())
+            .map_or(self.default, |&(_, level)| level)
+    }
+
+    pub fn enabled(&self, level: Level, target: &str) -> bool {
+        level <= self.level(target)
+    }
+}
+
+struct Logger {
+    filter: Filter,
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+/// Sends the messages that pass `filter` to `out`. Only the first call has an effect.
+pub fn init(filter: Filter, out: Box<dyn Write + Send>) -> bool {
+    LOGGER.set(Logger { filter, out: Mutex::new(out) }).is_ok()
+}
+
+/// Module paths without the crate name, so the solution of day 5 logs to `day_05`.
+pub fn target(module_path: &str) -> &str {
+    module_path.split_once("::").map_or(module_path, |(_, path)| path)
+}
+
+pub fn enabled(level: Level, target: &str) -> bool {
+    match LOGGER.get() {
+        Some(logger) => logger.filter.enabled(level, target),
+        None => level <= Level::Warn,
+    }
+}
+
+pub fn format_line(level: Level, target: &str, message: Arguments) -> String {
+    format!("[{} {}] {}", level, target, message)
+}
+
+/// Writes a message that already passed [`enabled`]; use the macros instead.
+pub fn write(level: Level, target: &str, message: Arguments) {
+    let line = format_line(level, target, message);
+    // a broken log destination shouldn't take the solutions down with it
+    let _ = match LOGGER.get() {
+        Some(logger) => writeln!(logger.out.lock().unwrap_or_else(|poisoned| poisoned.into_inner()), "{}", line),
+        None => writeln!(io::stderr(), "{}", line),
+    };
+}
+
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {{
+        let target = $crate::log::target(module_path!());
+        if $crate::log::enabled($level, target) {
+            $crate::log::write($level, target, format_args!($($arg)+));
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Error, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Warn, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Info, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Debug, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Trace, $($arg)+) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter() {
+        let filter = Filter::parse("info, day_05=trace,day_05::sorter=error", Level::Warn).unwrap();
+        assert_eq!(filter.level("day_01"), Level::Info);
+        assert_eq!(filter.level("day_05"), Level::Trace);
+        assert_eq!(filter.level("day_05::sorter"), Level::Error);
+        assert_eq!(filter.level("day_050"), Level::Info);
+        assert!(filter.enabled(Level::Debug, "day_05"));
+        assert!(!filter.enabled(Level::Debug, "day_06"));
+        assert_eq!(Filter::parse("", Level::Debug), Ok(Filter::new(Level::Debug)));
+
+        assert!(Filter::parse("loud", Level::Warn).is_err());
+        assert!(Filter::parse("day_05=loud", Level::Warn).is_err());
+        assert!(Filter::parse("=debug", Level::Warn).is_err());
+    }
+
+    #[test]
+    fn test_format_line() {
+        assert_eq!(target("aoc2024::day_05"), "day_05");
+        assert_eq!(target("aoc2024"), "aoc2024");
+        assert_eq!(format_line(Level::Debug, "day_05", format_args!("update {:?}", [1, 2])), "[DEBUG day_05] update [1, 2]");
+        assert_eq!("Trace".parse(), Ok(Level::Trace));
+    }
+}
@@ -1,58 +1,625 @@
-#![allow(unused_imports)]
-// mod day_01;
-// mod day_02;
-// mod day_03;
-// mod day_04;
-// mod day_05;
-// mod day_06;
-// mod day_07;
-// mod day_08;
-// mod day_09;
-// mod day_10;
-// mod day_11;
-// mod day_12;
-// mod day_13;
-// mod day_14;
-// mod day_15;
-// mod day_16;
-mod day_17;
-mod day_18;
-// use day_01::day_01;
-// use day_02::day_02;
-// use day_03::day_03;
-// use day_04::day_04;
-// use day_05::day_05;
-// use day_06::day_06;
-// use day_07::day_07;
-// use day_08::day_08;
-// use day_09::day_09;
-// use day_10::day_10;
-// use day_11::day_11;
-// use day_12::day_12;
-// use day_13::day_13;
-// use day_14::day_14;
-// use day_15::day_15;
-// use day_16::day_16;
-use day_17::day_17;
-use day_18::day_18;
+mod answers;
+mod bench;
+mod cli;
+mod examples;
+mod http;
+mod memory;
+mod mock_server;
+mod puzzle;
+mod repl;
+mod scaffold;
+mod submissions;
+mod summary;
+mod toml;
+mod watch;
+
+use std::{any::Any, collections::HashMap, env, fs, io, net::TcpListener, path::{Path, PathBuf}, process, time::Instant};
+
+use aoc2024::{
+    debug,
+    fuzz,
+    image::Export,
+    log,
+    params::Params,
+    parse::{self, Source},
+    random::Rng,
+    solution::{self, Entry, Part},
+    visualize::Renderer,
+    warn,
+};
+use answers::{Answers, Verdict};
+use cli::{
+    BenchArgs, Command, DaySelection, FetchArgs, FuzzArgs, GenArgs, InputSelection, Logging, ReplArgs, RunArgs, ServeArgs,
+    ServerArgs, SubmitArgs, WatchArgs,
+};
+use examples::Example;
+use mock_server::MockServer;
+use puzzle::{Client, Outcome};
+use repl::Repl;
+use submissions::{Rejected, Submissions};
+use summary::{DaySummary, PartSummary};
+use watch::Watcher;
+
+/// Counts allocations for `bench --profile-mem`, and only passes them on otherwise.
+#[cfg(feature = "profile-mem")]
+#[global_allocator]
+static ALLOCATOR: memory::CountingAllocator = memory::CountingAllocator;
 
 fn main() {
-    // day_01();
-    // day_02();
-    // day_03();
-    // day_04();
-    // day_05();
-    // day_06();
-    // day_07();
-    // day_08();
-    // day_09();
-    // day_10();
-    // day_11();
-    // day_12();
-    // day_13();
-    // day_14();
-    // day_15();
-    // day_16();
-    day_17();
-    // day_18();
-}
\ No newline at end of file
+    let invocation = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(invocation) => invocation,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, cli::USAGE);
+            process::exit(2);
+        }
+    };
+
+    init_logging(invocation.logging);
+    match invocation.command {
+        Command::Run(args) => run(&args),
+        Command::Bench(args) => bench(&args),
+        Command::Examples(day) => check_examples(day),
+        Command::New(day) => new_day(day),
+        Command::Gen(args) => generate(&args),
+        Command::Fuzz(args) => fuzz_days(&args),
+        Command::Watch(args) => watch(&args),
+        Command::Repl(args) => repl(&args),
+        Command::Fetch(args) => fetch(&args),
+        Command::Submit(args) => submit(&args),
+        Command::Serve(args) => serve(&args),
+    }
+}
+
+fn init_logging(logging: Logging) {
+    let out: Box<dyn io::Write + Send> = match &logging.file {
+        Some(path) => match fs::File::create(path) {
+            Ok(file) => Box::new(io::LineWriter::new(file)),
+            Err(err) => {
+                eprintln!("Failed to create log file {}: {}", path, err);
+                process::exit(1);
+            }
+        },
+        None => Box::new(io::stderr()),
+    };
+    log::init(logging.filter, out);
+}
+
+fn select_days(days: &DaySelection) -> Vec<&'static Entry> {
+    match days {
+        DaySelection::All => solution::SOLUTIONS.iter().collect(),
+        DaySelection::Single(day) => match solution::find(*day) {
+            Some(entry) => vec![entry],
+            None => {
+                eprintln!("No solution registered for day {}", day);
+                process::exit(1);
+            }
+        },
+    }
+}
+
+fn select_input(entry: &Entry, input: &InputSelection) -> String {
+    match input {
+        InputSelection::Real => entry.input_file(),
+        InputSelection::Example => entry.example_file(),
+        InputSelection::File(path) => path.clone(),
+    }
+}
+
+/// Resolves the input file for `entry`, or reports why the day has to be skipped.
+fn existing_input(entry: &Entry, input: &InputSelection) -> Option<String> {
+    let input_file = select_input(entry, input);
+    if input_file == parse::STDIN || Path::new(&input_file).is_file() {
+        Some(input_file)
+    } else {
+        warn!("day {:02}: input file {} not found, skipping", entry.day, input_file);
+        None
+    }
+}
+
+/// The parameters to solve `entry` with: the day's defaults, then those of the example in
+/// `input_file` if it is one, then the overrides from the command line.
+fn select_params(entry: &Entry, input_file: &str, overrides: &[(String, String)]) -> Result<Params, String> {
+    let mut params = entry.solution.params();
+    // `input_test/day_14.txt` and `./input_test/day_14.txt` name the same example
+    let relative = |path: &str| {
+        let path = Path::new(path);
+        path.strip_prefix(".").unwrap_or(path).to_path_buf()
+    };
+    let input_file = relative(input_file);
+    if input_file.starts_with("input_test") {
+        let examples = examples::load(examples::EXAMPLES_FILE)?;
+        let example = examples.iter().find(|example| example.day == entry.day && relative(&example.path()) == input_file);
+        if let Some(example) = example {
+            example.apply_params(&mut params)?;
+        }
+    }
+    for (name, value) in overrides {
+        params.set(name, value)?;
+    }
+    Ok(params)
+}
+
+/// Recorded answers only apply to the real inputs with their usual parameters, so other runs aren't verified.
+fn recorded_answers(input: &InputSelection, params: &[(String, String)]) -> Result<Option<Answers>, String> {
+    if input != &InputSelection::Real || !params.is_empty() {
+        return Ok(None);
+    }
+    Answers::load(answers::ANSWERS_FILE).map(Some)
+}
+
+fn load_answers(input: &InputSelection, params: &[(String, String)]) -> Option<Answers> {
+    recorded_answers(input, params).unwrap_or_else(|err| {
+        eprintln!("Failed to load recorded answers: {}", err);
+        process::exit(1);
+    })
+}
+
+/// Reads and parses the input of a day, describing what went wrong otherwise.
+fn load_day(entry: &Entry, input_file: &str, overrides: &[(String, String)]) -> Result<Box<dyn Any>, String> {
+    let params = select_params(entry, input_file, overrides).map_err(|err| format!("day {:02}: {}", entry.day, err))?;
+    if params.names().is_empty() {
+        debug!("day {:02}: parsing {}", entry.day, input_file);
+    } else {
+        debug!("day {:02}: parsing {} with {}", entry.day, input_file, params);
+    }
+    Source::read(input_file)
+        .and_then(|source| entry.solution.parse(&source, &params))
+        .map_err(|err| format!("failed to parse the input of day {:02}\n{}", entry.day, err))
+}
+
+fn run(args: &RunArgs) {
+    if let Some(jobs) = args.jobs {
+        return run_parallel(args, jobs);
+    }
+    let parts = args.part.map_or(Part::BOTH.to_vec(), |part| vec![part]);
+    let answers = load_answers(&args.input, &args.params);
+    let mut any_failed = false;
+    for entry in select_days(&args.days) {
+        let Some(input_file) = existing_input(entry, &args.input) else {
+            continue;
+        };
+
+        println!("Day {:02}", entry.day);
+        let input = match load_day(entry, &input_file, &args.params) {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                any_failed = true;
+                continue;
+            }
+        };
+        if args.visualize.is_some() || args.export.is_some() {
+            any_failed |= !visualize(entry, input.as_ref(), args);
+        }
+        for &part in &parts {
+            let result = entry.solve(input.as_ref(), part);
+            match &answers {
+                Some(answers) => {
+                    let verdict = answers.check(entry.day, part, &result);
+                    any_failed |= matches!(verdict, Verdict::Wrong(_));
+                    println!("Puzzle {} result: {} [{}]", part, result, verdict);
+                }
+                None => println!("Puzzle {} result: {}", part, result),
+            }
+        }
+    }
+    if any_failed {
+        process::exit(1);
+    }
+}
+
+/// Solves the days on `jobs` threads, then prints a table of the answers and how long they took.
+fn run_parallel(args: &RunArgs, jobs: usize) {
+    let parts = args.part.map_or(Part::BOTH.to_vec(), |part| vec![part]);
+    let answers = load_answers(&args.input, &args.params);
+    let days: Vec<(&Entry, String)> = select_days(&args.days)
+        .into_iter()
+        .filter_map(|entry| Some((entry, existing_input(entry, &args.input)?)))
+        .collect();
+
+    let start = Instant::now();
+    let summaries = summary::parallel_map(&days, jobs, |(entry, input_file)| {
+        let parse_start = Instant::now();
+        let outcome = load_day(entry, input_file, &args.params).map(|input| {
+            let parse = parse_start.elapsed();
+            let parts = parts
+                .iter()
+                .map(|&part| {
+                    let part_start = Instant::now();
+                    let answer = entry.solve(input.as_ref(), part);
+                    let time = part_start.elapsed();
+                    let verdict = answers.as_ref().map(|answers| answers.check(entry.day, part, &answer));
+                    PartSummary { part, answer, verdict, time }
+                })
+                .collect();
+            (parse, parts)
+        });
+        DaySummary { day: entry.day, outcome }
+    });
+    let elapsed = start.elapsed();
+
+    print!("{}", summary::to_table(&summaries));
+    println!("\nSolved {} day(s) in {} on {} thread(s)", summaries.len(), bench::format_duration(elapsed), jobs);
+    for summary in &summaries {
+        if let Err(err) = &summary.outcome {
+            eprintln!("error: {}", err);
+        }
+    }
+    if summaries.iter().any(DaySummary::failed) {
+        process::exit(1);
+    }
+}
+
+/// Shows and/or exports the visualization of a day, returning whether that worked.
+fn visualize(entry: &Entry, input: &dyn Any, args: &RunArgs) -> bool {
+    let mut renderer = args.visualize.map_or_else(Renderer::hidden, Renderer::new);
+    if let Some(export) = &args.export {
+        match Export::new(&export.path, export.frame, export.fps) {
+            Ok(target) => renderer = renderer.record(export.scale, target),
+            Err(err) => {
+                eprintln!("error: failed to export day {:02}: {}", entry.day, err);
+                return false;
+            }
+        }
+    }
+    if !entry.solution.visualize(input, &mut renderer) {
+        eprintln!("Day {:02} has no visualization", entry.day);
+        return args.export.is_none();
+    }
+    let (Some(export), Some(target)) = (&args.export, renderer.take_export()) else {
+        return true;
+    };
+    match target.finish() {
+        Ok(frames) => {
+            eprintln!("Saved {} frame(s) to {}", frames, export.path);
+            true
+        }
+        Err(err) => {
+            eprintln!("error: failed to export day {:02}: {}", entry.day, err);
+            false
+        }
+    }
+}
+
+fn bench(args: &BenchArgs) {
+    let mut any_failed = false;
+    let results: Vec<_> = select_days(&args.days)
+        .into_iter()
+        .filter_map(|entry| {
+            let input_file = existing_input(entry, &args.input)?;
+            let params = select_params(entry, &input_file, &args.params)
+                .inspect_err(|err| {
+                    eprintln!("error: day {:02}: {}", entry.day, err);
+                    any_failed = true;
+                })
+                .ok()?;
+            eprintln!("Benchmarking day {:02}...", entry.day);
+            bench::bench_entry(entry, &input_file, &params, args.iterations, args.profile_mem)
+                .inspect_err(|err| {
+                    eprintln!("error: failed to parse the input of day {:02}\n{}", entry.day, err);
+                    any_failed = true;
+                })
+                .ok()
+        })
+        .collect();
+
+    print!("{}", bench::to_markdown(&results));
+    if let Some(output) = &args.output {
+        let summary = if output.ends_with(".json") {
+            bench::to_json(&results)
+        } else {
+            bench::to_markdown(&results)
+        };
+        if let Err(err) = fs::write(output, summary) {
+            eprintln!("Failed to write benchmark summary to {}: {}", output, err);
+            process::exit(1);
+        }
+    }
+    if any_failed {
+        process::exit(1);
+    }
+}
+
+fn check_examples(day: Option<u8>) {
+    let examples = examples::load(examples::EXAMPLES_FILE).unwrap_or_else(|err| {
+        eprintln!("Failed to load examples: {}", err);
+        process::exit(1);
+    });
+
+    if !report_examples(examples.iter().filter(|example| day.is_none_or(|day| example.day == day))) {
+        process::exit(1);
+    }
+}
+
+/// Checks the examples and prints their results, returning whether all of them were right.
+fn report_examples<'a>(examples: impl Iterator<Item = &'a Example>) -> bool {
+    let mut any_wrong = false;
+    for example in examples {
+        if let Some(reason) = &example.ignore {
+            println!("{}: IGNORED ({})", example.file, reason);
+            continue;
+        }
+        match examples::check(example) {
+            Ok(results) => {
+                for (part, result, verdict) in results {
+                    any_wrong |= verdict != Verdict::Ok;
+                    println!("{} part {}: {} [{}]", example.file, part, result, verdict);
+                }
+            }
+            Err(err) => {
+                any_wrong = true;
+                println!("{}: {}", example.file, err);
+            }
+        }
+    }
+    !any_wrong
+}
+
+fn new_day(day: u8) {
+    match scaffold::scaffold(Path::new("."), day) {
+        Ok(steps) => {
+            for step in steps {
+                println!("{}", step);
+            }
+            println!("Download the puzzle input with `aoc2024 fetch --day {}`", day);
+        }
+        Err(err) => {
+            eprintln!("Failed to set up day {:02}: {}", day, err);
+            process::exit(1);
+        }
+    }
+}
+
+fn generate(args: &GenArgs) {
+    let entry = select_days(&DaySelection::Single(args.day))[0];
+    let mut params = entry.solution.params();
+    for (name, value) in &args.params {
+        if let Err(err) = params.set(name, value) {
+            eprintln!("error: day {:02}: {}", entry.day, err);
+            process::exit(1);
+        }
+    }
+    let text = match entry.solution.generate(&mut Rng::new(args.seed), args.size, &params) {
+        Ok(Some(text)) => text,
+        Ok(None) => {
+            eprintln!("Day {:02} has no input generator", entry.day);
+            process::exit(1);
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    };
+    let written = match &args.output {
+        Some(path) => fs::write(path, text),
+        None => io::Write::write_all(&mut io::stdout().lock(), text.as_bytes()),
+    };
+    if let Err(err) = written {
+        eprintln!("Failed to write the input of day {:02}: {}", entry.day, err);
+        process::exit(1);
+    }
+}
+
+fn fuzz_days(args: &FuzzArgs) {
+    fuzz::quiet_panics();
+    let mut any_crashed = false;
+    for entry in select_days(&args.days) {
+        // start from the example, if there is one, and a small generated input
+        let mut corpus: Vec<String> = Source::read(&entry.example_file()).map(|source| source.text).into_iter().collect();
+        if let Ok(Some(text)) = entry.solution.generate(&mut Rng::new(args.seed), 10, &entry.solution.params()) {
+            corpus.push(text);
+        }
+        if corpus.is_empty() {
+            eprintln!("Day {:02}: no example or generator to start from, skipping", entry.day);
+            continue;
+        }
+
+        let crashes = fuzz::fuzz(entry, &corpus, &entry.solution.params(), args.cases, &mut Rng::new(args.seed));
+        println!("Day {:02}: {} case(s), {} crash(es)", entry.day, args.cases, crashes.len());
+        for (index, crash) in crashes.iter().enumerate() {
+            any_crashed = true;
+            let path = Path::new(&args.output).join(format!("day_{:02}_crash_{}.txt", entry.day, index + 1));
+            let saved = fs::create_dir_all(&args.output).and_then(|()| fs::write(&path, &crash.input));
+            match saved {
+                Ok(()) => println!("  {} ({})", crash.message, path.display()),
+                Err(err) => println!("  {} (failed to save the input to {}: {})", crash.message, path.display(), err),
+            }
+        }
+    }
+    if any_crashed {
+        process::exit(1);
+    }
+}
+
+/// Solves a day and checks its examples, then does it again whenever the input, the examples or
+/// the recorded answers change, comparing the answers and timings with the previous run.
+fn watch(args: &WatchArgs) {
+    let entry = select_days(&DaySelection::Single(args.day))[0];
+    let input_file = select_input(entry, &args.input);
+    let code = PathBuf::from(format!("./src/day_{:02}.rs", entry.day));
+    let mut previous: Vec<PartSummary> = vec![];
+    for run in 1.. {
+        let examples = examples::load(examples::EXAMPLES_FILE).unwrap_or_else(|err| {
+            eprintln!("error: failed to load examples: {}", err);
+            vec![]
+        });
+        let examples: Vec<Example> = examples.into_iter().filter(|example| example.day == entry.day).collect();
+        let mut files = vec![
+            PathBuf::from(&input_file),
+            PathBuf::from(examples::EXAMPLES_FILE),
+            PathBuf::from(answers::ANSWERS_FILE),
+            code.clone(),
+        ];
+        files.extend(examples.iter().map(|example| PathBuf::from(example.path())));
+        // taken before solving, so changes made while it runs aren't missed
+        let mut watcher = Watcher::new(files);
+
+        println!("Day {:02}, run {}", entry.day, run);
+        match solve_watched(entry, &input_file, args) {
+            Ok(parts) => {
+                for part in &parts {
+                    println!("{}", watch::describe(watch::find(&previous, part.part), part));
+                }
+                previous = parts;
+            }
+            Err(err) => eprintln!("error: {}", err),
+        }
+        if !examples.is_empty() {
+            println!("Examples:");
+            report_examples(examples.iter());
+        }
+
+        println!("\nWatching for changes, press Ctrl+C to stop");
+        let changed = watcher.wait(args.interval);
+        for path in &changed {
+            println!("Changed: {}", path.display());
+        }
+        if changed.contains(&code) {
+            println!("This binary still has the old code of day {:02}, rebuild and restart watch to use the new one", entry.day);
+        }
+        println!();
+    }
+}
+
+/// Solves both parts of a watched day, timing them and checking them against the recorded answers.
+fn solve_watched(entry: &Entry, input_file: &str, args: &WatchArgs) -> Result<Vec<PartSummary>, String> {
+    let answers = recorded_answers(&args.input, &args.params)?;
+    let input = load_day(entry, input_file, &args.params)?;
+    Ok(Part::BOTH
+        .into_iter()
+        .map(|part| {
+            let start = Instant::now();
+            let answer = entry.solve(input.as_ref(), part);
+            let time = start.elapsed();
+            let verdict = answers.as_ref().map(|answers| answers.check(entry.day, part, &answer));
+            PartSummary { part, answer, verdict, time }
+        })
+        .collect())
+}
+
+/// Parses a day's input once and answers commands about it until the user quits.
+fn repl(args: &ReplArgs) {
+    let entry = select_days(&DaySelection::Single(args.day))[0];
+    let input_file = select_input(entry, &args.input);
+    let repl = Repl::new(entry, &input_file, args.params.clone(), args.settings).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    });
+    println!("Day {:02}: parsed {}, type help for the commands", entry.day, input_file);
+    if let Err(err) = repl::run(repl, io::stdin().lock(), io::stdout()) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+/// A client for the server and session given on the command line, or else in the environment.
+fn puzzle_client(server: &ServerArgs) -> Client {
+    let base_url = server
+        .base_url
+        .clone()
+        .or_else(|| env::var("AOC_BASE_URL").ok())
+        .unwrap_or_else(|| String::from(puzzle::DEFAULT_BASE_URL));
+    let Some(session) = server.session.clone().or_else(|| env::var("AOC_SESSION").ok()) else {
+        eprintln!("No session token, pass --session or set AOC_SESSION to the session cookie of the puzzle site");
+        process::exit(2);
+    };
+    Client::new(&base_url, session.trim()).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        process::exit(2);
+    })
+}
+
+fn fetch(args: &FetchArgs) {
+    let path = args.output.clone().unwrap_or_else(|| format!("./input/day_{:02}.txt", args.day));
+    // inputs never change, so there's no need to ask the server twice
+    if !args.force && fs::metadata(&path).is_ok_and(|metadata| metadata.len() > 0) {
+        println!("{} already exists, use --force to download it again", path);
+        return;
+    }
+    let input = puzzle_client(&args.server).fetch_input(args.day).and_then(|input| {
+        fs::write(&path, &input).map_err(|err| format!("failed to save {}: {}", path, err))?;
+        Ok(input)
+    });
+    match input {
+        Ok(input) => println!("Saved the input of day {:02} to {} ({} lines)", args.day, path, input.lines().count()),
+        Err(err) => {
+            eprintln!("error: failed to fetch the input of day {:02}: {}", args.day, err);
+            process::exit(1);
+        }
+    }
+}
+
+/// Submits an answer, unless it is already known to be right or wrong. Right answers are added
+/// to the recorded answers, wrong ones to the submissions file.
+fn submit(args: &SubmitArgs) {
+    let (day, part) = (args.day, args.part);
+    let answer = args.answer.clone().unwrap_or_else(|| {
+        let entry = select_days(&DaySelection::Single(day))[0];
+        let input = load_day(entry, &entry.input_file(), &[]).unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        });
+        entry.solve(input.as_ref(), part)
+    });
+    if answer.is_empty() || !answer.chars().all(|char| char.is_ascii_alphanumeric() || char == ',' || char == '-') {
+        eprintln!("error: {:?} doesn't look like an answer", answer);
+        process::exit(2);
+    }
+
+    let accepted = load_answers(&InputSelection::Real, &[]).and_then(|answers| answers.get(day, part).map(String::from));
+    match accepted {
+        Some(accepted) if accepted == answer => {
+            println!("{} is already the accepted answer of day {:02} part {}", answer, day, part);
+            return;
+        }
+        Some(accepted) => {
+            eprintln!("Day {:02} part {} was already solved with {}, not submitting {}", day, part, accepted, answer);
+            process::exit(1);
+        }
+        None => {}
+    }
+    let mut submissions = Submissions::load(submissions::SUBMISSIONS_FILE).unwrap_or_else(|err| {
+        eprintln!("Failed to load the submissions: {}", err);
+        process::exit(1);
+    });
+    if let Some(reason) = submissions.rejection(day, part, &answer) {
+        eprintln!("Not submitting: {}", reason);
+        process::exit(1);
+    }
+
+    println!("Submitting {} for day {:02} part {}", answer, day, part);
+    let outcome = puzzle_client(&args.server).submit(day, part, &answer).unwrap_or_else(|err| {
+        eprintln!("error: failed to submit: {}", err);
+        process::exit(1);
+    });
+    println!("{}", outcome);
+    let recorded = match outcome {
+        Outcome::Correct => answers::record(answers::ANSWERS_FILE, day, part, &answer),
+        Outcome::Wrong(hint) => submissions.record(submissions::SUBMISSIONS_FILE, Rejected { day, part, answer, hint }),
+        _ => Ok(()),
+    };
+    if let Err(err) = recorded {
+        eprintln!("error: failed to record the outcome: {}", err);
+    }
+    if outcome != Outcome::Correct {
+        process::exit(1);
+    }
+}
+
+fn serve(args: &ServeArgs) {
+    let inputs: HashMap<u8, String> = (1..=25)
+        .filter_map(|day| Some((day, fs::read_to_string(format!("./input/day_{:02}.txt", day)).ok()?)))
+        .collect();
+    let answers = load_answers(&InputSelection::Real, &[]).unwrap_or_default();
+    let listener = TcpListener::bind(("127.0.0.1", args.port)).unwrap_or_else(|err| {
+        eprintln!("Failed to listen on port {}: {}", args.port, err);
+        process::exit(1);
+    });
+    println!(
+        "Serving the inputs of {} day(s) at http://127.0.0.1:{}, use --session {} to log in",
+        inputs.len(),
+        args.port,
+        args.session
+    );
+    MockServer::new(&args.session, inputs, answers, args.cooldown).run(listener);
+}
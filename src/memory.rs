@@ -0,0 +1,157 @@
+//! Counting what the solutions allocate. With the `profile-mem` feature the binary's global
+//! allocator hands everything to the system allocator, and while `measure` runs it also tallies
+//! the allocations of the calling thread, which is all of them as the solutions don't start
+//! threads of their own. Without the feature nothing is counted and allocations cost nothing extra.
+
+#[cfg(feature = "profile-mem")]
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+/// What one measured piece of code allocated.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Usage {
+    /// Allocations and reallocations.
+    pub allocations: usize,
+    /// Bytes requested over all allocations, including those freed again.
+    pub allocated: usize,
+    /// The most bytes held at once beyond what was held before.
+    pub peak: usize,
+}
+
+#[cfg(feature = "profile-mem")]
+#[derive(Clone, Copy)]
+struct Counters {
+    counting: bool,
+    allocations: usize,
+    allocated: usize,
+    /// Negative once memory from before the measurement is freed.
+    live: isize,
+    peak: isize,
+}
+
+#[cfg(feature = "profile-mem")]
+const IDLE: Counters = Counters { counting: false, allocations: 0, allocated: 0, live: 0, peak: 0 };
+
+#[cfg(feature = "profile-mem")]
+thread_local! {
+    // const and without a destructor, so using it never allocates
+    static COUNTERS: Cell<Counters> = const { Cell::new(IDLE) };
+}
+
+#[cfg(feature = "profile-mem")]
+fn track(allocated: usize, freed: usize) {
+    let _ = COUNTERS.try_with(|counters| {
+        let mut current = counters.get();
+        if !current.counting {
+            return;
+        }
+        if allocated > 0 {
+            current.allocations += 1;
+            current.allocated += allocated;
+        }
+        current.live += allocated as isize - freed as isize;
+        current.peak = current.peak.max(current.live);
+        counters.set(current);
+    });
+}
+
+#[cfg(feature = "profile-mem")]
+pub struct CountingAllocator;
+
+#[cfg(feature = "profile-mem")]
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            track(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            track(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        track(0, layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            track(new_size, layout.size());
+        }
+        new_ptr
+    }
+}
+
+/// Runs `f`, counting what it allocates on this thread. Memory still held by the result counts
+/// towards the peak as it was never freed.
+#[cfg(feature = "profile-mem")]
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Usage) {
+    let saved = COUNTERS.replace(Counters { counting: true, ..IDLE });
+    let result = f();
+    let counters = COUNTERS.replace(saved);
+    let usage = Usage {
+        allocations: counters.allocations,
+        allocated: counters.allocated,
+        peak: counters.peak.max(0) as usize,
+    };
+    (result, usage)
+}
+
+pub fn format_bytes(bytes: usize) -> String {
+    let bytes = bytes as f64;
+    if bytes < 1024.0 {
+        format!("{} B", bytes)
+    } else if bytes < 1024.0 * 1024.0 {
+        format!("{:.1} KiB", bytes / 1024.0)
+    } else if bytes < 1024.0 * 1024.0 * 1024.0 {
+        format!("{:.2} MiB", bytes / (1024.0 * 1024.0))
+    } else {
+        format!("{:.3} GiB", bytes / (1024.0 * 1024.0 * 1024.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "profile-mem")]
+    #[test]
+    fn test_measure() {
+        use std::hint::black_box;
+
+        let (kept, usage) = measure(|| {
+            let scratch = black_box(vec![0u8; 4000]);
+            drop(scratch);
+            black_box(vec![0u64; 100])
+        });
+        assert_eq!(usage, Usage { allocations: 2, allocated: 4800, peak: 4000 });
+
+        let (_, usage) = measure(|| drop(kept));
+        assert_eq!(usage, Usage { allocations: 0, allocated: 0, peak: 0 });
+
+        let (_, usage) = measure(|| {
+            let mut grown = Vec::with_capacity(10);
+            grown.extend(0u8..20);
+            black_box(grown).len()
+        });
+        assert_eq!(usage, Usage { allocations: 2, allocated: 30, peak: 20 });
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.00 MiB");
+        assert_eq!(format_bytes(1 << 31), "2.000 GiB");
+    }
+}
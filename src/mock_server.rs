@@ -0,0 +1,217 @@
+//! A local stand-in for the puzzle server, for tests and for trying `fetch` and `submit` without
+//! bothering the real one. It serves the inputs it was given, judges answers against recorded
+//! ones with the same messages as the real server, and makes you wait after a wrong answer.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::{self, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    time::{Duration, Instant},
+};
+
+use aoc2024::solution::Part;
+
+use crate::{
+    answers::Answers,
+    http::{self, Request, Response},
+    puzzle::YEAR,
+};
+
+const NOT_LOGGED_IN: &str = "Puzzle inputs differ by user.  Please log in to get your puzzle input.";
+const LOCKED: &str = "Please don't repeatedly request this endpoint before it unlocks! The calendar countdown is synchronized with the server time; the link will be enabled on the calendar the instant this puzzle becomes available.";
+
+pub struct MockServer {
+    session: String,
+    inputs: HashMap<u8, String>,
+    answers: Answers,
+    /// How long to wait after a wrong answer before the next one is judged.
+    cooldown: Duration,
+    solved: HashSet<(u8, Part)>,
+    wait_until: Option<Instant>,
+}
+
+impl MockServer {
+    /// A server for the user with the `session` token, where the days with an input are unlocked.
+    pub fn new(session: &str, inputs: HashMap<u8, String>, answers: Answers, cooldown: Duration) -> MockServer {
+        MockServer {
+            session: String::from(session),
+            inputs,
+            answers,
+            cooldown,
+            solved: HashSet::new(),
+            wait_until: None,
+        }
+    }
+
+    /// Answers requests on `listener` one after another, forever.
+    pub fn run(mut self, listener: TcpListener) {
+        for stream in listener.incoming().flatten() {
+            // a client that hangs up early only concerns itself
+            let _ = self.handle(stream);
+        }
+    }
+
+    /// Runs the server on a free local port in the background, returning its base URL.
+    #[cfg(test)]
+    pub fn spawn(self) -> io::Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}", listener.local_addr()?);
+        std::thread::spawn(move || self.run(listener));
+        Ok(url)
+    }
+
+    fn handle(&mut self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        let response = match http::read_request(&mut BufReader::new(&stream)) {
+            Ok(request) => self.respond(&request, Instant::now()),
+            Err(err) => Response::new(400, "text/plain", err),
+        };
+        stream.write_all(&response.to_bytes())
+    }
+
+    /// The response to `request` if it arrives at `now`.
+    pub fn respond(&mut self, request: &Request, now: Instant) -> Response {
+        let route = request
+            .path
+            .strip_prefix(&format!("/{}/day/", YEAR))
+            .and_then(|rest| rest.split_once('/'))
+            .and_then(|(day, action)| Some((day.parse::<u8>().ok()?, action)));
+        let Some((day, action)) = route else {
+            return Response::new(404, "text/plain", "404 Not Found");
+        };
+        let logged_in = request
+            .get_header("Cookie")
+            .is_some_and(|cookies| cookies.split(';').any(|cookie| cookie.trim() == format!("session={}", self.session)));
+        match (request.method.as_str(), action) {
+            (_, "input" | "answer") if !logged_in => Response::new(400, "text/plain", NOT_LOGGED_IN),
+            (_, "input" | "answer") if !self.inputs.contains_key(&day) => Response::new(404, "text/plain", LOCKED),
+            ("GET", "input") => Response::new(200, "text/plain", self.inputs[&day].clone()),
+            ("POST", "answer") => match request.form_field("answer").filter(|answer| !answer.is_empty()) {
+                None => Response::new(400, "text/plain", "400 Bad Request: missing answer"),
+                Some(answer) => match self.judge(day, request.form_field("level"), answer, now) {
+                    Ok(message) => Response::new(200, "text/html", page(day, &message)),
+                    Err(response) => response,
+                },
+            },
+            (_, "input" | "answer") => Response::new(405, "text/plain", "405 Method Not Allowed"),
+            _ => Response::new(404, "text/plain", "404 Not Found"),
+        }
+    }
+
+    /// The message for `answer` to a level of `day`, or an error response if there is nothing to judge it against.
+    fn judge(&mut self, day: u8, level: Option<&str>, answer: &str, now: Instant) -> Result<String, Response> {
+        let part = match level {
+            Some("1") => Part::One,
+            Some("2") => Part::Two,
+            _ => return Ok(String::from("You don't seem to be solving the right level.  Did you already complete it?")),
+        };
+        let open = part == Part::One || self.solved.contains(&(day, Part::One));
+        if !open || self.solved.contains(&(day, part)) {
+            return Ok(String::from("You don't seem to be solving the right level.  Did you already complete it?"));
+        }
+        let Some(expected) = self.answers.get(day, part) else {
+            let message = format!("500 Internal Server Error: no answer recorded for day {} part {}", day, part);
+            return Err(Response::new(500, "text/plain", message));
+        };
+        let left = self.wait_until.and_then(|until| until.checked_duration_since(now)).filter(|left| !left.is_zero());
+        if let Some(left) = left {
+            return Ok(format!(
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have {} left to wait.",
+                format_wait(left)
+            ));
+        }
+        if answer == expected {
+            self.solved.insert((day, part));
+            return Ok(String::from("That's the right answer!  You are <span class=\"gold\">one gold star</span> closer to finishing the Advent of Code."));
+        }
+        self.wait_until = Some(now + self.cooldown);
+        let hint = match (answer.parse::<i64>(), expected.parse::<i64>()) {
+            (Ok(answer), Ok(expected)) if answer > expected => "; your answer is too high",
+            (Ok(answer), Ok(expected)) if answer < expected => "; your answer is too low",
+            _ => "",
+        };
+        Ok(format!(
+            "That's not the right answer{}.  If you're stuck, make sure you're using the full input data.  Please wait {} before trying again.",
+            hint,
+            format_wait(self.cooldown)
+        ))
+    }
+}
+
+/// A wait the way the server puts it, like `45s` or `1m 5s`.
+fn format_wait(wait: Duration) -> String {
+    let seconds = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+    match (seconds / 60, seconds % 60) {
+        (0, seconds) => format!("{}s", seconds),
+        (minutes, 0) => format!("{}m", minutes),
+        (minutes, seconds) => format!("{}m {}s", minutes, seconds),
+    }
+}
+
+/// A page shaped like the real ones, with the message in the `<article>`.
+fn page(day: u8, message: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en-us\">\n<head><title>Day {} - Advent of Code {}</title></head>\n<body>\n<main>\n<article><p>{} <a href=\"/{}/day/{}\">[Return to Day {}]</a></p></article>\n</main>\n</body>\n</html>\n",
+        day, YEAR, message, YEAR, day, day
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::{Client, Hint, Outcome};
+
+    fn server(cooldown: Duration) -> MockServer {
+        let inputs = HashMap::from([(1, String::from("3   4\n4   3\n"))]);
+        let answers = Answers::parse("[day_01]\npart1 = 2\npart2 = 12\n").unwrap();
+        MockServer::new("secret", inputs, answers, cooldown)
+    }
+
+    #[test]
+    fn test_fetch_and_submit() {
+        let url = server(Duration::from_millis(200)).spawn().unwrap();
+        let client = Client::new(&url, "secret").unwrap();
+        assert_eq!(client.fetch_input(1), Ok(String::from("3   4\n4   3\n")));
+        assert!(client.fetch_input(2).unwrap_err().starts_with("the server answered 404: Please don't repeatedly request"));
+        let stranger = Client::new(&url, "guess").unwrap();
+        assert_eq!(
+            stranger.fetch_input(1),
+            Err(String::from("the server answered 400: Puzzle inputs differ by user. Please log in to get your puzzle input."))
+        );
+
+        assert_eq!(client.submit(1, Part::Two, "12"), Ok(Outcome::WrongLevel));
+        assert_eq!(client.submit(1, Part::One, "5"), Ok(Outcome::Wrong(Some(Hint::TooHigh))));
+        assert_eq!(client.submit(1, Part::One, "2"), Ok(Outcome::TooSoon(String::from("1s"))));
+        std::thread::sleep(Duration::from_millis(250));
+        assert_eq!(client.submit(1, Part::One, "2"), Ok(Outcome::Correct));
+        assert_eq!(client.submit(1, Part::One, "2"), Ok(Outcome::WrongLevel));
+        assert_eq!(client.submit(1, Part::Two, "1"), Ok(Outcome::Wrong(Some(Hint::TooLow))));
+    }
+
+    #[test]
+    fn test_respond() {
+        let mut server = server(Duration::from_secs(60));
+        let now = Instant::now();
+        let submit = |answer: &str| {
+            Request::post_form("/2024/day/1/answer", &[("level", "1"), ("answer", answer)]).header("Cookie", "theme=dark; session=secret")
+        };
+        let wrong = server.respond(&submit("abc"), now);
+        assert_eq!(wrong.status, 200);
+        assert_eq!(Outcome::read(&wrong.body), Outcome::Wrong(None));
+        assert!(wrong.body.contains("Please wait 1m before trying again."));
+        let early = server.respond(&submit("2"), now + Duration::from_millis(15_500));
+        assert_eq!(Outcome::read(&early.body), Outcome::TooSoon(String::from("45s")));
+        assert_eq!(Outcome::read(&server.respond(&submit("2"), now + Duration::from_secs(60)).body), Outcome::Correct);
+
+        assert_eq!(server.respond(&Request::post_form("/2024/day/1/input", &[]).header("Cookie", "session=secret"), now).status, 405);
+        let form = |fields: &[(&str, &str)]| Request::post_form("/2024/day/1/answer", fields).header("Cookie", "session=secret");
+        assert_eq!(server.respond(&form(&[("level", "2")]), now).status, 400);
+        assert_eq!(server.respond(&form(&[("level", "2"), ("answer", "")]), now).status, 400);
+
+        let mut unrecorded = MockServer::new("secret", HashMap::from([(1, String::new())]), Answers::default(), Duration::ZERO);
+        let response = unrecorded.respond(&form(&[("level", "1"), ("answer", "2")]), now);
+        assert_eq!((response.status, response.body.as_str()), (500, "500 Internal Server Error: no answer recorded for day 1 part 1"));
+        assert_eq!(server.respond(&Request::get("/2024/leaderboard"), now).status, 404);
+        assert_eq!(format_wait(Duration::from_secs(65)), "1m 5s");
+    }
+}
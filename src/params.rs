@@ -0,0 +1,88 @@
+//! Puzzle parameters: the numbers a puzzle text states for the real input but changes for its
+//! examples, like day 14's grid size. Every day declares its parameters with the real values as
+//! defaults, and callers override them per input.
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::parse::ParseError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Params {
+    values: Vec<(&'static str, String)>,
+}
+
+impl Params {
+    pub fn new(defaults: &[(&'static str, &str)]) -> Params {
+        Params {
+            values: defaults
+                .iter()
+                .map(|&(name, value)| (name, String::from(value)))
+                .collect(),
+        }
+    }
+
+    /// Overrides a declared parameter; the value is only checked once a day reads it.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match self.values.iter().position(|(known, _)| *known == name) {
+            Some(index) => {
+                self.values[index].1 = String::from(value);
+                Ok(())
+            }
+            None if self.values.is_empty() => Err(format!("unknown parameter {}, this day has none", name)),
+            None => Err(format!("unknown parameter {}, expected one of {}", name, self.names().join(", "))),
+        }
+    }
+
+    pub fn with(mut self, name: &str, value: impl Display) -> Result<Params, String> {
+        self.set(name, &value.to_string())?;
+        Ok(self)
+    }
+
+    /// Reads a declared parameter, reporting values that don't parse as a [`ParseError`].
+    pub fn get<T: FromStr>(&self, name: &str) -> Result<T, ParseError> {
+        let value = self
+            .values
+            .iter()
+            .find(|(known, _)| *known == name)
+            .map(|(_, value)| value.as_str())
+            .unwrap_or_else(|| panic!("parameter {} is not declared", name));
+        value.parse().map_err(|_| ParseError {
+            file: format!("parameter {}", name),
+            line: 0,
+            column: 0,
+            width: 0,
+            expected: format!("a valid {}", name),
+            found: format!("`{}`", value),
+            source_line: String::new(),
+        })
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.values.iter().map(|(name, _)| *name).collect()
+    }
+}
+
+impl Display for Params {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pairs: Vec<String> = self.values.iter().map(|(name, value)| format!("{}={}", name, value)).collect();
+        write!(f, "{}", pairs.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params() {
+        let params = Params::new(&[("width", "101"), ("height", "103")]).with("height", 7).unwrap();
+        assert_eq!(params.get::<usize>("width"), Ok(101));
+        assert_eq!(params.get::<usize>("height"), Ok(7));
+        assert_eq!(params.to_string(), "width=101, height=7");
+
+        let err = params.clone().with("width", "wide").unwrap().get::<usize>("width").unwrap_err();
+        assert_eq!(err.to_string(), "parameter width: expected a valid width, found `wide`");
+        assert_eq!(params.with("depth", 3).unwrap_err(), "unknown parameter depth, expected one of width, height");
+        assert_eq!(Params::new(&[]).set("depth", "3").unwrap_err(), "unknown parameter depth, this day has none");
+    }
+}
@@ -0,0 +1,222 @@
+//! Input reading with located errors. Parsers slice tokens out of [`Source::text`] and hand those
+//! slices back to report problems, so every error knows its file, line and column. The text is
+//! [normalized](normalize) first, so parsers don't have to care how the input was saved.
+
+use std::{fmt::Display, fs, io, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub file: String,
+    /// 1-based; 0 if the error is not about a specific line, e.g. an unreadable file.
+    pub line: usize,
+    /// 1-based, counted in characters.
+    pub column: usize,
+    /// How many characters the offending token spans, for underlining it.
+    pub width: usize,
+    pub expected: String,
+    pub found: String,
+    /// The full offending line, shown below the message.
+    pub source_line: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.line == 0 {
+            return write!(f, "{}: expected {}, found {}", self.file, self.expected, self.found);
+        }
+        writeln!(f, "{}:{}:{}: expected {}, found {}", self.file, self.line, self.column, self.expected, self.found)?;
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        write!(f, "{} | {}{}", gutter, " ".repeat(self.column - 1), "^".repeat(self.width.max(1)))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The path that makes [`Source::read`] read standard input instead of a file.
+pub const STDIN: &str = "-";
+
+/// Turns `\r\n` line endings into `\n`, strips trailing whitespace from every line and drops
+/// blank lines at the end, keeping the final newline if there was one.
+pub fn normalize(text: &str) -> String {
+    let mut normalized = text.lines().map(str::trim_end).collect::<Vec<_>>().join("\n");
+    normalized.truncate(normalized.trim_end_matches('\n').len());
+    if !normalized.is_empty() && text.ends_with('\n') {
+        normalized.push('\n');
+    }
+    normalized
+}
+
+/// The [normalized](normalize) text of one input file, plus its name for error messages.
+pub struct Source {
+    pub file: String,
+    pub text: String,
+}
+
+impl Source {
+    pub fn new(file: &str, text: impl Into<String>) -> Source {
+        Source {
+            file: String::from(file),
+            text: normalize(&text.into()),
+        }
+    }
+
+    /// Reads the file at `path`, or standard input if `path` is [`STDIN`].
+    pub fn read(path: &str) -> Result<Source, ParseError> {
+        let (file, text) = match path {
+            STDIN => ("<stdin>", io::read_to_string(io::stdin())),
+            _ => (path, fs::read_to_string(path)),
+        };
+        let text = text.map_err(|err| ParseError {
+            file: String::from(file),
+            line: 0,
+            column: 0,
+            width: 0,
+            expected: String::from("a readable input file"),
+            found: err.to_string(),
+            source_line: String::new(),
+        })?;
+        Ok(Source::new(file, text))
+    }
+
+    /// Reports that `token`, which must be a slice of [`Source::text`], is not what the parser expected.
+    /// An empty token reports the end of the line (or of the input) at its position.
+    pub fn error(&self, token: &str, expected: impl Display) -> ParseError {
+        let start = self.text.as_ptr() as usize;
+        let offset = (token.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|&offset| offset + token.len() <= self.text.len());
+        let Some(offset) = offset else {
+            return ParseError {
+                file: self.file.clone(),
+                line: 0,
+                column: 0,
+                width: 0,
+                expected: expected.to_string(),
+                found: format!("`{}`", token),
+                source_line: String::new(),
+            };
+        };
+
+        let before = &self.text[..offset];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        let found = if !token.is_empty() {
+            format!("`{}`", token.lines().next().unwrap_or(token))
+        } else if offset == self.text.len() {
+            String::from("end of input")
+        } else {
+            String::from("end of line")
+        };
+        ParseError {
+            file: self.file.clone(),
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            width: token.lines().next().unwrap_or("").chars().count(),
+            expected: expected.to_string(),
+            found,
+            source_line: String::from(self.text[line_start..].lines().next().unwrap_or("")),
+        }
+    }
+
+    /// Reports that something expected is missing after the end of `text`.
+    pub fn missing(&self, text: &str, expected: impl Display) -> ParseError {
+        self.error(&text[text.len()..], expected)
+    }
+
+    pub fn parse<T: FromStr>(&self, token: &str, expected: impl Display) -> Result<T, ParseError> {
+        token.parse().map_err(|_| self.error(token, expected))
+    }
+
+    pub fn number<T: FromStr>(&self, token: &str) -> Result<T, ParseError> {
+        self.parse(token, "a number")
+    }
+
+    /// Takes the next token, reporting the end of `text` if there is none.
+    pub fn next<'a>(&self, tokens: &mut impl Iterator<Item = &'a str>, text: &'a str, expected: impl Display) -> Result<&'a str, ParseError> {
+        tokens.next().ok_or_else(|| self.missing(text, expected))
+    }
+
+    pub fn strip_prefix<'a>(&self, text: &'a str, prefix: &str) -> Result<&'a str, ParseError> {
+        text.strip_prefix(prefix).ok_or_else(|| {
+            let found = text
+                .char_indices()
+                .nth(prefix.chars().count())
+                .map_or(text, |(end, _)| &text[..end]);
+            self.error(found, format_args!("`{}`", prefix))
+        })
+    }
+
+    pub fn split_once<'a>(&self, text: &'a str, delimiter: &str) -> Result<(&'a str, &'a str), ParseError> {
+        text.split_once(delimiter)
+            .ok_or_else(|| self.missing(text, format_args!("`{}`", delimiter)))
+    }
+
+    /// Splits `text` at its first run of blank lines.
+    pub fn sections<'a>(&self, text: &'a str) -> Result<(&'a str, &'a str), ParseError> {
+        text.split_once("\n\n")
+            .map(|(first, second)| (first, second.trim_start_matches('\n')))
+            .ok_or_else(|| self.missing(text, "a blank line between sections"))
+    }
+
+    /// The blocks of lines in `text` that are separated by one or more blank lines.
+    pub fn blocks<'a>(&self, text: &'a str) -> impl Iterator<Item = &'a str> {
+        text.split("\n\n")
+            .map(|block| block.trim_start_matches('\n'))
+            .filter(|block| !block.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_location() {
+        let source = Source::new("day_01.txt", "3   4\n4 abc\n");
+        let token = source.text.lines().nth(1).unwrap().split_whitespace().nth(1).unwrap();
+        let err = source.number::<usize>(token).unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (2, 3, 3));
+        assert_eq!(err.found, "`abc`");
+        assert_eq!(err.to_string(), "day_01.txt:2:3: expected a number, found `abc`\n  |\n2 | 4 abc\n  |   ^^^");
+    }
+
+    #[test]
+    fn test_missing_tokens() {
+        let source = Source::new("day_18.txt", "5,4\n4");
+        let line = source.text.lines().nth(1).unwrap();
+        let err = source.split_once(line, ",").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert_eq!(err.expected, "`,`");
+        assert_eq!(err.found, "end of input");
+
+        let first = source.text.lines().next().unwrap();
+        assert_eq!(source.missing(first, "more").found, "end of line");
+        assert_eq!(source.strip_prefix(first, "p=").unwrap_err().found, "`5,`");
+        assert_eq!(source.error("not in the source", "x").line, 0);
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("1 2\r\n3 4  \r\n\r\n \n"), "1 2\n3 4\n");
+        assert_eq!(normalize("a\t\n\n b\n\n"), "a\n\n b\n");
+        assert_eq!(normalize("a\nb"), "a\nb");
+        assert_eq!(normalize("\n\n"), "");
+    }
+
+    #[test]
+    fn test_sections_and_blocks() {
+        let source = Source::new("day_13.txt", "a\r\nb\r\n\r\n\r\nc\r\n  \r\nd\r\n\r\n");
+        assert_eq!(source.sections(&source.text).unwrap(), ("a\nb", "c\n\nd\n"));
+        assert_eq!(source.blocks(&source.text).collect::<Vec<_>>(), ["a\nb", "c", "d\n"]);
+        let err = source.sections("a\nb\n").unwrap_err();
+        assert_eq!(err.expected, "a blank line between sections");
+    }
+
+    #[test]
+    fn test_unreadable_file() {
+        let err = Source::read("./input_test/does_not_exist.txt").err().unwrap();
+        assert_eq!(err.line, 0);
+        assert!(err.to_string().starts_with("./input_test/does_not_exist.txt: expected a readable input file, found "));
+    }
+}
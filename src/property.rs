@@ -0,0 +1,217 @@
+//! Differential property tests: inputs from a day's generator are solved two ways, typically the
+//! optimized way next to a naive one, and a check compares the results. When they disagree, the
+//! input is shrunk to a small example that still shows it and saved to `input_test/`, ready to be
+//! turned into a regular test.
+
+use std::{
+    fmt::Debug,
+    fs, io,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use regex::Regex;
+
+use crate::{params::Params, parse::Source, random::Rng, solution::Solution};
+
+/// How many inputs to generate, one per seed starting at 0, and how big they are.
+#[derive(Clone, Copy, Debug)]
+pub struct Config {
+    pub cases: u64,
+    pub size: usize,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config { cases: 50, size: 20 }
+    }
+}
+
+/// A generated input the check rejected, after shrinking.
+#[derive(Debug, PartialEq)]
+pub struct Counterexample {
+    pub seed: u64,
+    pub text: String,
+    pub message: String,
+}
+
+impl Counterexample {
+    /// Writes the shrunk input to `<dir>/<name>.txt`, returning the path.
+    pub fn save(&self, dir: &Path, name: &str) -> io::Result<PathBuf> {
+        let path = dir.join(format!("{}.txt", name));
+        fs::write(&path, &self.text)?;
+        Ok(path)
+    }
+}
+
+/// Compares two results of the same thing, describing the difference for the report.
+pub fn agree<T: PartialEq + Debug>(what: &str, optimized: T, naive: T) -> Result<(), String> {
+    if optimized == naive {
+        Ok(())
+    } else {
+        Err(format!("{}: optimized {:?}, naive {:?}", what, optimized, naive))
+    }
+}
+
+/// Runs `check` on inputs generated for `S`, returning the first one it rejects, shrunk. A panic
+/// in the check counts as a rejection, but inputs that no longer parse while shrinking don't.
+pub fn find_counterexample<S: Solution>(
+    config: Config,
+    params: &Params,
+    check: impl Fn(&S::Input) -> Result<(), String>,
+) -> Option<Counterexample> {
+    let verdict = |text: &str| -> Option<String> {
+        let input = S::parse(&Source::new("generated", text), params).ok()?;
+        match panic::catch_unwind(AssertUnwindSafe(|| check(&input))) {
+            Ok(result) => result.err(),
+            Err(panic) => Some(panic_message(panic.as_ref())),
+        }
+    };
+    (0..config.cases).find_map(|seed| {
+        let text = S::generate(&mut Rng::new(seed), config.size, params)
+            .expect("Expecting the parameters to be valid")
+            .expect("Expecting the day to have a generator");
+        verdict(&text)?;
+        let text = shrink(&text, |text| verdict(text).is_some());
+        let message = verdict(&text).expect("Expecting the shrunk input to still fail");
+        Some(Counterexample { seed, text, message })
+    })
+}
+
+/// Checks `S` with [`find_counterexample`] and the day's default parameters. A counterexample is
+/// saved as `./input_test/<name>.txt` before the test fails with it.
+pub fn assert_agree<S: Solution>(name: &str, config: Config, check: impl Fn(&S::Input) -> Result<(), String>) {
+    let Some(counterexample) = find_counterexample::<S>(config, &S::params(), check) else {
+        return;
+    };
+    let saved = match counterexample.save(Path::new("./input_test"), name) {
+        Ok(path) => format!("saved to {}", path.display()),
+        Err(err) => format!("failed to save it to ./input_test: {}", err),
+    };
+    panic!(
+        "{}: {} with the input of seed {}, shrunk to ({}):\n{}",
+        name, counterexample.message, counterexample.seed, saved, counterexample.text
+    );
+}
+
+/// Describes a caught panic by its message, if it has one.
+pub fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    let message = panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("no message");
+    format!("panicked: {}", message)
+}
+
+/// Makes `text` as small as possible while `fails` still holds for it: first by dropping
+/// blocks, lines and words, then by making numbers smaller.
+pub fn shrink(text: &str, fails: impl Fn(&str) -> bool) -> String {
+    let mut text = String::from(text);
+    for separator in ["\n\n", "\n", " "] {
+        text = drop_parts(&text, separator, &fails);
+    }
+    shrink_numbers(&text, &fails)
+}
+
+/// Removes ever smaller runs of the parts of `text` between `separator`s, keeping a trailing newline.
+fn drop_parts(text: &str, separator: &str, fails: &impl Fn(&str) -> bool) -> String {
+    let ending = if text.ends_with('\n') { "\n" } else { "" };
+    let join = |parts: &[&str]| parts.join(separator) + ending;
+    let mut parts: Vec<&str> = text.strip_suffix(ending).unwrap_or(text).split(separator).collect();
+    let mut run = parts.len() / 2;
+    while run > 0 {
+        let mut start = 0;
+        while start < parts.len() && parts.len() > 1 {
+            let end = (start + run).min(parts.len());
+            let candidate = [&parts[..start], &parts[end..]].concat();
+            if fails(&join(&candidate)) {
+                parts = candidate;
+            } else {
+                start = end;
+            }
+        }
+        run /= 2;
+    }
+    join(&parts)
+}
+
+static NUMBER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d+").unwrap());
+
+/// Replaces every number by the smallest of 0, 1, half of it or one less that still fails, for as long as that works.
+fn shrink_numbers(text: &str, fails: &impl Fn(&str) -> bool) -> String {
+    let mut text = String::from(text);
+    let mut index = 0;
+    while let Some(number) = NUMBER.find_iter(&text).nth(index) {
+        let range = number.range();
+        let Ok(value) = number.as_str().parse::<u64>() else {
+            index += 1;
+            continue;
+        };
+        let replace = |smaller: u64| format!("{}{}{}", &text[..range.start], smaller, &text[range.end..]);
+        let smaller = [0, 1, value / 2, value.saturating_sub(1)]
+            .into_iter()
+            .filter(|&smaller| smaller < value)
+            .map(replace)
+            .find(|candidate| fails(candidate));
+        match smaller {
+            Some(candidate) => text = candidate,
+            None => index += 1,
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shrink() {
+        // fails as long as some word is a number of at least 30
+        let fails = |text: &str| text.split_whitespace().any(|word| word.parse::<u64>().is_ok_and(|number| number >= 30));
+        assert_eq!(shrink("1 2\n3 45 6\n7\n\n80 9\n", fails), "30\n");
+        assert_eq!(shrink("1 2 3", |text: &str| text.contains('2')), "2");
+    }
+
+    #[test]
+    fn test_find_counterexample() {
+        use crate::day_01::{Day01, Lists};
+
+        // any two different lists have a total distance, so this check fails on every input
+        let check = |lists: &Lists| agree("total distance is zero", Day01::part1(lists) == 0, true);
+        let counterexample = find_counterexample::<Day01>(Config::default(), &Day01::params(), check).unwrap();
+        assert_eq!(counterexample.seed, 0);
+        assert_eq!(counterexample.text, "0 1\n");
+        assert_eq!(counterexample.message, "total distance is zero: optimized false, naive true");
+
+        assert_eq!(find_counterexample::<Day01>(Config::default(), &Day01::params(), |_| Ok(())), None);
+        let panics = find_counterexample::<Day01>(Config::default(), &Day01::params(), |_| panic!("boom"));
+        assert_eq!(panics.unwrap().message, "panicked: boom");
+    }
+
+    #[test]
+    fn test_save_counterexample() {
+        let name = format!("aoc2024_counterexample_{}", std::process::id());
+        let counterexample = Counterexample { seed: 3, text: String::from("0 1\n"), message: String::new() };
+        let path = counterexample.save(&std::env::temp_dir(), &name).unwrap();
+        assert_eq!(path, std::env::temp_dir().join(format!("{}.txt", name)));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "0 1\n");
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_assert_agree_saves_counterexample() {
+        use crate::day_01::{Day01, Lists};
+
+        let name = format!("aoc2024_property_{}", std::process::id());
+        let check = |lists: &Lists| agree("total distance is zero", Day01::part1(lists) == 0, true);
+        let failed = panic::catch_unwind(|| assert_agree::<Day01>(&name, Config::default(), check));
+        let path = format!("./input_test/{}.txt", name);
+        let saved = fs::read_to_string(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(failed.is_err());
+        assert_eq!(saved.unwrap(), "0 1\n");
+    }
+}
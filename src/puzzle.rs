@@ -0,0 +1,166 @@
+//! Downloading inputs from the puzzle server and submitting answers to it. Answers come back as
+//! a web page for people, so [`Outcome::read`] looks for the sentences that tell them apart.
+
+use std::fmt::Display;
+
+use aoc2024::solution::Part;
+
+use crate::http::{self, Request, Url};
+
+pub const YEAR: u16 = 2024;
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+/// The server asks tools to say who they are.
+const USER_AGENT: &str = "aoc2024 fetch/submit (a personal Advent of Code solutions repo)";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hint {
+    TooHigh,
+    TooLow,
+}
+
+impl Hint {
+    pub fn name(self) -> &'static str {
+        match self {
+            Hint::TooHigh => "too high",
+            Hint::TooLow => "too low",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Hint> {
+        [Hint::TooHigh, Hint::TooLow].into_iter().find(|hint| hint.name() == name)
+    }
+}
+
+/// What the server made of a submitted answer.
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Correct,
+    Wrong(Option<Hint>),
+    /// Another answer was wrong too recently; holds how long is left, like `45s`.
+    TooSoon(String),
+    /// The part was already solved, or part 2 isn't open yet.
+    WrongLevel,
+    /// Anything else, with the text of the page.
+    Unknown(String),
+}
+
+impl Outcome {
+    pub fn read(page: &str) -> Outcome {
+        let text = article_text(page);
+        if text.starts_with("That's the right answer") {
+            Outcome::Correct
+        } else if text.starts_with("That's not the right answer") {
+            let hint = [Hint::TooHigh, Hint::TooLow]
+                .into_iter()
+                .find(|hint| text.contains(&format!("your answer is {}", hint.name())));
+            Outcome::Wrong(hint)
+        } else if text.starts_with("You gave an answer too recently") {
+            let left = text
+                .split_once("You have ")
+                .and_then(|(_, rest)| rest.split_once(" left to wait"))
+                .map_or("a while", |(left, _)| left);
+            Outcome::TooSoon(String::from(left))
+        } else if text.starts_with("You don't seem to be solving the right level") {
+            Outcome::WrongLevel
+        } else {
+            Outcome::Unknown(text)
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "That's the right answer!"),
+            Outcome::Wrong(Some(hint)) => write!(f, "That's not the right answer, it's {}", hint.name()),
+            Outcome::Wrong(None) => write!(f, "That's not the right answer"),
+            Outcome::TooSoon(left) => write!(f, "An answer was submitted too recently, wait {} before trying again", left),
+            Outcome::WrongLevel => write!(f, "That part is already solved or not open yet"),
+            Outcome::Unknown(text) => write!(f, "Unexpected response: {}", text),
+        }
+    }
+}
+
+/// The text of the `<article>` that holds the message, without tags and with whitespace
+/// collapsed; the whole page if there is none.
+fn article_text(page: &str) -> String {
+    let article = page
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(page, |(article, _)| article);
+    let mut text = String::new();
+    let mut in_tag = false;
+    for char in article.chars() {
+        match char {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(char),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A session on the puzzle server; the token is the value of the `session` cookie after logging in.
+pub struct Client {
+    base: Url,
+    session: String,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Result<Client, String> {
+        if session.is_empty() {
+            return Err(String::from("the session token is empty"));
+        }
+        Ok(Client { base: Url::parse(base_url)?, session: String::from(session) })
+    }
+
+    fn send(&self, request: Request) -> Result<http::Response, String> {
+        let request = request
+            .header("Cookie", &format!("session={}", self.session))
+            .header("User-Agent", USER_AGENT);
+        http::send(&self.base, &request)
+    }
+
+    pub fn fetch_input(&self, day: u8) -> Result<String, String> {
+        let response = self.send(Request::get(&format!("/{}/day/{}/input", YEAR, day)))?;
+        match response.status {
+            200 => Ok(response.body),
+            status => Err(format!("the server answered {}: {}", status, article_text(&response.body))),
+        }
+    }
+
+    pub fn submit(&self, day: u8, part: Part, answer: &str) -> Result<Outcome, String> {
+        let level = part.to_string();
+        let request = Request::post_form(&format!("/{}/day/{}/answer", YEAR, day), &[("level", &level), ("answer", answer)]);
+        let response = self.send(request)?;
+        match response.status {
+            200 => Ok(Outcome::read(&response.body)),
+            status => Err(format!("the server answered {}: {}", status, article_text(&response.body))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_outcome() {
+        let page = |message: &str| format!("<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>", message);
+        assert_eq!(Outcome::read(&page("That's the right answer!  You are <em>one gold star</em> closer.")), Outcome::Correct);
+        assert_eq!(
+            Outcome::read(&page("That's not the right answer; your answer is too low.  Please wait one minute.")),
+            Outcome::Wrong(Some(Hint::TooLow))
+        );
+        assert_eq!(Outcome::read(&page("That's not the right answer.  If you're stuck...")), Outcome::Wrong(None));
+        assert_eq!(
+            Outcome::read(&page("You gave an answer too recently; you have to wait.  You have 1m 5s left to wait.")),
+            Outcome::TooSoon(String::from("1m 5s"))
+        );
+        assert_eq!(Outcome::read(&page("You don't seem to be solving the right level.  Did you already complete it?")), Outcome::WrongLevel);
+        assert_eq!(Outcome::read("Internal error"), Outcome::Unknown(String::from("Internal error")));
+        assert_eq!(Hint::from_name("too high"), Some(Hint::TooHigh));
+    }
+}
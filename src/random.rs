@@ -0,0 +1,93 @@
+//! A small seeded random number generator for the input generators, so the same seed always
+//! produces the same input. This is SplitMix64: fast, tiny, and plenty random for puzzle inputs,
+//! but not for anything that needs to be unpredictable.
+
+use std::ops::RangeInclusive;
+
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number below `bound`, which has to be positive. The slight bias of the multiply and
+    /// shift reduction doesn't matter at puzzle sizes.
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "Expecting a positive bound");
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<usize>) -> usize {
+        let (start, end) = range.into_inner();
+        start + self.below(end - start + 1)
+    }
+
+    /// Like [`Rng::range`], but for signed numbers such as velocities.
+    pub fn signed(&mut self, range: RangeInclusive<isize>) -> isize {
+        let (start, end) = range.into_inner();
+        start + self.below(end.abs_diff(start) + 1) as isize
+    }
+
+    /// True with a probability of `numerator / denominator`.
+    pub fn chance(&mut self, numerator: usize, denominator: usize) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            items.swap(index, self.below(index + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng_is_reproducible() {
+        let numbers = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..5).map(|_| rng.next_u64()).collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(7), numbers(7));
+        assert_ne!(numbers(7), numbers(8));
+        // the reference output of SplitMix64 for seed 0
+        assert_eq!(numbers(0)[0], 0xe220_a839_7b1d_cdaf);
+    }
+
+    #[test]
+    fn test_rng_ranges() {
+        let mut rng = Rng::new(1);
+        let mut seen = [false; 6];
+        for _ in 0..1000 {
+            let value = rng.range(3..=8);
+            seen[value - 3] = true;
+            assert!((-2..=2).contains(&rng.signed(-2..=2)));
+        }
+        assert!(seen.iter().all(|&seen| seen));
+        assert!(!rng.chance(0, 4) && rng.chance(4, 4));
+
+        let mut items: Vec<usize> = (0..20).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..20).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,212 @@
+//! An interactive prompt around one day's parsed input. The input stays in memory between
+//! commands, so solving it again with other parameters or asking the day's queries about it
+//! doesn't mean reading the file again, let alone recompiling.
+
+use std::{
+    any::Any,
+    io::{self, BufRead, Write},
+    time::Instant,
+};
+
+use aoc2024::{
+    solution::{Entry, Part},
+    visualize::{Renderer, Settings},
+};
+
+use crate::bench::format_duration;
+
+const COMMANDS: &[(&str, &str)] = &[
+    ("1, 2", "Solve part 1 or part 2"),
+    ("run", "Solve both parts"),
+    ("params", "Show the parameters the input was parsed with"),
+    ("set <name=N>", "Change a parameter and parse the input again"),
+    ("reset", "Go back to the usual parameters and parse the input again"),
+    ("reload", "Read the input file again, e.g. after editing it"),
+    ("show [fps]", "Play the day's visualization, which ends on its final state"),
+    ("help", "Show this help"),
+    ("quit", "Leave, as does Ctrl+D"),
+];
+
+pub struct Repl {
+    entry: &'static Entry,
+    input_file: String,
+    /// Parameters overridden on the command line or with `set`.
+    overrides: Vec<(String, String)>,
+    input: Box<dyn Any>,
+    settings: Settings,
+}
+
+impl Repl {
+    /// Parses the input of `entry` from `input_file` with the parameter `overrides`.
+    pub fn new(entry: &'static Entry, input_file: &str, overrides: Vec<(String, String)>, settings: Settings) -> Result<Repl, String> {
+        let input = crate::load_day(entry, input_file, &overrides)?;
+        Ok(Repl { entry, input_file: String::from(input_file), overrides, input, settings })
+    }
+
+    /// Runs one command line, returning what to print.
+    pub fn execute(&mut self, line: &str) -> Result<String, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = words.split_first() else {
+            return Ok(String::new());
+        };
+        let is_query = self.entry.solution.queries().iter().any(|(usage, _)| usage.split(' ').next() == Some(command));
+        match (command, args) {
+            ("1" | "part1", []) => Ok(self.solve(Part::One)),
+            ("2" | "part2", []) => Ok(self.solve(Part::Two)),
+            ("run", []) => Ok(format!("{}\n{}", self.solve(Part::One), self.solve(Part::Two))),
+            ("params", []) => self.params(),
+            ("set", [param]) => {
+                let (name, value) = param.split_once('=').ok_or(format!("expected name=value, got {}", param))?;
+                let mut overrides: Vec<_> = self.overrides.iter().filter(|(known, _)| known != name).cloned().collect();
+                overrides.push((String::from(name), String::from(value)));
+                self.reparse(overrides)
+            }
+            ("reset", []) => self.reparse(vec![]),
+            ("reload", []) => self.reparse(self.overrides.clone()),
+            ("show", []) => self.show(self.settings.fps),
+            ("show", [fps]) => self.show(fps.parse().map_err(|_| format!("expected frames per second, got {}", fps))?),
+            ("help", []) => Ok(self.help()),
+            _ if is_query => self.entry.solution.query(self.input.as_ref(), command, args),
+            ("set", _) => Err(String::from("usage: set <name=N>")),
+            ("show", _) => Err(String::from("usage: show [fps]")),
+            _ if COMMANDS.iter().any(|(usage, _)| usage.split(", ").any(|name| name == command)) => {
+                Err(format!("{} takes no arguments", command))
+            }
+            _ => Err(format!("unknown command {}, type help for the commands", command)),
+        }
+    }
+
+    fn solve(&self, part: Part) -> String {
+        let start = Instant::now();
+        let answer = self.entry.solve(self.input.as_ref(), part);
+        format!("Part {}: {} ({})", part, answer, format_duration(start.elapsed()))
+    }
+
+    fn params(&self) -> Result<String, String> {
+        let params = crate::select_params(self.entry, &self.input_file, &self.overrides)?;
+        if params.names().is_empty() {
+            Ok(format!("Day {:02} has no parameters", self.entry.day))
+        } else {
+            Ok(params.to_string())
+        }
+    }
+
+    /// Parses the input file again with `overrides`, keeping the current input if that fails.
+    fn reparse(&mut self, overrides: Vec<(String, String)>) -> Result<String, String> {
+        let start = Instant::now();
+        self.input = crate::load_day(self.entry, &self.input_file, &overrides)?;
+        let time = start.elapsed();
+        self.overrides = overrides;
+        Ok(format!("Parsed {} in {}, {}", self.input_file, format_duration(time), self.params()?))
+    }
+
+    fn show(&self, fps: u32) -> Result<String, String> {
+        let mut renderer = Renderer::new(Settings { fps, ..self.settings });
+        if self.entry.solution.visualize(self.input.as_ref(), &mut renderer) {
+            Ok(String::new())
+        } else {
+            Err(format!("day {:02} has no visualization", self.entry.day))
+        }
+    }
+
+    fn help(&self) -> String {
+        let queries = self.entry.solution.queries();
+        let width = COMMANDS.iter().chain(queries).map(|(usage, _)| usage.len()).max().unwrap_or_default();
+        let lines = |list: &[(&str, &str)]| {
+            list.iter().map(|(usage, description)| format!("  {:<width$}  {}", usage, description)).collect::<Vec<_>>().join("\n")
+        };
+        let mut help = format!("Commands:\n{}", lines(COMMANDS));
+        if !queries.is_empty() {
+            help.push_str(&format!("\n\nQueries of day {:02}:\n{}", self.entry.day, lines(queries)));
+        }
+        help
+    }
+}
+
+/// Prompts for commands on `input` and answers them on `out` until `quit` or the end of the input.
+pub fn run(mut repl: Repl, input: impl BufRead, mut out: impl Write) -> io::Result<()> {
+    let prompt = format!("day {:02}> ", repl.entry.day);
+    out.write_all(prompt.as_bytes())?;
+    out.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        if matches!(line.trim(), "quit" | "exit") {
+            return Ok(());
+        }
+        match repl.execute(&line) {
+            Ok(reply) if reply.is_empty() => {}
+            Ok(reply) => writeln!(out, "{}", reply)?,
+            Err(err) => writeln!(out, "error: {}", err)?,
+        }
+        out.write_all(prompt.as_bytes())?;
+        out.flush()?;
+    }
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc2024::solution;
+
+    use super::*;
+
+    fn repl(day: u8, overrides: &[(&str, &str)]) -> Repl {
+        let entry = solution::find(day).unwrap();
+        let overrides = overrides.iter().map(|&(name, value)| (String::from(name), String::from(value))).collect();
+        Repl::new(entry, &entry.example_file(), overrides, Settings { fps: 0, ..Settings::default() }).unwrap()
+    }
+
+    /// The answer part of a reply like `Part 1: 12 (0.01ms)`.
+    fn answer(reply: Result<String, String>) -> String {
+        let reply = reply.unwrap();
+        let (_, rest) = reply.split_once(": ").unwrap();
+        String::from(rest.split_once(' ').unwrap().0)
+    }
+
+    #[test]
+    fn test_parameters() {
+        let mut repl = repl(11, &[]);
+        assert_eq!(answer(repl.execute("1")), "55312");
+        assert_eq!(repl.execute("params"), Ok(String::from("blinks1=25, blinks2=25")));
+        assert!(repl.execute("set blinks1=6").unwrap().ends_with(", blinks1=6, blinks2=25"));
+        assert_eq!(answer(repl.execute("part1")), "22");
+        assert!(repl.execute("set blinks3=6").unwrap_err().contains("unknown parameter blinks3"));
+        assert!(repl.execute("set blinks1=many").unwrap_err().contains("expected a valid blinks1"));
+        assert_eq!(answer(repl.execute("1")), "22");
+        assert!(repl.execute("reset").is_ok());
+        assert_eq!(answer(repl.execute("1")), "55312");
+    }
+
+    #[test]
+    fn test_example_parameters_apply() {
+        let mut repl = repl(14, &[]);
+        assert_eq!(repl.execute("params"), Ok(String::from("width=11, height=7")));
+        assert_eq!(answer(repl.execute("1")), "12");
+        assert_eq!(repl.execute("params"), Ok(String::from("width=11, height=7")));
+        assert!(repl.execute("set width").is_err());
+    }
+
+    #[test]
+    fn test_queries_and_errors() {
+        let mut repl = repl(12, &[]);
+        assert!(repl.execute("region 3,1").unwrap().starts_with("Region of B at (3,1): area 4, perimeter 8, 4 sides"));
+        assert!(repl.execute("region").unwrap_err().starts_with("usage: region"));
+        assert!(repl.execute("help").unwrap().contains("Queries of day 12:\n  region <x,y>"));
+        assert_eq!(repl.execute("   "), Ok(String::new()));
+        assert_eq!(repl.execute("run 2"), Err(String::from("run takes no arguments")));
+        assert_eq!(repl.execute("cost 1,1 up"), Err(String::from("unknown command cost, type help for the commands")));
+        assert_eq!(self::repl(1, &[]).execute("show"), Err(String::from("day 01 has no visualization")));
+    }
+
+    #[test]
+    fn test_run() {
+        let mut out = vec![];
+        run(repl(1, &[]), "2\nparams\nquit\n1\n".as_bytes(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(lines[0].starts_with("day 01> Part 2: 31 ("));
+        assert_eq!(lines[1], "day 01> Day 01 has no parameters");
+        assert_eq!(lines[2], "day 01> ");
+        assert_eq!(lines.len(), 3);
+    }
+}
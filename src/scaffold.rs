@@ -0,0 +1,230 @@
+use std::{fmt::Display, fs, path::Path};
+
+/// What scaffolding did with one of the files a new day needs.
+#[derive(Debug, PartialEq)]
+pub enum Step {
+    Created(String),
+    Updated(String),
+    Unchanged(String),
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Step::Created(path) => write!(f, "created   {}", path),
+            Step::Updated(path) => write!(f, "updated   {}", path),
+            Step::Unchanged(path) => write!(f, "unchanged {}", path),
+        }
+    }
+}
+
+fn template(day: u8) -> String {
+    let day = format!("{:02}", day);
+    format!(
+"use crate::{{params::Params, parse::{{ParseError, Source}}, solution::Solution}};
+
+pub struct Day{day};
+
+impl Solution for Day{day} {{
+    type Input = String;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(source: &Source, _params: &Params) -> Result<String, ParseError> {{
+        Ok(source.text.clone())
+    }}
+
+    fn part1(input: &String) -> usize {{
+        puzzle1(input)
+    }}
+
+    fn part2(input: &String) -> usize {{
+        puzzle2(input)
+    }}
+}}
+
+// Puzzle 1 function
+fn puzzle1(_input: &str) -> usize {{
+    0
+}}
+
+// Puzzle 2 function
+fn puzzle2(_input: &str) -> usize {{
+    0
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+
+    #[test]
+    fn test_puzzle1() {{
+        assert_eq!(puzzle1(&Day{day}::load(\"./input_test/day_{day}.txt\").unwrap()), 0);
+    }}
+
+    #[test]
+    fn test_puzzle2() {{
+        assert_eq!(puzzle2(&Day{day}::load(\"./input_test/day_{day}.txt\").unwrap()), 0);
+    }}
+}}
+")
+}
+
+/// Inserts `line` among the consecutive lines matching `is_sibling`, keeping them sorted.
+/// Falls back to inserting before the first line matching `is_end` if there are no siblings yet.
+fn insert_sorted(content: &str, line: &str, is_sibling: impl Fn(&str) -> bool, is_end: impl Fn(&str) -> bool) -> Option<String> {
+    let mut lines: Vec<&str> = content.lines().collect();
+    if lines.contains(&line) {
+        return None;
+    }
+    let siblings: Vec<usize> = (0..lines.len()).filter(|&index| is_sibling(lines[index])).collect();
+    let index = match (siblings.first(), siblings.last()) {
+        (Some(&first), Some(&last)) => (first..=last)
+            .find(|&index| is_sibling(lines[index]) && lines[index] > line)
+            .unwrap_or(last + 1),
+        _ => lines.iter().position(|line| is_end(line))?,
+    };
+    lines.insert(index, line);
+    Some(lines.join("\n") + "\n")
+}
+
+/// Adds `pub mod day_XX;` to lib.rs, or returns `None` if it is already there.
+pub fn register_module(lib_rs: &str, day: u8) -> Option<String> {
+    insert_sorted(
+        lib_rs,
+        &format!("pub mod day_{:02};", day),
+        |line| line.starts_with("pub mod day_"),
+        |line| line.starts_with("pub mod "),
+    )
+}
+
+/// Adds the day's entry to `SOLUTIONS` in solution.rs, or returns `None` if it is already there.
+pub fn register_solution(solution_rs: &str, day: u8) -> Option<String> {
+    let entry = format!("    Entry {{ day: {}, solution: &crate::day_{:02}::Day{:02} }},", day, day, day);
+    // the days in the entries aren't zero padded, so compare them as numbers
+    let key = |line: &str| line
+        .trim()
+        .strip_prefix("Entry { day: ")
+        .and_then(|rest| rest.split(',').next())
+        .and_then(|num| num.parse::<u8>().ok());
+    if solution_rs.lines().any(|line| key(line) == Some(day)) {
+        return None;
+    }
+    let mut lines: Vec<&str> = solution_rs.lines().collect();
+    let start = lines.iter().position(|line| line.starts_with("pub static SOLUTIONS"))?;
+    let end = start + lines[start..].iter().position(|line| line.trim() == "];")?;
+    let index = (start + 1..end)
+        .find(|&index| key(lines[index]).is_some_and(|other| other > day))
+        .unwrap_or(end);
+    lines.insert(index, &entry);
+    Some(lines.join("\n") + "\n")
+}
+
+/// Appends an `[[example]]` for the day's example file, or returns `None` if one is listed already.
+pub fn add_example(examples_toml: &str, day: u8) -> Option<String> {
+    let file = format!("file = \"day_{:02}.txt\"", day);
+    if examples_toml.lines().any(|line| line.trim() == file) {
+        return None;
+    }
+    let mut content = String::from(examples_toml.trim_end());
+    content.push_str(&format!("\n\n[[example]]\nday = {}\n{}\n", day, file));
+    Some(content)
+}
+
+fn create_file(root: &Path, path: &str, content: &str) -> Result<Step, String> {
+    let full_path = root.join(path);
+    if full_path.exists() {
+        return Ok(Step::Unchanged(String::from(path)));
+    }
+    if let Some(parent) = full_path.parent() {
+        fs::create_dir_all(parent).map_err(|err| format!("{}: {}", parent.display(), err))?;
+    }
+    fs::write(&full_path, content).map_err(|err| format!("{}: {}", path, err))?;
+    Ok(Step::Created(String::from(path)))
+}
+
+fn update_file(root: &Path, path: &str, update: impl Fn(&str) -> Option<String>) -> Result<Step, String> {
+    let full_path = root.join(path);
+    let content = fs::read_to_string(&full_path).map_err(|err| format!("{}: {}", path, err))?;
+    match update(&content) {
+        Some(updated) => {
+            fs::write(&full_path, updated).map_err(|err| format!("{}: {}", path, err))?;
+            Ok(Step::Updated(String::from(path)))
+        }
+        None => Ok(Step::Unchanged(String::from(path))),
+    }
+}
+
+/// Sets up everything a new day needs below `root`. Existing files are never overwritten,
+/// so running it again only fills in what is missing.
+pub fn scaffold(root: &Path, day: u8) -> Result<Vec<Step>, String> {
+    let file = format!("day_{:02}", day);
+    Ok(vec![
+        create_file(root, &format!("src/{}.rs", file), &template(day))?,
+        create_file(root, &format!("input/{}.txt", file), "")?,
+        create_file(root, &format!("input_test/{}.txt", file), "")?,
+        update_file(root, "input_test/examples.toml", |content| add_example(content, day))?,
+        update_file(root, "src/lib.rs", |content| register_module(content, day))?,
+        update_file(root, "src/solution.rs", |content| register_solution(content, day))?,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_module() {
+        let lib_rs = "//! Docs\n\npub mod day_01;\npub mod day_03;\npub mod grid;\n";
+        assert_eq!(register_module(lib_rs, 2).unwrap(), "//! Docs\n\npub mod day_01;\npub mod day_02;\npub mod day_03;\npub mod grid;\n");
+        assert_eq!(register_module(lib_rs, 4).unwrap(), "//! Docs\n\npub mod day_01;\npub mod day_03;\npub mod day_04;\npub mod grid;\n");
+        assert_eq!(register_module(lib_rs, 3), None);
+        assert_eq!(register_module("pub mod grid;\n", 1).unwrap(), "pub mod day_01;\npub mod grid;\n");
+    }
+
+    #[test]
+    fn test_register_solution() {
+        let solution_rs = "pub static SOLUTIONS: &[Entry] = &[\n    Entry { day: 9, solution: &crate::day_09::Day09 },\n    Entry { day: 11, solution: &crate::day_11::Day11 },\n];\n";
+        let updated = register_solution(solution_rs, 10).unwrap();
+        assert_eq!(updated.lines().nth(2), Some("    Entry { day: 10, solution: &crate::day_10::Day10 },"));
+        assert_eq!(register_solution(&updated, 12).unwrap().lines().nth(4), Some("    Entry { day: 12, solution: &crate::day_12::Day12 },"));
+        assert_eq!(register_solution(&updated, 10), None);
+    }
+
+    #[test]
+    fn test_add_example() {
+        let updated = add_example("# examples\n[[example]]\nday = 1\nfile = \"day_01.txt\"\n", 2).unwrap();
+        assert!(updated.ends_with("file = \"day_01.txt\"\n\n[[example]]\nday = 2\nfile = \"day_02.txt\"\n"));
+        assert_eq!(add_example(&updated, 2), None);
+    }
+
+    #[test]
+    fn test_scaffold_is_idempotent() {
+        let root = std::env::temp_dir().join(format!("aoc2024_scaffold_{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("input_test")).unwrap();
+        fs::write(root.join("src/lib.rs"), "pub mod day_01;\npub mod solution;\n").unwrap();
+        fs::write(root.join("src/solution.rs"), "pub static SOLUTIONS: &[Entry] = &[\n    Entry { day: 1, solution: &crate::day_01::Day01 },\n];\n").unwrap();
+        fs::write(root.join("input_test/examples.toml"), "").unwrap();
+        fs::write(root.join("input_test/day_02.txt"), "my example").unwrap();
+
+        let steps = scaffold(&root, 2).unwrap();
+        assert_eq!(steps, vec![
+            Step::Created(String::from("src/day_02.rs")),
+            Step::Created(String::from("input/day_02.txt")),
+            Step::Unchanged(String::from("input_test/day_02.txt")),
+            Step::Updated(String::from("input_test/examples.toml")),
+            Step::Updated(String::from("src/lib.rs")),
+            Step::Updated(String::from("src/solution.rs")),
+        ]);
+        assert_eq!(fs::read_to_string(root.join("input_test/day_02.txt")).unwrap(), "my example");
+        assert!(fs::read_to_string(root.join("src/day_02.rs")).unwrap().contains("pub struct Day02;"));
+
+        fs::write(root.join("src/day_02.rs"), "// solved").unwrap();
+        let steps = scaffold(&root, 2).unwrap();
+        assert!(steps.iter().all(|step| matches!(step, Step::Unchanged(_))));
+        assert_eq!(fs::read_to_string(root.join("src/day_02.rs")).unwrap(), "// solved");
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,323 @@
+//! Graph searches for the days that look for shortest paths or explore what is reachable. They
+//! are generic over the node, anything `Eq + Hash` like a grid point or a point with a facing,
+//! and take the graph as a function from a node to its neighbors. Every search records the
+//! lowest cost of each node it reached together with all the nodes that reach it at that cost,
+//! so one cheapest path, all of them, or every node on one can be rebuilt afterwards.
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// What a search found: the lowest cost to every node it reached, how to get there, and the
+/// goals it stopped at.
+pub struct Search<N, C> {
+    costs: HashMap<N, C>,
+    /// Every node one step before the key on one of its cheapest paths; empty for the starts.
+    predecessors: HashMap<N, Vec<N>>,
+    goals: Vec<N>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy + Ord + Add<Output = C> + Default> Search<N, C> {
+    fn new() -> Search<N, C> {
+        Search { costs: HashMap::new(), predecessors: HashMap::new(), goals: vec![] }
+    }
+
+    /// Notes that `to` is reached at `cost` from `from`, or as a start without one. Returns
+    /// whether that is cheaper than before, so `to` has to be expanded again.
+    fn reach(&mut self, from: Option<&N>, to: N, cost: C) -> bool {
+        match self.costs.get(&to) {
+            Some(&known) if cost > known => false,
+            Some(&known) if cost == known => {
+                let predecessors = self.predecessors.entry(to).or_default();
+                if let Some(from) = from.filter(|from| !predecessors.contains(from)) {
+                    predecessors.push(from.clone());
+                }
+                false
+            }
+            _ => {
+                self.costs.insert(to.clone(), cost);
+                self.predecessors.insert(to, from.into_iter().cloned().collect());
+                true
+            }
+        }
+    }
+
+    /// The lowest cost to reach `node`, None if the search didn't get there.
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.costs.get(node).copied()
+    }
+
+    /// Every node the search reached, with its lowest cost, in no particular order.
+    pub fn reached(&self) -> impl Iterator<Item = (&N, C)> {
+        self.costs.iter().map(|(node, &cost)| (node, cost))
+    }
+
+    /// The goals reached at the lowest cost, in the order they were found; empty if none was.
+    pub fn goals(&self) -> &[N] {
+        &self.goals
+    }
+
+    /// The lowest cost to reach a goal, None if none was reached.
+    pub fn goal_cost(&self) -> Option<C> {
+        self.goals.first().map(|goal| self.costs[goal])
+    }
+
+    /// One of the cheapest paths from a start to `node`, both included.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        let mut path = vec![node.clone()];
+        let mut current = node;
+        while let Some(previous) = self.predecessors.get(current)?.first() {
+            path.push(previous.clone());
+            current = previous;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every cheapest path from a start to `node`. There can be exponentially many, so
+    /// [`Search::count_paths_to`] and [`Search::nodes_on_paths_to`] are better for big graphs.
+    pub fn paths_to(&self, node: &N) -> Vec<Vec<N>> {
+        let Some(predecessors) = self.predecessors.get(node) else {
+            return vec![];
+        };
+        if predecessors.is_empty() {
+            return vec![vec![node.clone()]];
+        }
+        predecessors
+            .iter()
+            .flat_map(|previous| self.paths_to(previous))
+            .map(|mut path| {
+                path.push(node.clone());
+                path
+            })
+            .collect()
+    }
+
+    /// How many cheapest paths lead from a start to `node`, without listing them.
+    pub fn count_paths_to(&self, node: &N) -> usize {
+        fn count<N: Clone + Eq + Hash>(predecessors: &HashMap<N, Vec<N>>, node: &N, counted: &mut HashMap<N, usize>) -> usize {
+            if let Some(&paths) = counted.get(node) {
+                return paths;
+            }
+            let paths = match predecessors.get(node) {
+                None => 0,
+                Some(previous) if previous.is_empty() => 1,
+                Some(previous) => previous.iter().map(|previous| count(predecessors, previous, counted)).sum(),
+            };
+            counted.insert(node.clone(), paths);
+            paths
+        }
+        count(&self.predecessors, node, &mut HashMap::new())
+    }
+
+    /// Every node on a cheapest path from a start to any of `ends`, the ends included.
+    pub fn nodes_on_paths_to<'a>(&'a self, ends: impl IntoIterator<Item = &'a N>) -> HashSet<N> {
+        let mut nodes = HashSet::new();
+        let mut stack: Vec<&N> = ends.into_iter().filter(|end| self.costs.contains_key(end)).collect();
+        while let Some(node) = stack.pop() {
+            if nodes.insert(node.clone()) {
+                stack.extend(&self.predecessors[node]);
+            }
+        }
+        nodes
+    }
+}
+
+/// A node waiting in the priority queue; the heap pops the lowest priority first.
+struct Queued<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> Ord for Queued<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+impl<N, C: Ord> PartialOrd for Queued<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> PartialEq for Queued<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Ord> Eq for Queued<N, C> {}
+
+/// Breadth first search from `starts`, where every step costs 1. Stops once the nodes as far
+/// away as the nearest goal are done, or explores everything reachable if `is_goal` never holds.
+pub fn bfs<N, I>(starts: impl IntoIterator<Item = N>, mut neighbors: impl FnMut(&N) -> I, mut is_goal: impl FnMut(&N) -> bool) -> Search<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if search.reach(None, start.clone(), 0) {
+            queue.push_back(start);
+        }
+    }
+    let mut goal_distance = None;
+    while let Some(node) = queue.pop_front() {
+        let distance = search.costs[&node];
+        if goal_distance.is_some_and(|goal| distance > goal) {
+            break;
+        }
+        if is_goal(&node) {
+            goal_distance = Some(distance);
+            search.goals.push(node);
+            continue;
+        }
+        for next in neighbors(&node) {
+            if search.reach(Some(&node), next.clone(), distance + 1) {
+                queue.push_back(next);
+            }
+        }
+    }
+    search
+}
+
+/// Dijkstra's search from `starts` over `successors`, which gives each neighbor with the cost
+/// of the step there. Stops like [`bfs`] once no goal can be reached any cheaper.
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    successors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    a_star(starts, successors, |_| C::default(), is_goal)
+}
+
+/// A* search: [`dijkstra`] that tries nodes closer to a goal by `heuristic` first. The
+/// heuristic must never overestimate the cost left, nor drop by more than a step costs, or the
+/// costs and paths found may not be the cheapest.
+pub fn a_star<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut search = Search::new();
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        if search.reach(None, start.clone(), C::default()) {
+            heap.push(Queued { priority: heuristic(&start), cost: C::default(), node: start });
+        }
+    }
+    let mut goal_cost = None;
+    while let Some(Queued { priority, cost, node }) = heap.pop() {
+        if goal_cost.is_some_and(|goal| priority > goal) {
+            break;
+        }
+        if search.costs[&node] < cost {
+            // queued again since with a lower cost
+            continue;
+        }
+        if is_goal(&node) {
+            goal_cost = Some(cost);
+            search.goals.push(node);
+            continue;
+        }
+        for (next, step) in successors(&node) {
+            let next_cost = cost + step;
+            if search.reach(Some(&node), next.clone(), next_cost) {
+                heap.push(Queued { priority: next_cost + heuristic(&next), cost: next_cost, node: next });
+            }
+        }
+    }
+    search
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{grid::{Grid, Point}, parse::Source};
+
+    /// A weighted diamond with two cheapest ways from `a` to `d` and a dearer one through `e`.
+    fn diamond(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'a' => vec![('b', 1), ('c', 2), ('e', 1)],
+            'b' => vec![('d', 2)],
+            'c' => vec![('d', 1)],
+            'e' => vec![('d', 5)],
+            _ => vec![],
+        }
+    }
+
+    fn maze(text: &str) -> Grid<char> {
+        Grid::parse(&Source::new("maze", text), text).unwrap()
+    }
+
+    #[test]
+    fn test_dijkstra_paths() {
+        let search = dijkstra(['a'], diamond, |&node| node == 'd');
+        assert_eq!((search.goals(), search.goal_cost()), (&['d'][..], Some(3)));
+        assert_eq!(search.path_to(&'d'), Some(vec!['a', 'b', 'd']));
+        assert_eq!(search.paths_to(&'d'), vec![vec!['a', 'b', 'd'], vec!['a', 'c', 'd']]);
+        assert_eq!(search.count_paths_to(&'d'), 2);
+        assert_eq!(search.nodes_on_paths_to(search.goals()), HashSet::from(['a', 'b', 'c', 'd']));
+        assert_eq!((search.cost(&'e'), search.path_to(&'x')), (Some(1), None));
+    }
+
+    #[test]
+    fn test_bfs_stops_at_the_goal() {
+        let grid = maze("S....\n.###.\n....E\n");
+        let open = |&point: &Point| grid.neighbors4(point).filter(|&next| grid[next] != '#').collect::<Vec<_>>();
+        let search = bfs([Point(0, 0)], open, |&point| grid[point] == 'E');
+        assert_eq!(search.goal_cost(), Some(6));
+        assert_eq!(search.count_paths_to(&Point(4, 2)), 2);
+        assert_eq!(search.paths_to(&Point(4, 2)).len(), 2);
+
+        let everything = bfs([Point(0, 0)], open, |_| false);
+        assert_eq!((everything.reached().count(), everything.goal_cost()), (12, None));
+        assert_eq!(everything.cost(&Point(4, 2)), Some(6));
+    }
+
+    #[test]
+    fn test_a_star_agrees_with_dijkstra() {
+        let grid = maze("S.#.....\n..#.##..\n....#..E\n.##.#.#.\n........\n");
+        let end = Point(7, 2);
+        let steps = |&point: &Point| grid.neighbors4(point).filter(|&next| grid[next] != '#').map(|next| (next, 1)).collect::<Vec<_>>();
+        let distance = |&point: &Point| (end.0 - point.0).unsigned_abs() + (end.1 - point.1).unsigned_abs();
+        let guided = a_star([Point(0, 0)], steps, distance, |&point| point == end);
+        let plain = dijkstra([Point(0, 0)], steps, |&point| point == end);
+        assert_eq!(guided.goal_cost(), Some(13));
+        assert_eq!(guided.goal_cost(), plain.goal_cost());
+        assert_eq!(guided.count_paths_to(&end), plain.count_paths_to(&end));
+
+        // straight across an open field, the heuristic keeps the search on the first two rows
+        let field = Grid::new(10, 10, '.');
+        let steps = |&point: &Point| field.neighbors4(point).map(|next| (next, 1)).collect::<Vec<_>>();
+        let guided = a_star([Point(0, 0)], steps, |&point: &Point| 9 - point.0.unsigned_abs(), |&point| point == Point(9, 0));
+        let plain = dijkstra([Point(0, 0)], steps, |&point| point == Point(9, 0));
+        assert_eq!((guided.goal_cost(), plain.goal_cost()), (Some(9), Some(9)));
+        assert!(guided.reached().count() <= 20 && plain.reached().count() > 50);
+    }
+
+    #[test]
+    fn test_unreachable_goal() {
+        let search = bfs([0], |&node: &u32| (node < 3).then_some(node + 1), |&node| node == 5);
+        assert_eq!((search.goals(), search.goal_cost()), (&[][..], None));
+        assert!(search.nodes_on_paths_to(&[5]).is_empty());
+        assert_eq!(search.count_paths_to(&5), 0);
+    }
+}
@@ -0,0 +1,159 @@
+//! Snapshot tests of simulations. A test renders the state after every step as text, and the
+//! steps are compared with the ones saved in `input_test/snapshots/<name>.txt`, so a change in
+//! behavior is reported at the first step that differs instead of only in the final answer.
+//! Snapshot files are only written when `UPDATE_SNAPSHOTS` is set, so a missing one fails the
+//! test with the steps it would contain; review the new files before committing them.
+
+use std::{env, fmt::Display, fs, path::Path};
+
+/// The rendered steps of one simulation, each with a label like `Move <`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Snapshot {
+    steps: Vec<(String, String)>,
+}
+
+impl Snapshot {
+    pub fn new() -> Snapshot {
+        Snapshot::default()
+    }
+
+    pub fn step(&mut self, label: impl Display, state: impl Display) {
+        let state = state.to_string();
+        self.steps.push((label.to_string(), String::from(state.trim_end_matches('\n'))));
+    }
+
+    /// The snapshot file format: every step under a `== label ==` line, separated by blank lines.
+    pub fn to_text(&self) -> String {
+        self.steps.iter().map(|(label, state)| format!("== {} ==\n{}\n", label, state)).collect::<Vec<_>>().join("\n")
+    }
+
+    pub fn parse(text: &str) -> Snapshot {
+        let mut snapshot = Snapshot::new();
+        for line in text.lines() {
+            match line.strip_prefix("== ").and_then(|line| line.strip_suffix(" ==")) {
+                Some(label) => snapshot.steps.push((String::from(label), String::new())),
+                None => {
+                    if let Some((_, state)) = snapshot.steps.last_mut() {
+                        state.push_str(line);
+                        state.push('\n');
+                    }
+                }
+            }
+        }
+        for (_, state) in &mut snapshot.steps {
+            state.truncate(state.trim_end_matches('\n').len());
+        }
+        snapshot
+    }
+
+    /// Describes the first step where `self` differs from `expected`, with a diff of its lines.
+    pub fn compare(&self, expected: &Snapshot) -> Result<(), String> {
+        for (index, (actual, expected)) in self.steps.iter().zip(&expected.steps).enumerate() {
+            if actual != expected {
+                let step = if actual.0 == expected.0 {
+                    format!("step {} (`{}`)", index + 1, actual.0)
+                } else {
+                    format!("step {} (expected `{}`, got `{}`)", index + 1, expected.0, actual.0)
+                };
+                return Err(format!("{} differs:\n{}", step, diff_lines(&expected.1, &actual.1)));
+            }
+        }
+        if self.steps.len() != expected.steps.len() {
+            return Err(format!("expected {} steps, got {}", expected.steps.len(), self.steps.len()));
+        }
+        Ok(())
+    }
+
+    /// Compares with the snapshot file at `path`, or writes it if `update` is set.
+    pub fn check(&self, path: &Path, update: bool) -> Result<(), String> {
+        if update {
+            let written = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|()| fs::write(path, self.to_text()));
+            return written.map_err(|err| format!("failed to write {}: {}", path.display(), err));
+        }
+        if !path.exists() {
+            return Err(format!("there is no snapshot yet, the steps are:\n{}", self.to_text()));
+        }
+        let saved = fs::read_to_string(path).map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+        self.compare(&Snapshot::parse(&saved))
+    }
+
+    /// Checks the snapshot against `./input_test/snapshots/<name>.txt`, failing the test at the first step that differs.
+    pub fn assert(&self, name: &str) {
+        let path = format!("./input_test/snapshots/{}.txt", name);
+        let update = env::var_os("UPDATE_SNAPSHOTS").is_some_and(|value| !value.is_empty());
+        if let Err(err) = self.check(Path::new(&path), update) {
+            panic!("snapshot {} failed: {}\n(run with UPDATE_SNAPSHOTS=1 to accept the new one)", path, err);
+        }
+    }
+}
+
+/// Lines prefixed with `-` if only `expected` has them at that position, `+` if only `actual`
+/// does, and two spaces if both agree, which suits grids of the same shape best.
+fn diff_lines(expected: &str, actual: &str) -> String {
+    let (expected, actual): (Vec<&str>, Vec<&str>) = (expected.lines().collect(), actual.lines().collect());
+    let mut diff = vec![];
+    for index in 0..expected.len().max(actual.len()) {
+        match (expected.get(index), actual.get(index)) {
+            (Some(expected), Some(actual)) if expected == actual => diff.push(format!("  {}", actual)),
+            (expected, actual) => {
+                diff.extend(expected.map(|line| format!("- {}", line)));
+                diff.extend(actual.map(|line| format!("+ {}", line)));
+            }
+        }
+    }
+    diff.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter(steps: usize, wrong_at: Option<usize>) -> Snapshot {
+        let mut snapshot = Snapshot::new();
+        for step in 0..steps {
+            let state = if wrong_at == Some(step) { step + 10 } else { step };
+            snapshot.step(format_args!("Step {}", step), format!("#{}#\n#..#\n", state));
+        }
+        snapshot
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let snapshot = counter(3, None);
+        assert_eq!(snapshot.to_text(), "== Step 0 ==\n#0#\n#..#\n\n== Step 1 ==\n#1#\n#..#\n\n== Step 2 ==\n#2#\n#..#\n");
+        assert_eq!(Snapshot::parse(&snapshot.to_text()), snapshot);
+    }
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(counter(3, None).compare(&counter(3, None)), Ok(()));
+        assert_eq!(
+            counter(3, Some(1)).compare(&counter(3, None)),
+            Err(String::from("step 2 (`Step 1`) differs:\n- #1#\n+ #11#\n  #..#"))
+        );
+        let mut relabeled = counter(2, None);
+        relabeled.step("Last step", "#2#\n#..#");
+        assert_eq!(
+            relabeled.compare(&counter(3, None)),
+            Err(String::from("step 3 (expected `Step 2`, got `Last step`) differs:\n  #2#\n  #..#"))
+        );
+        assert_eq!(counter(2, None).compare(&counter(3, None)), Err(String::from("expected 3 steps, got 2")));
+    }
+
+    #[test]
+    fn test_check_only_writes_when_updating() {
+        let path = env::temp_dir().join(format!("aoc2024_snapshot_{}", std::process::id())).join("counter.txt");
+        let _ = fs::remove_file(&path);
+        assert_eq!(
+            counter(1, None).check(&path, false),
+            Err(String::from("there is no snapshot yet, the steps are:\n== Step 0 ==\n#0#\n#..#\n"))
+        );
+        assert!(!path.exists());
+        assert_eq!(counter(3, None).check(&path, true), Ok(()));
+        assert_eq!(counter(3, None).check(&path, false), Ok(()));
+        assert!(counter(3, Some(2)).check(&path, false).unwrap_err().starts_with("step 3 (`Step 2`) differs:"));
+        assert_eq!(counter(3, Some(2)).check(&path, true), Ok(()));
+        assert_eq!(counter(3, Some(2)).check(&path, false), Ok(()));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,260 @@
+use std::{any::Any, fmt::Display};
+
+use crate::{params::Params, parse::{ParseError, Source}, random::Rng, visualize::Renderer};
+
+/// A single day's puzzle: parse the input once, then solve both parts on it.
+pub trait Solution {
+    type Input: 'static;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    /// The parameters the day reads, with their values for the real input.
+    const PARAMS: &'static [(&'static str, &'static str)] = &[];
+
+    /// The questions the REPL can ask about a parsed input, as usage and description, like
+    /// `("region <x,y>", "...")`. The first word of the usage is the name passed to [`Solution::query`].
+    const QUERIES: &'static [(&'static str, &'static str)] = &[];
+
+    fn parse(source: &Source, params: &Params) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Self::Answer1;
+    fn part2(input: &Self::Input) -> Self::Answer2;
+
+    /// The day's parameters set to their defaults, ready to be overridden.
+    fn params() -> Params {
+        Params::new(Self::PARAMS)
+    }
+
+    /// Reads and parses the input file at `path` with the default parameters.
+    fn load(path: &str) -> Result<Self::Input, ParseError> {
+        Self::load_with(path, &Self::params())
+    }
+
+    fn load_with(path: &str, params: &Params) -> Result<Self::Input, ParseError> {
+        Self::parse(&Source::read(path)?, params)
+    }
+
+    /// Solves both parts of the puzzle input `text` with the default parameters, without touching the file system.
+    fn solve(text: &str) -> Result<(Self::Answer1, Self::Answer2), ParseError> {
+        let input = Self::parse(&Source::new("input", text), &Self::params())?;
+        Ok((Self::part1(&input), Self::part2(&input)))
+    }
+
+    /// Plays the day's simulation on `renderer`, returning false if the day has nothing to show.
+    fn visualize(_input: &Self::Input, _renderer: &mut Renderer) -> bool {
+        false
+    }
+
+    /// Answers one of the [`Solution::QUERIES`] with the words that followed its name.
+    fn query(_input: &Self::Input, name: &str, _args: &[&str]) -> Result<String, String> {
+        Err(format!("unknown query {}", name))
+    }
+
+    /// Writes a random puzzle input, to test and time the day beyond the sizes of the real input.
+    /// What `size` counts, like lines or tiles per side of a map, is up to the day. None if the
+    /// day has no generator.
+    fn generate(_rng: &mut Rng, _size: usize, _params: &Params) -> Result<Option<String>, ParseError> {
+        Ok(None)
+    }
+}
+
+/// Type erased view on a [`Solution`] so days with different input types fit into one registry.
+pub trait AnySolution: Sync {
+    fn params(&self) -> Params;
+    fn parse(&self, source: &Source, params: &Params) -> Result<Box<dyn Any>, ParseError>;
+    fn part1(&self, input: &dyn Any) -> String;
+    fn part2(&self, input: &dyn Any) -> String;
+    /// Solves `part` inside `around`, which has to call the closure it is given, and only then
+    /// formats the answer, so `around` can observe the solving alone.
+    fn solve_within(&self, input: &dyn Any, part: Part, around: &mut dyn FnMut(&mut dyn FnMut())) -> String;
+    fn visualize(&self, input: &dyn Any, renderer: &mut Renderer) -> bool;
+    fn queries(&self) -> &'static [(&'static str, &'static str)];
+    fn query(&self, input: &dyn Any, name: &str, args: &[&str]) -> Result<String, String>;
+    fn generate(&self, rng: &mut Rng, size: usize, params: &Params) -> Result<Option<String>, ParseError>;
+}
+
+impl<S: Solution + Sync> AnySolution for S {
+    fn params(&self) -> Params {
+        S::params()
+    }
+
+    fn parse(&self, source: &Source, params: &Params) -> Result<Box<dyn Any>, ParseError> {
+        Ok(Box::new(S::parse(source, params)?))
+    }
+
+    fn part1(&self, input: &dyn Any) -> String {
+        S::part1(downcast::<S>(input)).to_string()
+    }
+
+    fn part2(&self, input: &dyn Any) -> String {
+        S::part2(downcast::<S>(input)).to_string()
+    }
+
+    fn solve_within(&self, input: &dyn Any, part: Part, around: &mut dyn FnMut(&mut dyn FnMut())) -> String {
+        let input = downcast::<S>(input);
+        let (mut answer1, mut answer2) = (None, None);
+        match part {
+            Part::One => around(&mut || answer1 = Some(S::part1(input))),
+            Part::Two => around(&mut || answer2 = Some(S::part2(input))),
+        }
+        let answer = answer1.map(|answer| answer.to_string()).or_else(|| answer2.map(|answer| answer.to_string()));
+        answer.expect("Expecting the part to be solved")
+    }
+
+    fn visualize(&self, input: &dyn Any, renderer: &mut Renderer) -> bool {
+        S::visualize(downcast::<S>(input), renderer)
+    }
+
+    fn queries(&self) -> &'static [(&'static str, &'static str)] {
+        S::QUERIES
+    }
+
+    fn query(&self, input: &dyn Any, name: &str, args: &[&str]) -> Result<String, String> {
+        S::query(downcast::<S>(input), name, args)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize, params: &Params) -> Result<Option<String>, ParseError> {
+        S::generate(rng, size, params)
+    }
+}
+
+fn downcast<S: Solution>(input: &dyn Any) -> &S::Input {
+    input
+        .downcast_ref()
+        .expect("Expecting input to be parsed by the same solution")
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+pub struct Entry {
+    pub day: u8,
+    pub solution: &'static dyn AnySolution,
+}
+
+impl Entry {
+    pub fn input_file(&self) -> String {
+        format!("./input/day_{:02}.txt", self.day)
+    }
+
+    pub fn example_file(&self) -> String {
+        format!("./input_test/day_{:02}.txt", self.day)
+    }
+
+    pub fn solve(&self, input: &dyn Any, part: Part) -> String {
+        match part {
+            Part::One => self.solution.part1(input),
+            Part::Two => self.solution.part2(input),
+        }
+    }
+}
+
+/// Every solved day in order; `aoc2024 new` inserts new days here.
+pub static SOLUTIONS: &[Entry] = &[
+    Entry { day: 1, solution: &crate::day_01::Day01 },
+    Entry { day: 2, solution: &crate::day_02::Day02 },
+    Entry { day: 3, solution: &crate::day_03::Day03 },
+    Entry { day: 4, solution: &crate::day_04::Day04 },
+    Entry { day: 5, solution: &crate::day_05::Day05 },
+    Entry { day: 6, solution: &crate::day_06::Day06 },
+    Entry { day: 7, solution: &crate::day_07::Day07 },
+    Entry { day: 8, solution: &crate::day_08::Day08 },
+    Entry { day: 9, solution: &crate::day_09::Day09 },
+    Entry { day: 10, solution: &crate::day_10::Day10 },
+    Entry { day: 11, solution: &crate::day_11::Day11 },
+    Entry { day: 12, solution: &crate::day_12::Day12 },
+    Entry { day: 13, solution: &crate::day_13::Day13 },
+    Entry { day: 14, solution: &crate::day_14::Day14 },
+    Entry { day: 15, solution: &crate::day_15::Day15 },
+    Entry { day: 16, solution: &crate::day_16::Day16 },
+    Entry { day: 17, solution: &crate::day_17::Day17 },
+    Entry { day: 18, solution: &crate::day_18::Day18 },
+];
+
+pub fn find(day: u8) -> Option<&'static Entry> {
+    SOLUTIONS.iter().find(|entry| entry.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_is_complete() {
+        for (index, entry) in SOLUTIONS.iter().enumerate() {
+            assert_eq!(entry.day as usize, index + 1);
+        }
+        assert!(find(7).is_some());
+        assert!(find(25).is_none());
+    }
+
+    #[test]
+    fn test_solve_entry() {
+        let entry = find(1).unwrap();
+        assert_eq!(entry.input_file(), "./input/day_01.txt");
+        assert_eq!(entry.example_file(), "./input_test/day_01.txt");
+        let input = entry.solution.parse(&Source::read(&entry.example_file()).unwrap(), &entry.solution.params()).unwrap();
+        assert_eq!(entry.solve(input.as_ref(), Part::One), "11");
+        assert_eq!(entry.solve(input.as_ref(), Part::Two), "31");
+
+        let mut calls = 0;
+        let answer = entry.solution.solve_within(input.as_ref(), Part::Two, &mut |solve| {
+            calls += 1;
+            solve();
+        });
+        assert_eq!((answer.as_str(), calls), ("31", 1));
+    }
+
+    #[test]
+    fn test_generated_inputs_solve() {
+        for entry in SOLUTIONS {
+            let params = entry.solution.params();
+            let generate = |seed| entry.solution.generate(&mut Rng::new(seed), 5, &params).unwrap();
+            // most seeds give day 11 a stone that takes seconds to blink at 75 times in a debug build
+            let Some(text) = generate(3) else {
+                // days don't have to come with a generator
+                continue;
+            };
+            assert_eq!(generate(3), Some(text.clone()), "day {} isn't reproducible", entry.day);
+            let input = entry.solution.parse(&Source::new("generated", &text), &params).unwrap();
+            entry.solve(input.as_ref(), Part::One);
+            entry.solve(input.as_ref(), Part::Two);
+        }
+    }
+
+    #[test]
+    fn test_inputs_saved_differently_solve_the_same() {
+        let answers = |entry: &Entry, text: &str| {
+            let params = entry.solution.params();
+            let input = entry.solution.parse(&Source::new("example", text), &params).unwrap();
+            (entry.solve(input.as_ref(), Part::One), entry.solve(input.as_ref(), Part::Two))
+        };
+        for entry in SOLUTIONS {
+            let text = Source::read(&entry.example_file()).unwrap().text;
+            let windows = text.lines().map(|line| format!("{} \r\n", line)).collect::<String>() + "\r\n\r\n";
+            assert_eq!(answers(entry, &windows), answers(entry, &text), "day {}", entry.day);
+        }
+    }
+
+    #[test]
+    fn test_solve_text() {
+        assert_eq!(crate::day_01::Day01::solve("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n"), Ok((11, 31)));
+        let err = crate::day_01::Day01::solve("3   4\n4\n").unwrap_err();
+        assert_eq!((err.file.as_str(), err.line, err.found.as_str()), ("input", 2, "end of line"));
+    }
+}
@@ -0,0 +1,146 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+};
+
+use aoc2024::solution::Part;
+
+use crate::{puzzle::Hint, toml};
+
+pub const SUBMISSIONS_FILE: &str = "./submissions.toml";
+
+const HEADER: &str = "# Answers the puzzle server rejected, so `aoc2024 submit` never sends them again.\n";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rejected {
+    pub day: u8,
+    pub part: Part,
+    pub answer: String,
+    /// Whether the server said the answer was too high or too low.
+    pub hint: Option<Hint>,
+}
+
+/// The wrong answers submitted so far, one `[[wrong]]` table each.
+#[derive(Debug, Default)]
+pub struct Submissions {
+    wrong: Vec<Rejected>,
+}
+
+impl Submissions {
+    /// Loads the submissions file, treating a missing file as "nothing submitted yet".
+    pub fn load(path: &str) -> Result<Submissions, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => Submissions::parse(&content).map_err(|err| format!("{}: {}", path, err)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Submissions::default()),
+            Err(err) => Err(format!("{}: {}", path, err)),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Submissions, String> {
+        let wrong = toml::parse(content)?
+            .into_iter()
+            .map(|table| {
+                if table.name != "wrong" {
+                    return Err(format!("line {}: expected [[wrong]], got [{}]", table.line, table.name));
+                }
+                let text = |key: &str| table.get(key).map(toml::Value::as_text);
+                let day = text("day").and_then(|day| day.parse().ok());
+                let part = match text("part").as_deref() {
+                    Some("1") => Some(Part::One),
+                    Some("2") => Some(Part::Two),
+                    _ => None,
+                };
+                let (Some(day), Some(part), Some(answer)) = (day, part, text("answer")) else {
+                    return Err(format!("line {}: a wrong answer needs a day, a part of 1 or 2 and the answer", table.line));
+                };
+                let hint = match text("hint") {
+                    Some(name) => Some(Hint::from_name(&name).ok_or(format!("line {}: unknown hint {}", table.line, name))?),
+                    None => None,
+                };
+                Ok(Rejected { day, part, answer, hint })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Submissions { wrong })
+    }
+
+    /// Why `answer` is known to be wrong: it was rejected before, or it lies beyond an answer
+    /// that was too high or too low.
+    pub fn rejection(&self, day: u8, part: Part, answer: &str) -> Option<String> {
+        let number = answer.parse::<i64>().ok();
+        self.wrong
+            .iter()
+            .filter(|rejected| rejected.day == day && rejected.part == part)
+            .find_map(|rejected| {
+                if rejected.answer == answer {
+                    return Some(format!("{} was already rejected", answer));
+                }
+                let bound = rejected.answer.parse::<i64>().ok()?;
+                match (rejected.hint?, number?) {
+                    (Hint::TooHigh, number) if number >= bound => Some(format!("{} is too high, {} already was", answer, bound)),
+                    (Hint::TooLow, number) if number <= bound => Some(format!("{} is too low, {} already was", answer, bound)),
+                    _ => None,
+                }
+            })
+    }
+
+    /// Remembers `rejected` here and in the file at `path`.
+    pub fn record(&mut self, path: &str, rejected: Rejected) -> Result<(), String> {
+        let exists = fs::metadata(path).is_ok();
+        let mut entry = if exists { String::new() } else { String::from(HEADER) };
+        entry.push_str(&format!(
+            "\n[[wrong]]\nday = {}\npart = {}\nanswer = \"{}\"\n",
+            rejected.day, rejected.part, rejected.answer
+        ));
+        if let Some(hint) = rejected.hint {
+            entry.push_str(&format!("hint = \"{}\"\n", hint.name()));
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(entry.as_bytes()))
+            .map_err(|err| format!("{}: {}", path, err))?;
+        self.wrong.push(rejected);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejection() {
+        let submissions = Submissions::parse(
+            "[[wrong]]\nday = 5\npart = 1\nanswer = \"4000\"\nhint = \"too high\"\n\n[[wrong]]\nday = 5\npart = 1\nanswer = \"100\"\nhint = \"too low\"\n\n[[wrong]]\nday = 17\npart = 1\nanswer = \"1,2,3\"\n",
+        )
+        .unwrap();
+        assert_eq!(submissions.rejection(5, Part::One, "4000"), Some(String::from("4000 was already rejected")));
+        assert_eq!(submissions.rejection(5, Part::One, "4100"), Some(String::from("4100 is too high, 4000 already was")));
+        assert_eq!(submissions.rejection(5, Part::One, "99"), Some(String::from("99 is too low, 100 already was")));
+        assert_eq!(submissions.rejection(5, Part::One, "2000"), None);
+        assert_eq!(submissions.rejection(5, Part::Two, "4100"), None);
+        assert_eq!(submissions.rejection(17, Part::One, "1,2,3"), Some(String::from("1,2,3 was already rejected")));
+
+        assert!(Submissions::parse("[[right]]\nday = 5").is_err());
+        assert!(Submissions::parse("[[wrong]]\nday = 5\npart = 3\nanswer = \"1\"").is_err());
+        assert!(Submissions::parse("[[wrong]]\nday = 5\npart = 1\nanswer = \"1\"\nhint = \"close\"").is_err());
+    }
+
+    #[test]
+    fn test_record() {
+        let path = std::env::temp_dir().join(format!("aoc2024_submissions_{}.toml", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = fs::remove_file(path);
+        let mut submissions = Submissions::load(path).unwrap();
+        let rejected = |answer: &str, hint| Rejected { day: 5, part: Part::Two, answer: String::from(answer), hint };
+        submissions.record(path, rejected("123", Some(Hint::TooLow))).unwrap();
+        submissions.record(path, rejected("456", None)).unwrap();
+        assert!(submissions.rejection(5, Part::Two, "120").is_some());
+
+        let content = fs::read_to_string(path).unwrap();
+        assert!(content.starts_with(HEADER));
+        assert_eq!(Submissions::parse(&content).unwrap().wrong, [rejected("123", Some(Hint::TooLow)), rejected("456", None)]);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,159 @@
+//! Solving several days at once on a small pool of threads, and the table summarizing the results.
+
+use std::{
+    fmt::Write,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::Duration,
+};
+
+use aoc2024::solution::Part;
+
+use crate::{answers::Verdict, bench::format_duration};
+
+#[derive(Debug)]
+pub struct PartSummary {
+    pub part: Part,
+    pub answer: String,
+    /// How the answer compares to the recorded one, if the run is verified at all.
+    pub verdict: Option<Verdict>,
+    pub time: Duration,
+}
+
+#[derive(Debug)]
+pub struct DaySummary {
+    pub day: u8,
+    /// The parse time and the solved parts, or why the day couldn't be solved.
+    pub outcome: Result<(Duration, Vec<PartSummary>), String>,
+}
+
+impl DaySummary {
+    pub fn failed(&self) -> bool {
+        match &self.outcome {
+            Ok((_, parts)) => parts.iter().any(|part| matches!(part.verdict, Some(Verdict::Wrong(_)))),
+            Err(_) => true,
+        }
+    }
+}
+
+/// Applies `f` to every item on up to `jobs` threads, which take the next item as soon as they
+/// are done with one. The results keep the order of the items.
+pub fn parallel_map<T: Sync, R: Send>(items: &[T], jobs: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, R)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs.clamp(1, items.len().max(1)))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = vec![];
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            return done;
+                        };
+                        done.push((index, f(item)));
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+            .collect()
+    });
+    results.sort_by_key(|&(index, _)| index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+fn status(parts: &[PartSummary]) -> String {
+    let verdicts: Vec<String> = parts
+        .iter()
+        .filter_map(|part| part.verdict.as_ref())
+        .map(|verdict| verdict.to_string())
+        .collect();
+    if verdicts.is_empty() {
+        String::from("-")
+    } else {
+        verdicts.join(" / ")
+    }
+}
+
+pub fn to_table(days: &[DaySummary]) -> String {
+    let mut out = String::from("| Day | Part 1 | Part 2 | Status | Parse | Part 1 time | Part 2 time |\n");
+    out.push_str("|----:|---|---|---|---:|---:|---:|\n");
+    for summary in days {
+        let (parse, parts) = match &summary.outcome {
+            Ok(outcome) => outcome,
+            Err(_) => {
+                writeln!(out, "| {:02} | - | - | ERROR | - | - | - |", summary.day).unwrap();
+                continue;
+            }
+        };
+        let find = |part| parts.iter().find(|summary: &&PartSummary| summary.part == part);
+        let answer = |part| find(part).map_or(String::from("-"), |summary| summary.answer.clone());
+        let time = |part| find(part).map_or(String::from("-"), |summary| format_duration(summary.time));
+        writeln!(
+            out,
+            "| {:02} | {} | {} | {} | {} | {} | {} |",
+            summary.day,
+            answer(Part::One),
+            answer(Part::Two),
+            status(parts),
+            format_duration(*parse),
+            time(Part::One),
+            time(Part::Two)
+        )
+        .unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, sync::Mutex};
+
+    use super::*;
+
+    #[test]
+    fn test_parallel_map_keeps_order() {
+        let items: Vec<u64> = (0..50).collect();
+        let threads = Mutex::new(HashSet::new());
+        let squares = parallel_map(&items, 4, |&item| {
+            threads.lock().unwrap().insert(thread::current().id());
+            thread::sleep(Duration::from_millis(50 - item));
+            item * item
+        });
+        assert_eq!(squares, items.iter().map(|item| item * item).collect::<Vec<_>>());
+        assert!(threads.lock().unwrap().len() > 1);
+        assert_eq!(parallel_map(&[] as &[u8], 4, |&item| item), vec![]);
+    }
+
+    #[test]
+    fn test_to_table() {
+        let part = |part, answer: &str, verdict| PartSummary {
+            part,
+            answer: String::from(answer),
+            verdict,
+            time: Duration::from_micros(1_500),
+        };
+        let days = [
+            DaySummary {
+                day: 1,
+                outcome: Ok((Duration::from_nanos(800), vec![
+                    part(Part::One, "11", Some(Verdict::Ok)),
+                    part(Part::Two, "30", Some(Verdict::Wrong(String::from("31")))),
+                ])),
+            },
+            DaySummary { day: 2, outcome: Ok((Duration::from_nanos(800), vec![part(Part::Two, "4", None)])) },
+            DaySummary { day: 3, outcome: Err(String::from("failed to parse the input of day 03")) },
+        ];
+        assert_eq!(
+            to_table(&days).lines().skip(2).collect::<Vec<_>>(),
+            [
+                "| 01 | 11 | 30 | OK / WRONG, expected 31 | 800ns | 1.50ms | 1.50ms |",
+                "| 02 | - | 4 | - | 800ns | - | 1.50ms |",
+                "| 03 | - | - | ERROR | - | - | - |",
+            ]
+        );
+        assert_eq!(days.iter().map(DaySummary::failed).collect::<Vec<_>>(), [true, false, true]);
+    }
+}
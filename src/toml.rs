@@ -0,0 +1,118 @@
+//! Just enough TOML for the data files in this repo: `[table]` and `[[table]]` headers,
+//! `key = value` pairs (dotted keys are kept as-is), integers, basic strings and `#` comments.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Integer(i64),
+    String(String),
+}
+
+impl Value {
+    /// Renders the value the way a puzzle answer or parameter is printed.
+    pub fn as_text(&self) -> String {
+        match self {
+            Value::Integer(num) => num.to_string(),
+            Value::String(str) => str.clone(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Table {
+    pub name: String,
+    pub line: usize,
+    pub entries: Vec<(String, Value)>,
+}
+
+impl Table {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries
+            .iter()
+            .find(|(entry_key, _)| entry_key == key)
+            .map(|(_, value)| value)
+    }
+}
+
+/// Parses `content` into its tables in file order; keys before the first header end up in a table named "".
+pub fn parse(content: &str) -> Result<Vec<Table>, String> {
+    let mut tables = vec![Table { name: String::new(), line: 0, entries: vec![] }];
+    for (index, line) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let header = line
+            .strip_prefix("[[")
+            .and_then(|rest| rest.strip_suffix("]]"))
+            .or_else(|| line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')));
+        if let Some(name) = header {
+            tables.push(Table { name: String::from(name.trim()), line: line_no, entries: vec![] });
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or(format!("line {}: expected `key = value`", line_no))?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(format!("line {}: missing key", line_no));
+        }
+        let value = parse_value(value.trim()).ok_or(format!("line {}: invalid value {}", line_no, value.trim()))?;
+        tables
+            .last_mut()
+            .expect("Expecting the root table")
+            .entries
+            .push((String::from(key), value));
+    }
+    if tables[0].entries.is_empty() {
+        tables.remove(0);
+    }
+    Ok(tables)
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (index, char) in line.char_indices() {
+        match char {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(value: &str) -> Option<Value> {
+    if let Some(quoted) = value.strip_prefix('"').and_then(|rest| rest.strip_suffix('"')) {
+        (!quoted.contains('"')).then(|| Value::String(String::from(quoted)))
+    } else {
+        value.replace('_', "").parse().ok().map(Value::Integer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tables() {
+        let tables = parse("top = 1\n\n[[example]] # first\nday = 3\nfile = \"day_03.txt\"\nparams.size = 6\n[[example]]\nanswer = \"a#b\"\n").unwrap();
+        assert_eq!(tables.len(), 3);
+        assert_eq!(tables[0].get("top"), Some(&Value::Integer(1)));
+        assert_eq!(tables[1].name, "example");
+        assert_eq!(tables[1].line, 3);
+        assert_eq!(tables[1].get("file"), Some(&Value::String(String::from("day_03.txt"))));
+        assert_eq!(tables[1].get("params.size").map(Value::as_text), Some(String::from("6")));
+        assert_eq!(tables[2].get("answer").map(Value::as_text), Some(String::from("a#b")));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("key").is_err());
+        assert!(parse("= 1").is_err());
+        assert!(parse("key = abc").is_err());
+        assert!(parse("key = \"open").is_err());
+    }
+}
@@ -0,0 +1,272 @@
+//! Terminal animation for the grid days: each frame is a grid drawn through a per-cell style,
+//! cropped to a viewport that follows a focus point, with a pause between frames.
+//! The same frames can be recorded as images and exported through [`crate::image::Export`].
+
+use std::{
+    io::{self, Write},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    grid::{Grid, Point},
+    image::{Export, Image, Rgb},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Color {
+    Plain,
+    Gray,
+    Red,
+    Green,
+    Yellow,
+    Magenta,
+    /// Any other color, for terminals with 24 bit color support.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    fn ansi(self) -> String {
+        let code = match self {
+            Color::Plain => "0",
+            Color::Gray => "90",
+            Color::Red => "91",
+            Color::Green => "92",
+            Color::Yellow => "93",
+            Color::Magenta => "95",
+            Color::Rgb(r, g, b) => return format!("\x1b[38;2;{};{};{}m", r, g, b),
+        };
+        format!("\x1b[{}m", code)
+    }
+
+    /// The color of a cell in an exported image; plain cells are the dark background.
+    pub fn rgb(self) -> Rgb {
+        match self {
+            Color::Plain => Rgb(24, 24, 24),
+            Color::Gray => Rgb(128, 128, 128),
+            Color::Red => Rgb(230, 60, 60),
+            Color::Green => Rgb(80, 200, 80),
+            Color::Yellow => Rgb(230, 200, 60),
+            Color::Magenta => Rgb(200, 80, 200),
+            Color::Rgb(r, g, b) => Rgb(r, g, b),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Settings {
+    /// Frames per second; 0 draws as fast as the terminal keeps up.
+    pub fps: u32,
+    pub width: usize,
+    pub height: usize,
+    pub color: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            fps: 20,
+            width: 100,
+            height: 50,
+            color: true,
+        }
+    }
+}
+
+/// Picks the top left corner of a `size` long window over `0..total` that keeps `focus` as central as possible.
+fn window_start(total: usize, size: usize, focus: isize) -> usize {
+    if total <= size {
+        return 0;
+    }
+    let centered = focus.max(0) as usize;
+    centered.saturating_sub(size / 2).min(total - size)
+}
+
+/// Draws the part of `grid` that fits into the viewport, centered on `focus` if it has to be cropped.
+pub fn render_frame<T>(
+    grid: &Grid<T>,
+    settings: &Settings,
+    focus: Option<Point>,
+    cell: impl Fn(Point, &T) -> (char, Color),
+) -> String {
+    let focus = focus.unwrap_or_default();
+    let left = window_start(grid.width(), settings.width, focus.0);
+    let top = window_start(grid.height(), settings.height, focus.1);
+    let mut out = String::new();
+    for y in top..(top + settings.height).min(grid.height()) {
+        let mut current = Color::Plain;
+        for x in left..(left + settings.width).min(grid.width()) {
+            let point = Point(x as isize, y as isize);
+            let (char, color) = cell(point, &grid[point]);
+            if settings.color && color != current {
+                out.push_str(&color.ansi());
+                current = color;
+            }
+            out.push(char);
+        }
+        if current != Color::Plain {
+            out.push_str(&Color::Plain.ansi());
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Plays frames on the terminal. Drawing starts on a cleared screen and every frame overwrites the last one.
+/// A renderer can also export its frames as images, with or without showing them.
+pub struct Renderer {
+    settings: Settings,
+    out: Option<Box<dyn Write>>,
+    /// Pixels per cell and where the frames go, if recording.
+    recording: Option<(usize, Export)>,
+    frames: usize,
+}
+
+impl Renderer {
+    pub fn new(settings: Settings) -> Renderer {
+        Renderer::with_output(settings, Box::new(io::stdout()))
+    }
+
+    pub fn with_output(settings: Settings, out: Box<dyn Write>) -> Renderer {
+        Renderer {
+            settings,
+            out: Some(out),
+            recording: None,
+            frames: 0,
+        }
+    }
+
+    /// A renderer that draws nothing and doesn't wait, for recording only.
+    pub fn hidden() -> Renderer {
+        Renderer {
+            settings: Settings::default(),
+            out: None,
+            recording: None,
+            frames: 0,
+        }
+    }
+
+    /// Passes every following frame to `export` as an image with `scale` by `scale` pixels per cell.
+    pub fn record(mut self, scale: usize, export: Export) -> Renderer {
+        self.recording = Some((scale, export));
+        self
+    }
+
+    pub fn take_export(&mut self) -> Option<Export> {
+        self.recording.take().map(|(_, export)| export)
+    }
+
+    /// Draws one frame with `caption` below the grid, then waits for the next one.
+    pub fn frame<T>(&mut self, grid: &Grid<T>, focus: Option<Point>, caption: &str, cell: impl Fn(Point, &T) -> (char, Color)) {
+        if let Some((scale, export)) = &mut self.recording {
+            export.push(|| Image::from_grid(grid, *scale, |point, value| cell(point, value).1.rgb()));
+        }
+        let Some(out) = &mut self.out else {
+            self.frames += 1;
+            return;
+        };
+        let mut screen = String::new();
+        if self.settings.color {
+            // first frame clears the screen and hides the cursor, later ones just jump back to the top
+            screen.push_str(if self.frames == 0 { "\x1b[2J\x1b[?25l\x1b[H" } else { "\x1b[H" });
+        }
+        screen.push_str(&render_frame(grid, &self.settings, focus, cell));
+        screen.push_str(caption);
+        if self.settings.color {
+            // clear whatever a longer caption left behind
+            screen.push_str("\x1b[K");
+        }
+        screen.push('\n');
+        // a broken pipe only means nobody is watching anymore
+        let _ = out.write_all(screen.as_bytes()).and_then(|_| out.flush());
+        self.frames += 1;
+        if self.settings.fps > 0 {
+            thread::sleep(Duration::from_secs(1) / self.settings.fps);
+        }
+    }
+}
+
+impl Drop for Renderer {
+    fn drop(&mut self) {
+        if let Some(out) = &mut self.out {
+            if self.settings.color && self.frames > 0 {
+                let _ = out.write_all(b"\x1b[?25h").and_then(|_| out.flush());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{rc::Rc, cell::RefCell};
+
+    use super::*;
+    use crate::parse::Source;
+
+    fn plain(width: usize, height: usize) -> Settings {
+        Settings { fps: 0, width, height, color: false }
+    }
+
+    #[test]
+    fn test_render_viewport() {
+        let source = Source::new("grid.txt", "abcd\nefgh\nijkl\nmnop");
+        let grid = Grid::parse(&source, &source.text).unwrap();
+        let cell = |_, &char: &char| (char, Color::Plain);
+        assert_eq!(render_frame(&grid, &plain(10, 10), None, cell), "abcd\nefgh\nijkl\nmnop\n");
+        assert_eq!(render_frame(&grid, &plain(2, 2), None, cell), "ab\nef\n");
+        assert_eq!(render_frame(&grid, &plain(2, 2), Some(Point(2, 2)), cell), "fg\njk\n");
+        assert_eq!(render_frame(&grid, &plain(2, 2), Some(Point(3, 3)), cell), "kl\nop\n");
+        assert_eq!(render_frame(&grid, &plain(3, 1), Some(Point(-5, 9)), cell), "mno\n");
+    }
+
+    #[test]
+    fn test_render_colors() {
+        let source = Source::new("grid.txt", "#.#");
+        let grid = Grid::parse(&source, &source.text).unwrap();
+        let settings = Settings { color: true, ..plain(3, 1) };
+        let frame = render_frame(&grid, &settings, None, |_, &char| (char, if char == '#' { Color::Gray } else { Color::Plain }));
+        assert_eq!(frame, "\x1b[90m#\x1b[0m.\x1b[90m#\x1b[0m\n");
+    }
+
+    #[derive(Clone, Default)]
+    struct Captured(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_renderer_frames() {
+        let captured = Captured::default();
+        let grid = Grid::new(2, 1, '.');
+        let mut renderer = Renderer::with_output(plain(5, 5), Box::new(captured.clone()));
+        renderer.frame(&grid, None, "step 1", |_, &char| (char, Color::Plain));
+        renderer.frame(&grid, None, "step 2", |_, &char| (char, Color::Plain));
+        assert_eq!(renderer.frames, 2);
+        let output = String::from_utf8(captured.0.borrow().clone()).unwrap();
+        assert_eq!(output, "..\nstep 1\n..\nstep 2\n");
+    }
+
+    #[test]
+    fn test_renderer_recording() {
+        let grid = Grid::new(2, 1, '.');
+        let path = std::env::temp_dir().join(format!("aoc2024_recording_{}.ppm", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut renderer = Renderer::hidden().record(1, Export::new(path, Some(2), 0).unwrap());
+        renderer.frame(&grid, None, "step 1", |_, _| ('.', Color::Plain));
+        renderer.frame(&grid, None, "step 2", |point, _| ('#', if point.0 == 0 { Color::Red } else { Color::Rgb(1, 2, 3) }));
+        renderer.frame(&grid, None, "step 3", |_, _| ('.', Color::Plain));
+        assert_eq!(renderer.take_export().unwrap().finish(), Ok(1));
+        let Rgb(r, g, b) = Color::Red.rgb();
+        let mut expected = b"P6\n2 1\n255\n".to_vec();
+        expected.extend([r, g, b, 1, 2, 3]);
+        assert_eq!(std::fs::read(path).unwrap(), expected);
+        std::fs::remove_file(path).unwrap();
+    }
+}
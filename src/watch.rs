@@ -0,0 +1,148 @@
+//! Re-solving a day whenever one of its files changes. There's no file system notification in
+//! std, so the [`Watcher`] polls modification times, which is plenty for a handful of files.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use aoc2024::solution::Part;
+
+use crate::{bench::format_duration, summary::PartSummary};
+
+/// What a file looked like when it was last seen; `None` if it didn't exist.
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Remembers the files to watch as they are now, so later changes can be spotted.
+pub struct Watcher {
+    files: Vec<(PathBuf, Stamp)>,
+}
+
+impl Watcher {
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Watcher {
+        Watcher {
+            files: paths.into_iter().map(|path| {
+                let stamp = stamp(&path);
+                (path, stamp)
+            }).collect(),
+        }
+    }
+
+    /// The files that were modified, created or deleted since the last call, or since [`Watcher::new`].
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        self.files
+            .iter_mut()
+            .filter_map(|(path, last)| {
+                let now = stamp(path);
+                (now != *last).then(|| {
+                    *last = now;
+                    path.clone()
+                })
+            })
+            .collect()
+    }
+
+    /// Checks every `interval` until something changed.
+    pub fn wait(&mut self, interval: Duration) -> Vec<PathBuf> {
+        loop {
+            let changed = self.changed();
+            if !changed.is_empty() {
+                return changed;
+            }
+            std::thread::sleep(interval);
+        }
+    }
+}
+
+/// How much faster or slower `after` is than `before`, like `-25%`.
+fn timing_change(before: Duration, after: Duration) -> String {
+    if before.is_zero() {
+        return String::from("n/a");
+    }
+    let change = (after.as_secs_f64() / before.as_secs_f64() - 1.0) * 100.0;
+    format!("{:+.0}%", change)
+}
+
+/// The result line of a part, with what changed since the `previous` round.
+pub fn describe(previous: Option<&PartSummary>, current: &PartSummary) -> String {
+    let mut line = format!("Puzzle {} result: {}", current.part, current.answer);
+    if let Some(verdict) = &current.verdict {
+        line.push_str(&format!(" [{}]", verdict));
+    }
+    line.push_str(&format!(" in {}", format_duration(current.time)));
+    if let Some(previous) = previous {
+        let answer = if previous.answer == current.answer {
+            String::from("unchanged")
+        } else {
+            format!("was {}", previous.answer)
+        };
+        line.push_str(&format!(
+            " ({}, {} vs {})",
+            answer,
+            timing_change(previous.time, current.time),
+            format_duration(previous.time)
+        ));
+    }
+    line
+}
+
+/// The summary of `part` in the last round, if it was solved.
+pub fn find(parts: &[PartSummary], part: Part) -> Option<&PartSummary> {
+    parts.iter().find(|summary| summary.part == part)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+    use crate::answers::Verdict;
+
+    #[test]
+    fn test_watcher() {
+        let root = std::env::temp_dir().join(format!("aoc2024_watch_{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let (input, answers) = (root.join("day_05.txt"), root.join("answers.toml"));
+        fs::write(&input, "1|2\n").unwrap();
+        let _ = fs::remove_file(&answers);
+
+        let mut watcher = Watcher::new([input.clone(), answers.clone()]);
+        assert_eq!(watcher.changed(), Vec::<PathBuf>::new());
+        // set the time explicitly, as some file systems only keep whole seconds
+        let file = File::options().write(true).open(&input).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10)).unwrap();
+        fs::write(&answers, "[day_05]\n").unwrap();
+        assert_eq!(watcher.changed(), [input.clone(), answers.clone()]);
+        assert_eq!(watcher.changed(), Vec::<PathBuf>::new());
+        fs::remove_file(&answers).unwrap();
+        assert_eq!(watcher.wait(Duration::from_millis(1)), [answers]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_describe() {
+        let summary = |answer: &str, verdict, millis| PartSummary {
+            part: Part::One,
+            answer: String::from(answer),
+            verdict,
+            time: Duration::from_millis(millis),
+        };
+        assert_eq!(describe(None, &summary("143", None, 2)), "Puzzle 1 result: 143 in 2.00ms");
+        assert_eq!(
+            describe(Some(&summary("143", None, 4)), &summary("143", Some(Verdict::Ok), 3)),
+            "Puzzle 1 result: 143 [OK] in 3.00ms (unchanged, -25% vs 4.00ms)"
+        );
+        assert_eq!(
+            describe(Some(&summary("143", None, 2)), &summary("150", None, 3)),
+            "Puzzle 1 result: 150 in 3.00ms (was 143, +50% vs 2.00ms)"
+        );
+        assert_eq!(timing_change(Duration::ZERO, Duration::from_millis(1)), "n/a");
+    }
+}
@@ -0,0 +1,28 @@
+use aoc2024::{
+    day_01::Day01,
+    day_13::Day13,
+    parse::Source,
+    solution::{self, Part, Solution},
+};
+
+#[test]
+fn test_solve_from_memory() {
+    let source = Source::new("inline", "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n");
+    let lists = Day01::parse(&source, &Day01::params()).unwrap();
+    assert_eq!(Day01::part1(&lists), 11);
+    assert_eq!(Day01::part2(&lists), 31);
+}
+
+#[test]
+fn test_parse_error_names_the_source() {
+    let source = Source::new("inline", "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400\n");
+    let err = Day13::parse(&source, &Day13::params()).unwrap_err();
+    assert_eq!((err.file.as_str(), err.line), ("inline", 3));
+}
+
+#[test]
+fn test_registry_solves_loaded_input() {
+    let entry = solution::find(13).unwrap();
+    let input = entry.solution.parse(&Source::read(&entry.example_file()).unwrap(), &entry.solution.params()).unwrap();
+    assert_eq!(entry.solve(input.as_ref(), Part::One), "480");
+}